@@ -0,0 +1,68 @@
+use alt::fs::acl::{self, Tag};
+use alt::io;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use tlpi::{group_name_from_id, usage_err, user_name_from_id};
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+
+    let mut default_acl = false;
+    let mut optind = 1;
+    while let Some(opt) = argv.get(optind).filter(|arg| arg.starts_with('-')) {
+        match opt.as_str() {
+            "-d" => default_acl = true,
+            _ => usage_err!("{} [-d] filename\n", argv[0]),
+        }
+        optind += 1;
+    }
+
+    if optind + 1 != argv.len() {
+        usage_err!("{} [-d] filename\n", argv[0]);
+    }
+    let file = &argv[optind];
+
+    let acl = if default_acl {
+        acl::get_default(file)
+            .context("acl_get_file")?
+            .unwrap_or_default()
+    } else {
+        acl::get(file).context("acl_get_file")?
+    };
+
+    /* Walk through each entry in this ACL */
+
+    let mut out = io::stdout();
+    for entry in acl.entries() {
+        /* Retrieve and display tag type and optional tag qualifier */
+
+        let (tag, qualifier) = match entry.tag {
+            Tag::UserObj => ("user_obj", String::new()),
+            Tag::User(uid) => (
+                "user",
+                user_name_from_id(uid).unwrap_or_else(|| uid.to_string()),
+            ),
+            Tag::GroupObj => ("group_obj", String::new()),
+            Tag::Group(gid) => (
+                "group",
+                group_name_from_id(gid).unwrap_or_else(|| gid.to_string()),
+            ),
+            Tag::Mask => ("mask", String::new()),
+            Tag::Other => ("other", String::new()),
+        };
+        write!(out, "{:<12}{:<8} ", tag, qualifier)?;
+
+        /* Retrieve and display permissions, and for group class entries
+         * limited by the mask, the permissions actually granted */
+
+        write!(out, "{}", entry.perms)?;
+        let effective = acl.effective(entry);
+        if effective != entry.perms {
+            write!(out, "      #effective:{}", effective)?;
+        }
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
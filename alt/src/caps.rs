@@ -0,0 +1,793 @@
+use crate::fs::xattr::{self, SetFlags};
+use crate::process::credentials;
+use libc::{c_int, c_ulong};
+use std::fmt;
+use std::io;
+use std::ops::BitOr;
+use std::path::Path;
+use std::str::FromStr;
+
+const CAPABILITY_XATTR: &str = "security.capability";
+
+/// A capability, numbered as in linux/capability.h.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Capability {
+    Chown = libc::CAP_CHOWN as u8,
+    DacOverride = libc::CAP_DAC_OVERRIDE as u8,
+    DacReadSearch = libc::CAP_DAC_READ_SEARCH as u8,
+    Fowner = libc::CAP_FOWNER as u8,
+    Fsetid = libc::CAP_FSETID as u8,
+    Kill = libc::CAP_KILL as u8,
+    Setgid = libc::CAP_SETGID as u8,
+    Setuid = libc::CAP_SETUID as u8,
+    Setpcap = libc::CAP_SETPCAP as u8,
+    LinuxImmutable = libc::CAP_LINUX_IMMUTABLE as u8,
+    NetBindService = libc::CAP_NET_BIND_SERVICE as u8,
+    NetBroadcast = libc::CAP_NET_BROADCAST as u8,
+    NetAdmin = libc::CAP_NET_ADMIN as u8,
+    NetRaw = libc::CAP_NET_RAW as u8,
+    IpcLock = libc::CAP_IPC_LOCK as u8,
+    IpcOwner = libc::CAP_IPC_OWNER as u8,
+    SysModule = libc::CAP_SYS_MODULE as u8,
+    SysRawio = libc::CAP_SYS_RAWIO as u8,
+    SysChroot = libc::CAP_SYS_CHROOT as u8,
+    SysPtrace = libc::CAP_SYS_PTRACE as u8,
+    SysPacct = libc::CAP_SYS_PACCT as u8,
+    SysAdmin = libc::CAP_SYS_ADMIN as u8,
+    SysBoot = libc::CAP_SYS_BOOT as u8,
+    SysNice = libc::CAP_SYS_NICE as u8,
+    SysResource = libc::CAP_SYS_RESOURCE as u8,
+    SysTime = libc::CAP_SYS_TIME as u8,
+    SysTtyConfig = libc::CAP_SYS_TTY_CONFIG as u8,
+    Mknod = libc::CAP_MKNOD as u8,
+    Lease = libc::CAP_LEASE as u8,
+    AuditWrite = libc::CAP_AUDIT_WRITE as u8,
+    AuditControl = libc::CAP_AUDIT_CONTROL as u8,
+    Setfcap = libc::CAP_SETFCAP as u8,
+    MacOverride = libc::CAP_MAC_OVERRIDE as u8,
+    MacAdmin = libc::CAP_MAC_ADMIN as u8,
+    Syslog = libc::CAP_SYSLOG as u8,
+    WakeAlarm = libc::CAP_WAKE_ALARM as u8,
+    BlockSuspend = libc::CAP_BLOCK_SUSPEND as u8,
+    AuditRead = libc::CAP_AUDIT_READ as u8,
+    Perfmon = libc::CAP_PERFMON as u8,
+    Bpf = libc::CAP_BPF as u8,
+    CheckpointRestore = libc::CAP_CHECKPOINT_RESTORE as u8,
+}
+
+const ALL: [Capability; libc::CAP_LAST_CAP as usize + 1] = [
+    Capability::Chown,
+    Capability::DacOverride,
+    Capability::DacReadSearch,
+    Capability::Fowner,
+    Capability::Fsetid,
+    Capability::Kill,
+    Capability::Setgid,
+    Capability::Setuid,
+    Capability::Setpcap,
+    Capability::LinuxImmutable,
+    Capability::NetBindService,
+    Capability::NetBroadcast,
+    Capability::NetAdmin,
+    Capability::NetRaw,
+    Capability::IpcLock,
+    Capability::IpcOwner,
+    Capability::SysModule,
+    Capability::SysRawio,
+    Capability::SysChroot,
+    Capability::SysPtrace,
+    Capability::SysPacct,
+    Capability::SysAdmin,
+    Capability::SysBoot,
+    Capability::SysNice,
+    Capability::SysResource,
+    Capability::SysTime,
+    Capability::SysTtyConfig,
+    Capability::Mknod,
+    Capability::Lease,
+    Capability::AuditWrite,
+    Capability::AuditControl,
+    Capability::Setfcap,
+    Capability::MacOverride,
+    Capability::MacAdmin,
+    Capability::Syslog,
+    Capability::WakeAlarm,
+    Capability::BlockSuspend,
+    Capability::AuditRead,
+    Capability::Perfmon,
+    Capability::Bpf,
+    Capability::CheckpointRestore,
+];
+
+impl Capability {
+    pub fn all() -> impl Iterator<Item = Capability> {
+        ALL.iter().copied()
+    }
+
+    pub fn from_number(n: u32) -> Option<Capability> {
+        ALL.get(n as usize).copied()
+    }
+
+    pub fn number(self) -> u32 {
+        self as u32
+    }
+
+    /// The name used by libcap and capabilities(7), e.g.
+    /// "cap_net_bind_service".
+    pub fn name(self) -> &'static str {
+        match self {
+            Capability::Chown => "cap_chown",
+            Capability::DacOverride => "cap_dac_override",
+            Capability::DacReadSearch => "cap_dac_read_search",
+            Capability::Fowner => "cap_fowner",
+            Capability::Fsetid => "cap_fsetid",
+            Capability::Kill => "cap_kill",
+            Capability::Setgid => "cap_setgid",
+            Capability::Setuid => "cap_setuid",
+            Capability::Setpcap => "cap_setpcap",
+            Capability::LinuxImmutable => "cap_linux_immutable",
+            Capability::NetBindService => "cap_net_bind_service",
+            Capability::NetBroadcast => "cap_net_broadcast",
+            Capability::NetAdmin => "cap_net_admin",
+            Capability::NetRaw => "cap_net_raw",
+            Capability::IpcLock => "cap_ipc_lock",
+            Capability::IpcOwner => "cap_ipc_owner",
+            Capability::SysModule => "cap_sys_module",
+            Capability::SysRawio => "cap_sys_rawio",
+            Capability::SysChroot => "cap_sys_chroot",
+            Capability::SysPtrace => "cap_sys_ptrace",
+            Capability::SysPacct => "cap_sys_pacct",
+            Capability::SysAdmin => "cap_sys_admin",
+            Capability::SysBoot => "cap_sys_boot",
+            Capability::SysNice => "cap_sys_nice",
+            Capability::SysResource => "cap_sys_resource",
+            Capability::SysTime => "cap_sys_time",
+            Capability::SysTtyConfig => "cap_sys_tty_config",
+            Capability::Mknod => "cap_mknod",
+            Capability::Lease => "cap_lease",
+            Capability::AuditWrite => "cap_audit_write",
+            Capability::AuditControl => "cap_audit_control",
+            Capability::Setfcap => "cap_setfcap",
+            Capability::MacOverride => "cap_mac_override",
+            Capability::MacAdmin => "cap_mac_admin",
+            Capability::Syslog => "cap_syslog",
+            Capability::WakeAlarm => "cap_wake_alarm",
+            Capability::BlockSuspend => "cap_block_suspend",
+            Capability::AuditRead => "cap_audit_read",
+            Capability::Perfmon => "cap_perfmon",
+            Capability::Bpf => "cap_bpf",
+            Capability::CheckpointRestore => "cap_checkpoint_restore",
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Parses a name in any case, with or without the "cap_" prefix.
+impl FromStr for Capability {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Capability> {
+        let s = s.to_ascii_lowercase();
+        let name = if s.starts_with("cap_") {
+            s
+        } else {
+            format!("cap_{}", s)
+        };
+        Capability::all()
+            .find(|cap| cap.name() == name)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "unknown capability"))
+    }
+}
+
+/// A set of capabilities, as a bit mask indexed by capability number.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CapSet(u64);
+
+impl CapSet {
+    pub const fn empty() -> CapSet {
+        CapSet(0)
+    }
+
+    /// Every capability known to this library. The kernel may support
+    /// fewer or more.
+    pub const fn all() -> CapSet {
+        CapSet((1 << (libc::CAP_LAST_CAP + 1)) - 1)
+    }
+
+    pub const fn from_bits(bits: u64) -> CapSet {
+        CapSet(bits)
+    }
+
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn contains(self, cap: Capability) -> bool {
+        self.0 & (1 << cap as u8) != 0
+    }
+
+    pub fn insert(&mut self, cap: Capability) {
+        self.0 |= 1 << cap as u8;
+    }
+
+    pub fn remove(&mut self, cap: Capability) {
+        self.0 &= !(1 << cap as u8);
+    }
+
+    pub const fn intersection(self, other: CapSet) -> CapSet {
+        CapSet(self.0 & other.0)
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Capability> {
+        Capability::all().filter(move |&cap| self.contains(cap))
+    }
+}
+
+impl From<Capability> for CapSet {
+    fn from(cap: Capability) -> CapSet {
+        CapSet(1 << cap as u8)
+    }
+}
+
+impl FromIterator<Capability> for CapSet {
+    fn from_iter<I: IntoIterator<Item = Capability>>(iter: I) -> CapSet {
+        let mut set = CapSet::empty();
+        for cap in iter {
+            set.insert(cap);
+        }
+        set
+    }
+}
+
+impl BitOr for CapSet {
+    type Output = CapSet;
+
+    fn bitor(self, rhs: CapSet) -> CapSet {
+        CapSet(self.0 | rhs.0)
+    }
+}
+
+/// Renders as a comma-separated list of names, e.g.
+/// "cap_chown,cap_kill", or "none" if empty.
+impl fmt::Display for CapSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.pad("none");
+        }
+        let names: Vec<&str> = self.iter().map(Capability::name).collect();
+        f.pad(&names.join(","))
+    }
+}
+
+/// The capability sets of a thread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The capabilities the kernel checks for privileged operations.
+    pub effective: CapSet,
+    /// The limit on what may be added to the effective and inheritable
+    /// sets.
+    pub permitted: CapSet,
+    /// The capabilities that may be kept across `execve()`.
+    pub inheritable: CapSet,
+}
+
+impl Capabilities {
+    /// Return the capability sets of the calling thread.
+    pub fn current() -> io::Result<Capabilities> {
+        Capabilities::of(0)
+    }
+
+    /// Return the capability sets of thread `tid` (0 for the caller).
+    pub fn of(tid: u32) -> io::Result<Capabilities> {
+        let mut header = libc::__user_cap_header_struct {
+            version: libc::_LINUX_CAPABILITY_VERSION_3,
+            pid: tid as c_int,
+        };
+        let mut data = [libc::__user_cap_data_struct {
+            effective: 0,
+            permitted: 0,
+            inheritable: 0,
+        }; libc::_LINUX_CAPABILITY_U32S_3];
+        let result = unsafe { libc::capget(&mut header, data.as_mut_ptr()) };
+        if result == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let join = |lo: u32, hi: u32| CapSet(lo as u64 | (hi as u64) << 32);
+        Ok(Capabilities {
+            effective: join(data[0].effective, data[1].effective),
+            permitted: join(data[0].permitted, data[1].permitted),
+            inheritable: join(data[0].inheritable, data[1].inheritable),
+        })
+    }
+
+    /// Replace the capability sets of the calling thread. The permitted
+    /// set may only shrink, and the effective set must be a subset of it.
+    pub fn set(&self) -> io::Result<()> {
+        let mut header = libc::__user_cap_header_struct {
+            version: libc::_LINUX_CAPABILITY_VERSION_3,
+            pid: 0,
+        };
+        let word = |i: u32| libc::__user_cap_data_struct {
+            effective: (self.effective.0 >> (32 * i)) as u32,
+            permitted: (self.permitted.0 >> (32 * i)) as u32,
+            inheritable: (self.inheritable.0 >> (32 * i)) as u32,
+        };
+        let data = [word(0), word(1)];
+        let result = unsafe { libc::capset(&mut header, data.as_ptr()) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Renders in the textual form of libcap's `cap_to_text()`, e.g. "=ep"
+/// for root, "cap_net_bind_service=ep", or "=ep cap_sys_admin-e".
+///
+/// As in libcap, the most common combination of flags is given first, for
+/// all capabilities, and the others are then raised or lowered from it.
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Flags as bits in libcap's order: effective, inheritable, permitted
+        let sets = [self.effective, self.inheritable, self.permitted];
+        let flags_of = |cap: Capability| -> usize {
+            (0..3)
+                .filter(|&i| sets[i].contains(cap))
+                .map(|i| 1 << i)
+                .sum()
+        };
+        let letters = |flags: usize| -> String {
+            (0..3)
+                .filter(|i| flags & (1 << i) != 0)
+                .map(|i| ['e', 'i', 'p'][i])
+                .collect()
+        };
+
+        let mut histogram = [0; 8];
+        for cap in Capability::all() {
+            histogram[flags_of(cap)] += 1;
+        }
+        // Ties go to the lower e/i/p bit pattern, as in libcap, so "ei" (3)
+        // wins over "p" (4)
+        let mut most = 7;
+        for flags in (0..7).rev() {
+            if histogram[flags] >= histogram[most] {
+                most = flags;
+            }
+        }
+
+        let mut text = format!("={}", letters(most));
+        for flags in (0..8).rev() {
+            if flags == most || histogram[flags] == 0 {
+                continue;
+            }
+            let names: Vec<&str> = Capability::all()
+                .filter(|&cap| flags_of(cap) == flags)
+                .map(Capability::name)
+                .collect();
+            text.push(' ');
+            text.push_str(&names.join(","));
+            let raised = flags & !most;
+            if raised != 0 {
+                // libcap shortens a leading "= cap_x+ep" to "cap_x=ep"
+                if text.starts_with("= ") {
+                    text.drain(..2);
+                    text.push('=');
+                } else {
+                    text.push('+');
+                }
+                text.push_str(&letters(raised));
+            }
+            let lowered = !flags & most;
+            if lowered != 0 {
+                text.push('-');
+                text.push_str(&letters(lowered));
+            }
+        }
+        f.pad(&text)
+    }
+}
+
+fn prctl(option: c_int, arg2: c_ulong, arg3: c_ulong) -> io::Result<c_int> {
+    let result = unsafe { libc::prctl(option, arg2, arg3, 0 as c_ulong, 0 as c_ulong) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result)
+    }
+}
+
+/// Whether `cap` is in the calling thread's ambient set, which is kept
+/// across `execve()` of programs without file capabilities.
+pub fn ambient_is_set(cap: Capability) -> io::Result<bool> {
+    prctl(
+        libc::PR_CAP_AMBIENT,
+        libc::PR_CAP_AMBIENT_IS_SET,
+        cap as c_ulong,
+    )
+    .map(|set| set == 1)
+}
+
+/// Add `cap` to the ambient set. It must already be in both the permitted
+/// and inheritable sets.
+pub fn ambient_raise(cap: Capability) -> io::Result<()> {
+    prctl(
+        libc::PR_CAP_AMBIENT,
+        libc::PR_CAP_AMBIENT_RAISE,
+        cap as c_ulong,
+    )
+    .map(drop)
+}
+
+pub fn ambient_lower(cap: Capability) -> io::Result<()> {
+    prctl(
+        libc::PR_CAP_AMBIENT,
+        libc::PR_CAP_AMBIENT_LOWER,
+        cap as c_ulong,
+    )
+    .map(drop)
+}
+
+pub fn ambient_clear_all() -> io::Result<()> {
+    prctl(libc::PR_CAP_AMBIENT, libc::PR_CAP_AMBIENT_CLEAR_ALL, 0).map(drop)
+}
+
+/// Whether `cap` is in the calling thread's bounding set, which limits the
+/// capabilities it can gain by executing a program.
+pub fn bounding_is_set(cap: Capability) -> io::Result<bool> {
+    prctl(libc::PR_CAPBSET_READ, cap as c_ulong, 0).map(|set| set == 1)
+}
+
+/// Remove `cap` from the bounding set, for good. Requires
+/// `CAP_SETPCAP`.
+pub fn bounding_drop(cap: Capability) -> io::Result<()> {
+    prctl(libc::PR_CAPBSET_DROP, cap as c_ulong, 0).map(drop)
+}
+
+/// The securebits flags, which change how the kernel grants and removes
+/// capabilities for user ID 0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SecureBits(u32);
+
+impl SecureBits {
+    /// Executing a program does not grant capabilities to user ID 0.
+    pub const NOROOT: SecureBits = SecureBits(libc::SECBIT_NOROOT);
+    pub const NOROOT_LOCKED: SecureBits = SecureBits(libc::SECBIT_NOROOT_LOCKED);
+    /// Changing user IDs to and from 0 does not adjust capabilities.
+    pub const NO_SETUID_FIXUP: SecureBits = SecureBits(libc::SECBIT_NO_SETUID_FIXUP);
+    pub const NO_SETUID_FIXUP_LOCKED: SecureBits = SecureBits(libc::SECBIT_NO_SETUID_FIXUP_LOCKED);
+    /// Keep the permitted set when all user IDs become nonzero; cleared by
+    /// `execve()`.
+    pub const KEEP_CAPS: SecureBits = SecureBits(libc::SECBIT_KEEP_CAPS);
+    pub const KEEP_CAPS_LOCKED: SecureBits = SecureBits(libc::SECBIT_KEEP_CAPS_LOCKED);
+    pub const NO_CAP_AMBIENT_RAISE: SecureBits = SecureBits(libc::SECBIT_NO_CAP_AMBIENT_RAISE);
+    pub const NO_CAP_AMBIENT_RAISE_LOCKED: SecureBits =
+        SecureBits(libc::SECBIT_NO_CAP_AMBIENT_RAISE_LOCKED);
+
+    pub const fn empty() -> SecureBits {
+        SecureBits(0)
+    }
+
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(self, other: SecureBits) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for SecureBits {
+    type Output = SecureBits;
+
+    fn bitor(self, rhs: SecureBits) -> SecureBits {
+        SecureBits(self.0 | rhs.0)
+    }
+}
+
+pub fn securebits() -> io::Result<SecureBits> {
+    prctl(libc::PR_GET_SECUREBITS, 0, 0).map(|bits| SecureBits(bits as u32))
+}
+
+/// Replace the securebits flags. Requires `CAP_SETPCAP`, and locked flags
+/// cannot be changed.
+pub fn set_securebits(bits: SecureBits) -> io::Result<()> {
+    prctl(libc::PR_SET_SECUREBITS, bits.0 as c_ulong, 0).map(drop)
+}
+
+/// Set or clear `SecureBits::KEEP_CAPS` without needing `CAP_SETPCAP`.
+pub fn set_keep_caps(keep: bool) -> io::Result<()> {
+    prctl(libc::PR_SET_KEEPCAPS, keep as c_ulong, 0).map(drop)
+}
+
+/// Switch all user and group IDs to `uid` and `gid`, clearing the
+/// supplementary groups, while keeping just the capabilities in `keep`
+/// (which must be permitted) rather than those of root.
+///
+/// Everything else is also dropped from the bounding set, so executing a
+/// program with file capabilities cannot bring it back.
+pub fn keep_only(keep: CapSet, uid: u32, gid: u32) -> io::Result<()> {
+    for cap in Capability::all().filter(|&cap| !keep.contains(cap)) {
+        match bounding_drop(cap) {
+            // Not supported by the running kernel
+            Err(err) if err.raw_os_error() == Some(libc::EINVAL) => (),
+            result => result?,
+        }
+    }
+
+    set_keep_caps(true)?;
+    credentials::setgroups(&[])?;
+    credentials::setresgid(Some(gid), Some(gid), Some(gid))?;
+    credentials::setresuid(Some(uid), Some(uid), Some(uid))?;
+    set_keep_caps(false)?;
+
+    // The user ID change emptied the effective set
+    Capabilities {
+        effective: keep,
+        permitted: keep,
+        inheritable: CapSet::empty(),
+    }
+    .set()?;
+    if Capabilities::current()?.permitted != keep {
+        return Err(io::Error::from_raw_os_error(libc::EPERM));
+    }
+    Ok(())
+}
+
+/// The capabilities of a program file, from its `security.capability`
+/// extended attribute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FileCaps {
+    /// The format of the attribute: 1, which only holds capabilities 0 to
+    /// 31, 2, or 3, which adds `root_id`.
+    pub revision: u32,
+    pub permitted: CapSet,
+    pub inheritable: CapSet,
+    /// Whether the new permitted capabilities are also made effective on
+    /// `execve()`, for programs that are not capability-aware.
+    pub effective: bool,
+    /// For namespaced file capabilities (revision 3), the user ID in the
+    /// user namespace that is root for the purpose of these capabilities.
+    pub root_id: Option<u32>,
+}
+
+impl Default for FileCaps {
+    fn default() -> FileCaps {
+        FileCaps {
+            revision: 2,
+            permitted: CapSet::empty(),
+            inheritable: CapSet::empty(),
+            effective: false,
+            root_id: None,
+        }
+    }
+}
+
+impl FileCaps {
+    /// Decode the little-endian `struct vfs_ns_cap_data`: a revision and
+    /// flags word, then (permitted, inheritable) pairs of 32-bit words (one
+    /// pair in revision 1, two since), then the root ID in revision 3.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<FileCaps> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed file capabilities");
+        let word = |i: usize| -> io::Result<u32> {
+            let word = bytes.get(4 * i..4 * i + 4).ok_or_else(invalid)?;
+            Ok(u32::from_le_bytes(word.try_into().unwrap()))
+        };
+        let magic = word(0)?;
+        let (revision, words, len) = match magic & libc::VFS_CAP_REVISION_MASK {
+            libc::VFS_CAP_REVISION_1 => (1, 1, 12),
+            libc::VFS_CAP_REVISION_2 => (2, 2, 20),
+            libc::VFS_CAP_REVISION_3 => (3, 2, 24),
+            _ => return Err(invalid()),
+        };
+        if bytes.len() != len {
+            return Err(invalid());
+        }
+        let mut caps = FileCaps {
+            revision,
+            effective: magic & libc::VFS_CAP_FLAGS_EFFECTIVE != 0,
+            ..FileCaps::default()
+        };
+        for i in 0..words {
+            caps.permitted.0 |= (word(1 + 2 * i)? as u64) << (32 * i);
+            caps.inheritable.0 |= (word(2 + 2 * i)? as u64) << (32 * i);
+        }
+        if len == 24 {
+            caps.root_id = Some(word(5)?);
+        }
+        Ok(caps)
+    }
+
+    /// Encode as `revision`, except that a root ID always needs revision 3
+    /// and anything else is written as revision 2. Revision 1 drops
+    /// capabilities 32 and up.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let revision = match (self.root_id, self.revision) {
+            (Some(_), _) => libc::VFS_CAP_REVISION_3,
+            (None, 1) => libc::VFS_CAP_REVISION_1,
+            (None, _) => libc::VFS_CAP_REVISION_2,
+        };
+        let flags = if self.effective {
+            libc::VFS_CAP_FLAGS_EFFECTIVE
+        } else {
+            0
+        };
+        let mut words = vec![
+            revision | flags,
+            self.permitted.0 as u32,
+            self.inheritable.0 as u32,
+        ];
+        if revision != libc::VFS_CAP_REVISION_1 {
+            words.push((self.permitted.0 >> 32) as u32);
+            words.push((self.inheritable.0 >> 32) as u32);
+        }
+        words.extend(self.root_id);
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+}
+
+/// Return the capabilities of the program `path`, or `None` if it has
+/// none.
+pub fn file_caps<P: AsRef<Path>>(path: P) -> io::Result<Option<FileCaps>> {
+    xattr::get(path, CAPABILITY_XATTR)?
+        .map(|bytes| FileCaps::from_bytes(&bytes))
+        .transpose()
+}
+
+/// Set the capabilities of the program `path`. Requires `CAP_SETFCAP`.
+pub fn set_file_caps<P: AsRef<Path>>(path: P, caps: &FileCaps) -> io::Result<()> {
+    xattr::set(path, CAPABILITY_XATTR, &caps.to_bytes(), SetFlags::empty())
+}
+
+pub fn remove_file_caps<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    xattr::remove(path, CAPABILITY_XATTR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(caps: &[Capability]) -> CapSet {
+        caps.iter().copied().collect()
+    }
+
+    #[test]
+    fn capability_names() {
+        let cap: Capability = "cap_net_bind_service".parse().unwrap();
+        assert_eq!(cap, Capability::NetBindService);
+        assert_eq!(
+            "CAP_CHOWN".parse::<Capability>().unwrap(),
+            Capability::Chown
+        );
+        assert_eq!(
+            "sys_admin".parse::<Capability>().unwrap(),
+            Capability::SysAdmin
+        );
+        let err = "cap_flying".parse::<Capability>().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!("".parse::<Capability>().is_err());
+
+        for cap in Capability::all() {
+            assert_eq!(Capability::from_number(cap.number()), Some(cap));
+            assert_eq!(cap.to_string().parse::<Capability>().unwrap(), cap);
+        }
+        assert_eq!(Capability::from_number(libc::CAP_LAST_CAP as u32 + 1), None);
+        assert_eq!(format!("{:<10}|", Capability::Kill), "cap_kill  |");
+    }
+
+    #[test]
+    fn cap_set_display() {
+        assert_eq!(CapSet::empty().to_string(), "none");
+        assert_eq!(
+            set(&[Capability::Kill, Capability::Chown]).to_string(),
+            "cap_chown,cap_kill"
+        );
+        assert_eq!(
+            CapSet::all().iter().count(),
+            libc::CAP_LAST_CAP as usize + 1
+        );
+    }
+
+    #[test]
+    fn capabilities_display() {
+        let text = |effective: CapSet, permitted: CapSet, inheritable: CapSet| {
+            Capabilities {
+                effective,
+                permitted,
+                inheritable,
+            }
+            .to_string()
+        };
+        let none = CapSet::empty();
+        let all = CapSet::all();
+        assert_eq!(text(none, none, none), "=");
+        assert_eq!(text(all, all, none), "=ep");
+        assert_eq!(text(all, all, all), "=eip");
+
+        let bind = set(&[Capability::NetBindService]);
+        assert_eq!(text(bind, bind, none), "cap_net_bind_service=ep");
+        assert_eq!(text(none, bind, bind), "cap_net_bind_service=ip");
+
+        let chown = set(&[Capability::Chown]);
+        let chown_kill = set(&[Capability::Chown, Capability::Kill]);
+        assert_eq!(text(chown, chown_kill, none), "cap_chown=ep cap_kill+p");
+        assert_eq!(text(chown_kill, chown_kill, none), "cap_chown,cap_kill=ep");
+
+        let mut most = all;
+        most.remove(Capability::SysAdmin);
+        assert_eq!(text(most, all, none), "=ep cap_sys_admin-e");
+        assert_eq!(
+            text(most, all, bind),
+            "=ep cap_net_bind_service+i cap_sys_admin-e"
+        );
+    }
+
+    fn words(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn file_caps_revisions() {
+        let bind = set(&[Capability::NetBindService]);
+        let mac = set(&[Capability::MacAdmin]);
+
+        let v1 = words(&[0x0100_0001, 1 << 10, 1 << 5]);
+        let caps = FileCaps::from_bytes(&v1).unwrap();
+        assert_eq!(
+            caps,
+            FileCaps {
+                revision: 1,
+                permitted: bind,
+                inheritable: set(&[Capability::Kill]),
+                effective: true,
+                root_id: None,
+            }
+        );
+        assert_eq!(caps.to_bytes(), v1);
+
+        let v2 = words(&[0x0200_0000, 1 << 10, 0, 1 << 1, 0]);
+        let caps = FileCaps::from_bytes(&v2).unwrap();
+        assert_eq!((caps.revision, caps.effective), (2, false));
+        assert_eq!(caps.permitted, bind | mac);
+        assert_eq!(caps.to_bytes(), v2);
+
+        let v3 = words(&[0x0300_0001, 1 << 10, 0, 0, 0, 100000]);
+        let caps = FileCaps::from_bytes(&v3).unwrap();
+        assert_eq!((caps.revision, caps.root_id), (3, Some(100000)));
+        assert_eq!(caps.to_bytes(), v3);
+
+        let caps = FileCaps {
+            permitted: bind,
+            effective: true,
+            ..FileCaps::default()
+        };
+        assert_eq!(caps.to_bytes(), words(&[0x0200_0001, 1 << 10, 0, 0, 0]));
+    }
+
+    #[test]
+    fn file_caps_malformed() {
+        for bytes in [
+            Vec::new(),
+            vec![0, 0, 0],
+            words(&[0x0100_0000, 0]),
+            words(&[0x0100_0000, 0, 0, 0, 0]),
+            words(&[0x0200_0000, 0, 0]),
+            words(&[0x0300_0000, 0, 0, 0, 0]),
+            words(&[0x0400_0000, 0, 0, 0, 0]),
+        ] {
+            let err = FileCaps::from_bytes(&bytes).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{:?}", bytes);
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use crate::fs::OpenOptions;
+use libc::{c_char, c_int, c_ulong};
+use std::ffi::{CStr, CString, OsStr, OsString};
+use std::io::{self, Read};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::ptr;
+use std::sync::atomic::{AtomicIsize, AtomicPtr, Ordering};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::vec;
+
+/// Serializes access to `environ` by the functions of this module, since
+/// `setenv()` and friends may reallocate the array and free the strings it
+/// held while another thread is reading them.
+static ENV_LOCK: RwLock<()> = RwLock::new(());
+
+fn read_lock() -> RwLockReadGuard<'static, ()> {
+    ENV_LOCK.read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn write_lock() -> RwLockWriteGuard<'static, ()> {
+    ENV_LOCK.write().unwrap_or_else(|e| e.into_inner())
+}
+
+fn to_c_string(s: &OsStr) -> io::Result<CString> {
+    CString::new(s.as_bytes()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "environment string contained an unexpected NUL byte",
+        )
+    })
+}
+
+/// Return a copy of the value of environment variable `name`.
+pub fn getenv<K: AsRef<OsStr>>(name: K) -> Option<OsString> {
+    let name = CString::new(name.as_ref().as_bytes()).ok()?;
+    let _guard = read_lock();
+    let value = unsafe { libc::getenv(name.as_ptr()) };
+    if value.is_null() {
+        None
+    } else {
+        let value = unsafe { CStr::from_ptr(value) };
+        Some(OsString::from_vec(value.to_bytes().to_vec()))
+    }
+}
+
+/// Set environment variable `name` to `value`, unless it already exists
+/// and `overwrite` is false. Fails with `EINVAL` if `name` is empty or
+/// contains '='.
+///
+/// The functions here that change the environment exclude the readers
+/// here, but not code that calls the C library's `getenv()` directly (as
+/// glibc itself does for `TZ` and others) or uses `std::env`, which has a
+/// lock of its own. Change the environment before starting threads.
+pub fn setenv<K: AsRef<OsStr>, V: AsRef<OsStr>>(
+    name: K,
+    value: V,
+    overwrite: bool,
+) -> io::Result<()> {
+    let name = to_c_string(name.as_ref())?;
+    let value = to_c_string(value.as_ref())?;
+    let _guard = write_lock();
+    let result = unsafe { libc::setenv(name.as_ptr(), value.as_ptr(), overwrite as libc::c_int) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Remove environment variable `name`; it is not an error if it does not
+/// exist. The thread-safety caveats of `setenv` apply.
+pub fn unsetenv<K: AsRef<OsStr>>(name: K) -> io::Result<()> {
+    let name = to_c_string(name.as_ref())?;
+    let _guard = write_lock();
+    let result = unsafe { libc::unsetenv(name.as_ptr()) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Remove all environment variables, as a set-user-ID program should
+/// before building the environment it wants from scratch. The
+/// thread-safety caveats of `setenv` apply.
+pub fn clearenv() -> io::Result<()> {
+    let _guard = write_lock();
+    let result = unsafe { libc::clearenv() };
+    if result != 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// An iterator over a snapshot of the environment, as (name, value)
+/// pairs in the order of `environ`.
+pub struct Vars(vec::IntoIter<(OsString, OsString)>);
+
+impl Iterator for Vars {
+    type Item = (OsString, OsString);
+
+    fn next(&mut self) -> Option<(OsString, OsString)> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Return the current environment. Strings without a '=' (after the first
+/// byte, so that a name cannot be empty) are skipped.
+pub fn vars() -> Vars {
+    let mut vars = Vec::new();
+    let _guard = read_lock();
+    let mut entry = unsafe { libc::environ } as *const *const c_char;
+    while !entry.is_null() && !unsafe { *entry }.is_null() {
+        let bytes = unsafe { CStr::from_ptr(*entry) }.to_bytes();
+        vars.extend(split_var(bytes));
+        entry = unsafe { entry.add(1) };
+    }
+    Vars(vars.into_iter())
+}
+
+/// Split "NAME=value" at the first '=' after the first byte.
+fn split_var(bytes: &[u8]) -> Option<(OsString, OsString)> {
+    let pos = bytes.iter().skip(1).position(|&b| b == b'=')? + 1;
+    let (name, value) = (&bytes[..pos], &bytes[pos + 1..]);
+    Some((
+        OsString::from_vec(name.to_vec()),
+        OsString::from_vec(value.to_vec()),
+    ))
+}
+
+/// The command-line arguments of the process, as arbitrary byte strings.
+pub struct Args(vec::IntoIter<OsString>);
+
+impl Iterator for Args {
+    type Item = OsString;
+
+    fn next(&mut self) -> Option<OsString> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Args {}
+
+impl DoubleEndedIterator for Args {
+    fn next_back(&mut self) -> Option<OsString> {
+        self.0.next_back()
+    }
+}
+
+/// Return argv, read from `/proc/self/cmdline`; `len()` of the result is
+/// argc.
+///
+/// Unlike `std::env::args()`, this never panics on arguments that are not
+/// UTF-8. The kernel reads the arguments from our own memory, so a program
+/// that overwrites its argv strings (to change what ps(1) shows) sees the
+/// change here too.
+///
+/// If `/proc` is not mounted, as in a bare chroot or a minimal container,
+/// the argv that glibc passed to our `.init_array` function at startup is
+/// used instead.
+pub fn args() -> io::Result<Args> {
+    let mut buf = Vec::new();
+    let read = OpenOptions::new()
+        .read(true)
+        .open("/proc/self/cmdline")
+        .and_then(|mut file| file.read_to_end(&mut buf));
+    let args = match read {
+        Ok(_) => parse_cmdline(buf),
+        Err(e) => startup_args().ok_or(e)?,
+    };
+    Ok(Args(args.into_iter()))
+}
+
+/// Split the contents of `/proc/PID/cmdline`. The file is empty for kernel
+/// threads and zombies; otherwise each argument, even an empty one, is
+/// followed by a NUL.
+fn parse_cmdline(mut buf: Vec<u8>) -> Vec<OsString> {
+    if buf.is_empty() {
+        return Vec::new();
+    }
+    if buf.last() == Some(&0) {
+        buf.pop();
+    }
+    buf.split(|&b| b == 0)
+        .map(|arg| OsString::from_vec(arg.to_vec()))
+        .collect()
+}
+
+static ARGC: AtomicIsize = AtomicIsize::new(0);
+static ARGV: AtomicPtr<*const c_char> = AtomicPtr::new(ptr::null_mut());
+
+/// glibc calls the functions in `.init_array` with argc, argv and envp,
+/// before `main()` and before any thread can call `args()`. Other C
+/// libraries pass nothing, leaving argv null.
+#[used]
+#[link_section = ".init_array.00099"]
+static ARGV_INIT_ARRAY: extern "C" fn(c_int, *const *const c_char, *const *const c_char) =
+    save_args;
+
+extern "C" fn save_args(argc: c_int, argv: *const *const c_char, _envp: *const *const c_char) {
+    ARGC.store(argc as isize, Ordering::Relaxed);
+    ARGV.store(argv as *mut _, Ordering::Relaxed);
+}
+
+/// Return the argv saved at startup, if any.
+fn startup_args() -> Option<Vec<OsString>> {
+    let argv = ARGV.load(Ordering::Relaxed) as *const *const c_char;
+    if argv.is_null() {
+        return None;
+    }
+    let argc = ARGC.load(Ordering::Relaxed).max(0) as usize;
+    let args = (0..argc)
+        .map(|i| unsafe { *argv.add(i) })
+        .take_while(|arg| !arg.is_null())
+        .map(|arg| OsString::from_vec(unsafe { CStr::from_ptr(arg) }.to_bytes().to_vec()))
+        .collect();
+    Some(args)
+}
+
+/// Return an entry of the auxiliary vector that the kernel passed to the
+/// program, such as `libc::AT_PAGESZ`, or `None` if there is none.
+pub fn getauxval(key: c_ulong) -> Option<u64> {
+    unsafe {
+        *libc::__errno_location() = 0;
+    }
+    let value = unsafe { libc::getauxval(key) };
+    if value == 0 && unsafe { *libc::__errno_location() } == libc::ENOENT {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// The pathname that was passed to `execve()` to run the program
+/// (`AT_EXECFN`), which argv[0] need not match.
+pub fn exec_filename() -> Option<OsString> {
+    let ptr = getauxval(libc::AT_EXECFN)? as *const c_char;
+    if ptr.is_null() {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr(ptr) };
+    Some(OsString::from_vec(name.to_bytes().to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os(s: &str) -> OsString {
+        OsString::from(s)
+    }
+
+    #[test]
+    fn split_vars() {
+        assert_eq!(split_var(b"HOME=/root"), Some((os("HOME"), os("/root"))));
+        assert_eq!(split_var(b"EMPTY="), Some((os("EMPTY"), os(""))));
+        assert_eq!(split_var(b"A==b=c"), Some((os("A"), os("=b=c"))));
+        // A name cannot be empty, so a leading '=' is part of it
+        assert_eq!(split_var(b"=C:=C:\\"), Some((os("=C:"), os("C:\\"))));
+        assert_eq!(split_var(b"NOVALUE"), None);
+        assert_eq!(split_var(b"="), None);
+        assert_eq!(split_var(b""), None);
+        let (name, value) = split_var(b"K=\xff").unwrap();
+        assert_eq!(
+            (name.as_bytes(), value.as_bytes()),
+            (&b"K"[..], &b"\xff"[..])
+        );
+    }
+
+    #[test]
+    fn vars_include_path() {
+        assert!(vars().any(|(name, value)| name == "PATH" && Some(value) == getenv("PATH")));
+    }
+
+    #[test]
+    fn parse_args() {
+        assert_eq!(parse_cmdline(Vec::new()), Vec::<OsString>::new());
+        // Empty arguments still count towards argc
+        assert_eq!(parse_cmdline(b"\0".to_vec()), [os("")]);
+        assert_eq!(
+            parse_cmdline(b"prog\0\0x\0".to_vec()),
+            [os("prog"), os(""), os("x")]
+        );
+        assert_eq!(parse_cmdline(b"prog\0\0".to_vec()), [os("prog"), os("")]);
+        // A process may overwrite its argv, including the final NUL
+        assert_eq!(parse_cmdline(b"prog\0a".to_vec()), [os("prog"), os("a")]);
+        let args = parse_cmdline(b"\xfe\0".to_vec());
+        assert_eq!(args[0].as_bytes(), b"\xfe");
+    }
+
+    #[test]
+    fn args_match_startup_argv() {
+        let args: Vec<OsString> = args().unwrap().collect();
+        assert!(!args.is_empty());
+        assert_eq!(startup_args(), Some(args));
+    }
+}
@@ -1,6 +1,21 @@
+pub mod acl;
+mod dir;
+mod filesystem;
+mod tempfile;
+pub mod xattr;
+
+use crate::os::fd::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use crate::sys::fs as fs_imp;
+use std::ffi::OsString;
+use std::fmt;
 use std::io::{self, Read, Seek, SeekFrom, Write};
-use std::path::Path;
+use std::ops::{BitOr, Range};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub use dir::*;
+pub use filesystem::*;
+pub use tempfile::*;
 
 pub struct File {
     inner: fs_imp::File,
@@ -10,6 +25,117 @@ impl File {
     pub fn close(self) -> io::Result<()> {
         self.inner.close()
     }
+
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.inner.file_attr().map(Metadata)
+    }
+
+    /// Flush all data and metadata of the file to the storage device
+    /// (`fsync()`).
+    ///
+    /// Note that `flush()` does not do this: it only empties user-space
+    /// buffers, of which `File` has none.
+    pub fn sync_all(&self) -> io::Result<()> {
+        self.inner.fsync()
+    }
+
+    /// Like `sync_all`, but skip metadata that is not needed to read the
+    /// data back, such as timestamps (`fdatasync()`).
+    pub fn sync_data(&self) -> io::Result<()> {
+        self.inner.datasync()
+    }
+
+    /// Start and/or wait for writeback of the byte range
+    /// `offset..offset + nbytes` (`sync_file_range()`). An `nbytes` of 0
+    /// means through the end of the file.
+    ///
+    /// This neither flushes metadata nor the disk's write cache, so it gives
+    /// no durability guarantee on its own; it is useful to spread writeback
+    /// out before a final `sync_data`.
+    pub fn sync_range(&self, offset: u64, nbytes: u64, flags: SyncRangeFlags) -> io::Result<()> {
+        self.inner.sync_range(offset, nbytes, flags.bits())
+    }
+
+    /// Flush the whole filesystem containing the file (`syncfs()`).
+    pub fn syncfs(&self) -> io::Result<()> {
+        self.inner.syncfs()
+    }
+
+    /// Announce the expected access pattern for the byte range
+    /// `offset..offset + len` (`posix_fadvise()`). A `len` of 0 means
+    /// through the end of the file.
+    pub fn advise(&self, offset: u64, len: u64, advice: Advice) -> io::Result<()> {
+        let advice = match advice {
+            Advice::Normal => libc::POSIX_FADV_NORMAL,
+            Advice::Sequential => libc::POSIX_FADV_SEQUENTIAL,
+            Advice::Random => libc::POSIX_FADV_RANDOM,
+            Advice::NoReuse => libc::POSIX_FADV_NOREUSE,
+            Advice::WillNeed => libc::POSIX_FADV_WILLNEED,
+            Advice::DontNeed => libc::POSIX_FADV_DONTNEED,
+        };
+        self.inner.advise(offset, len, advice)
+    }
+
+    /// Change the permission bits (`fchmod()`).
+    pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
+        self.inner.set_permissions(&perm.0)
+    }
+
+    /// Change the last access and modification times (`futimens()`).
+    /// Times not set in `times` are left unchanged.
+    pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
+        self.inner.set_times(times.0)
+    }
+
+    pub fn set_modified(&self, time: SystemTime) -> io::Result<()> {
+        self.set_times(FileTimes::new().set_modified(time))
+    }
+
+    /// Create a new name for the open file at `path`.
+    ///
+    /// This is how a file from `tempfile()` is given a name once it is
+    /// complete. It fails with `ENOENT` if the file was unlinked, which
+    /// includes anonymous files made without `O_TMPFILE` support.
+    pub fn link_to<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs_imp::link_fd(self.as_raw_fd(), path.as_ref())
+    }
+
+    /// Truncate or extend the file to `size` bytes. Extending leaves a hole
+    /// rather than allocating blocks.
+    pub fn set_len(&self, size: u64) -> io::Result<()> {
+        self.inner.truncate(size)
+    }
+
+    /// Manipulate the disk space allocated to the byte range
+    /// `offset..offset + len` with `fallocate()`.
+    ///
+    /// With empty flags, blocks are allocated and the file is extended if
+    /// necessary; `PUNCH_HOLE` must be combined with `KEEP_SIZE`.
+    pub fn allocate(&self, offset: u64, len: u64, flags: AllocateFlags) -> io::Result<()> {
+        self.inner.allocate(flags.bits(), offset, len)
+    }
+
+    /// Move the file offset to the first byte of data at or after `offset`,
+    /// returning `None` if there is no more data in the file.
+    pub fn seek_data(&self, offset: u64) -> io::Result<Option<u64>> {
+        self.inner.seek_data(offset)
+    }
+
+    /// Move the file offset to the start of the first hole at or after
+    /// `offset`. The end of the file counts as a hole.
+    pub fn seek_hole(&self, offset: u64) -> io::Result<u64> {
+        self.inner.seek_hole(offset)
+    }
+
+    /// Iterate over the byte ranges of the file that contain data,
+    /// skipping holes. This moves the file offset.
+    pub fn data_extents(&self) -> DataExtents<'_> {
+        DataExtents {
+            file: self,
+            pos: 0,
+            done: false,
+        }
+    }
 }
 
 impl Read for File {
@@ -58,6 +184,443 @@ impl Write for &File {
     }
 }
 
+impl AsRawFd for File {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl FromRawFd for File {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        File {
+            inner: FromRawFd::from_raw_fd(fd),
+        }
+    }
+}
+
+impl IntoRawFd for File {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_raw_fd()
+    }
+}
+
+pub struct DataExtents<'a> {
+    file: &'a File,
+    pos: u64,
+    done: bool,
+}
+
+impl Iterator for DataExtents<'_> {
+    type Item = io::Result<Range<u64>>;
+
+    fn next(&mut self) -> Option<io::Result<Range<u64>>> {
+        if self.done {
+            return None;
+        }
+        let extent = self.file.seek_data(self.pos).and_then(|start| match start {
+            Some(start) => Ok(Some(start..self.file.seek_hole(start)?)),
+            None => Ok(None),
+        });
+        match extent {
+            Ok(Some(extent)) => {
+                self.pos = extent.end;
+                Some(Ok(extent))
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocateFlags(i32);
+
+impl AllocateFlags {
+    pub const KEEP_SIZE: AllocateFlags = AllocateFlags(libc::FALLOC_FL_KEEP_SIZE);
+    pub const PUNCH_HOLE: AllocateFlags = AllocateFlags(libc::FALLOC_FL_PUNCH_HOLE);
+    pub const COLLAPSE_RANGE: AllocateFlags = AllocateFlags(libc::FALLOC_FL_COLLAPSE_RANGE);
+    pub const ZERO_RANGE: AllocateFlags = AllocateFlags(libc::FALLOC_FL_ZERO_RANGE);
+    pub const INSERT_RANGE: AllocateFlags = AllocateFlags(libc::FALLOC_FL_INSERT_RANGE);
+
+    pub const fn empty() -> AllocateFlags {
+        AllocateFlags(0)
+    }
+
+    pub const fn bits(self) -> i32 {
+        self.0
+    }
+
+    pub const fn contains(self, other: AllocateFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for AllocateFlags {
+    type Output = AllocateFlags;
+
+    fn bitor(self, rhs: AllocateFlags) -> AllocateFlags {
+        AllocateFlags(self.0 | rhs.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SyncRangeFlags(u32);
+
+impl SyncRangeFlags {
+    pub const WAIT_BEFORE: SyncRangeFlags = SyncRangeFlags(libc::SYNC_FILE_RANGE_WAIT_BEFORE);
+    pub const WRITE: SyncRangeFlags = SyncRangeFlags(libc::SYNC_FILE_RANGE_WRITE);
+    pub const WAIT_AFTER: SyncRangeFlags = SyncRangeFlags(libc::SYNC_FILE_RANGE_WAIT_AFTER);
+
+    pub const fn empty() -> SyncRangeFlags {
+        SyncRangeFlags(0)
+    }
+
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(self, other: SyncRangeFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for SyncRangeFlags {
+    type Output = SyncRangeFlags;
+
+    fn bitor(self, rhs: SyncRangeFlags) -> SyncRangeFlags {
+        SyncRangeFlags(self.0 | rhs.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Advice {
+    Normal,
+    Sequential,
+    Random,
+    NoReuse,
+    WillNeed,
+    DontNeed,
+}
+
+#[derive(Clone)]
+pub struct Metadata(fs_imp::FileAttr);
+
+impl Metadata {
+    pub fn file_type(&self) -> FileType {
+        FileType(self.0.file_type())
+    }
+
+    pub fn permissions(&self) -> Permissions {
+        Permissions(self.0.perm())
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.file_type().is_dir()
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.file_type().is_file()
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.file_type().is_symlink()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.0.size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The file type and permission bits, as in `st_mode`.
+    pub fn mode(&self) -> u32 {
+        self.0.as_inner().st_mode
+    }
+
+    pub fn accessed(&self) -> SystemTime {
+        self.0.accessed()
+    }
+
+    pub fn modified(&self) -> SystemTime {
+        self.0.modified()
+    }
+
+    /// The time of the last status change (`st_ctime`), which is not a
+    /// creation time: it is updated by changes to the inode as well as to
+    /// the file contents.
+    pub fn changed(&self) -> SystemTime {
+        self.0.changed()
+    }
+
+    pub fn dev(&self) -> u64 {
+        self.0.as_inner().st_dev
+    }
+
+    pub fn ino(&self) -> u64 {
+        self.0.as_inner().st_ino
+    }
+
+    pub fn nlink(&self) -> u64 {
+        self.0.as_inner().st_nlink
+    }
+
+    pub fn uid(&self) -> u32 {
+        self.0.as_inner().st_uid
+    }
+
+    pub fn gid(&self) -> u32 {
+        self.0.as_inner().st_gid
+    }
+
+    pub fn rdev(&self) -> u64 {
+        self.0.as_inner().st_rdev
+    }
+
+    /// The preferred block size for I/O on the file.
+    pub fn blksize(&self) -> u64 {
+        self.0.as_inner().st_blksize as u64
+    }
+
+    /// The number of 512-byte blocks allocated to the file, which is less
+    /// than `len() / 512` for a sparse file.
+    pub fn blocks(&self) -> u64 {
+        self.0.as_inner().st_blocks as u64
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct FileType(fs_imp::FileType);
+
+impl FileType {
+    pub fn is_dir(&self) -> bool {
+        self.0.is_dir()
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.0.is_file()
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.0.is_symlink()
+    }
+
+    pub fn is_block_device(&self) -> bool {
+        self.0.is(libc::S_IFBLK)
+    }
+
+    pub fn is_char_device(&self) -> bool {
+        self.0.is(libc::S_IFCHR)
+    }
+
+    pub fn is_fifo(&self) -> bool {
+        self.0.is(libc::S_IFIFO)
+    }
+
+    pub fn is_socket(&self) -> bool {
+        self.0.is(libc::S_IFSOCK)
+    }
+}
+
+/// Timestamps to apply with `File::set_times` or `alt::fs::set_times`.
+///
+/// Each time can be set to a `SystemTime` (to nanosecond precision), to
+/// the current time, or left out, in which case it is not changed
+/// (`UTIME_OMIT`).
+#[derive(Clone, Copy, Default)]
+pub struct FileTimes(fs_imp::FileTimes);
+
+impl FileTimes {
+    pub fn new() -> Self {
+        FileTimes::default()
+    }
+
+    pub fn set_accessed(mut self, t: SystemTime) -> Self {
+        self.0.set_accessed(t);
+        self
+    }
+
+    /// Set the access time to the current time (`UTIME_NOW`). Unlike
+    /// passing `SystemTime::now()`, this only requires write permission
+    /// on the file, not ownership.
+    pub fn set_accessed_now(mut self) -> Self {
+        self.0.set_accessed_now();
+        self
+    }
+
+    pub fn set_modified(mut self, t: SystemTime) -> Self {
+        self.0.set_modified(t);
+        self
+    }
+
+    pub fn set_modified_now(mut self) -> Self {
+        self.0.set_modified_now();
+        self
+    }
+}
+
+/// The permission bits of a file, including the set-user-ID, set-group-ID
+/// and sticky bits.
+///
+/// `Display` renders the nine `rwx` characters as `ls -l` does, with `s`,
+/// `S`, `t` or `T` in the execute positions when the special bits are set.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Permissions(fs_imp::FilePermissions);
+
+impl Permissions {
+    /// Any file type bits in `mode` are discarded.
+    pub fn from_mode(mode: u32) -> Permissions {
+        Permissions(fs_imp::FilePermissions::from_mode(mode))
+    }
+
+    pub fn mode(&self) -> u32 {
+        self.0.mode()
+    }
+
+    pub fn set_mode(&mut self, mode: u32) {
+        self.0 = fs_imp::FilePermissions::from_mode(mode);
+    }
+
+    /// True if no class has write permission. This says nothing about
+    /// whether the caller can write the file: see `access`.
+    pub fn readonly(&self) -> bool {
+        self.0.readonly()
+    }
+
+    /// Clear or set the write bits of all three classes.
+    pub fn set_readonly(&mut self, readonly: bool) {
+        self.0.set_readonly(readonly)
+    }
+
+    pub fn is_setuid(&self) -> bool {
+        self.mode() & libc::S_ISUID != 0
+    }
+
+    pub fn is_setgid(&self) -> bool {
+        self.mode() & libc::S_ISGID != 0
+    }
+
+    pub fn is_sticky(&self) -> bool {
+        self.mode() & libc::S_ISVTX != 0
+    }
+
+    pub fn set_setuid(&mut self, setuid: bool) {
+        self.set_bit(libc::S_ISUID, setuid)
+    }
+
+    pub fn set_setgid(&mut self, setgid: bool) {
+        self.set_bit(libc::S_ISGID, setgid)
+    }
+
+    pub fn set_sticky(&mut self, sticky: bool) {
+        self.set_bit(libc::S_ISVTX, sticky)
+    }
+
+    fn set_bit(&mut self, bit: u32, on: bool) {
+        let mode = if on {
+            self.mode() | bit
+        } else {
+            self.mode() & !bit
+        };
+        self.set_mode(mode);
+    }
+}
+
+impl fmt::Display for Permissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = self.mode();
+        let bit = |mask, c| if mode & mask != 0 { c } else { '-' };
+        let exec = |mask, special, set, unset| match (mode & mask != 0, mode & special != 0) {
+            (true, true) => set,
+            (false, true) => unset,
+            (true, false) => 'x',
+            (false, false) => '-',
+        };
+        let s: String = [
+            bit(libc::S_IRUSR, 'r'),
+            bit(libc::S_IWUSR, 'w'),
+            exec(libc::S_IXUSR, libc::S_ISUID, 's', 'S'),
+            bit(libc::S_IRGRP, 'r'),
+            bit(libc::S_IWGRP, 'w'),
+            exec(libc::S_IXGRP, libc::S_ISGID, 's', 'S'),
+            bit(libc::S_IROTH, 'r'),
+            bit(libc::S_IWOTH, 'w'),
+            exec(libc::S_IXOTH, libc::S_ISVTX, 't', 'T'),
+        ]
+        .iter()
+        .collect();
+        f.pad(&s)
+    }
+}
+
+/// The checks made by `access`. `EXISTS` alone only tests that the file
+/// exists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AccessMode(i32);
+
+impl AccessMode {
+    pub const EXISTS: AccessMode = AccessMode(libc::F_OK);
+    pub const READ: AccessMode = AccessMode(libc::R_OK);
+    pub const WRITE: AccessMode = AccessMode(libc::W_OK);
+    pub const EXECUTE: AccessMode = AccessMode(libc::X_OK);
+
+    pub const fn bits(self) -> i32 {
+        self.0
+    }
+
+    pub const fn contains(self, other: AccessMode) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for AccessMode {
+    type Output = AccessMode;
+
+    fn bitor(self, rhs: AccessMode) -> AccessMode {
+        AccessMode(self.0 | rhs.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenameFlags(u32);
+
+impl RenameFlags {
+    /// Fail with `EEXIST` rather than replace the destination.
+    pub const NOREPLACE: RenameFlags = RenameFlags(libc::RENAME_NOREPLACE);
+    /// Atomically swap the source and destination, which must both exist.
+    pub const EXCHANGE: RenameFlags = RenameFlags(libc::RENAME_EXCHANGE);
+    /// Leave an overlay/union whiteout object at the source.
+    pub const WHITEOUT: RenameFlags = RenameFlags(libc::RENAME_WHITEOUT);
+
+    pub const fn empty() -> RenameFlags {
+        RenameFlags(0)
+    }
+
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(self, other: RenameFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for RenameFlags {
+    type Output = RenameFlags;
+
+    fn bitor(self, rhs: RenameFlags) -> RenameFlags {
+        RenameFlags(self.0 | rhs.0)
+    }
+}
+
 pub struct OpenOptions(fs_imp::OpenOptions);
 
 impl OpenOptions {
@@ -85,6 +648,13 @@ impl OpenOptions {
         self
     }
 
+    /// Fail with `EEXIST` if the file already exists (`O_CREAT | O_EXCL`).
+    /// `create` and `truncate` are ignored when this is set.
+    pub fn create_new(&mut self, create_new: bool) -> &mut Self {
+        self.0.create_new(create_new);
+        self
+    }
+
     pub fn mode(&mut self, mode: u32) -> &mut Self {
         self.0.mode(mode);
         self
@@ -104,3 +674,582 @@ impl Default for OpenOptions {
         OpenOptions::new()
     }
 }
+
+pub fn remove_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    fs_imp::rmdir(path.as_ref())
+}
+
+/// Remove a directory after removing its contents. Symbolic links are
+/// removed, never followed, including one given as `path` itself.
+pub fn remove_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    fs_imp::remove_dir_all(path.as_ref())
+}
+
+/// An iterator over the entries of a directory, other than "." and "..",
+/// in the order the filesystem returns them.
+pub struct ReadDir(fs_imp::ReadDir);
+
+impl Iterator for ReadDir {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<DirEntry>> {
+        self.0.next().map(|entry| entry.map(DirEntry))
+    }
+}
+
+pub struct DirEntry(fs_imp::DirEntry);
+
+impl DirEntry {
+    /// The path of the entry: the directory passed to `read_dir` joined
+    /// with the file name.
+    pub fn path(&self) -> PathBuf {
+        self.0.path()
+    }
+
+    pub fn file_name(&self) -> OsString {
+        self.0.file_name()
+    }
+
+    pub fn ino(&self) -> u64 {
+        self.0.ino()
+    }
+
+    /// The type of the entry, which is not followed if a symbolic link.
+    pub fn file_type(&self) -> io::Result<FileType> {
+        self.0.file_type().map(FileType)
+    }
+
+    /// Like `symlink_metadata` on the entry's path.
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        symlink_metadata(self.path())
+    }
+}
+
+pub fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<ReadDir> {
+    fs_imp::read_dir(path.as_ref()).map(ReadDir)
+}
+
+pub fn metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
+    fs_imp::stat(path.as_ref()).map(Metadata)
+}
+
+/// Like `metadata`, but describe a symbolic link itself rather than the
+/// file it refers to (`lstat()`).
+pub fn symlink_metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
+    fs_imp::lstat(path.as_ref()).map(Metadata)
+}
+
+pub fn hard_link<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> io::Result<()> {
+    fs_imp::link(original.as_ref(), link.as_ref())
+}
+
+/// Create a symbolic link at `link` whose contents are `original`. The
+/// target is not checked, and a relative one is resolved against the
+/// directory containing the link.
+pub fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> io::Result<()> {
+    fs_imp::symlink(original.as_ref(), link.as_ref())
+}
+
+pub fn read_link<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
+    fs_imp::readlink(path.as_ref())
+}
+
+pub fn remove_file<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    fs_imp::unlink(path.as_ref())
+}
+
+/// Rename `from` to `to`, atomically replacing `to` if it exists.
+pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()> {
+    fs_imp::rename(from.as_ref(), to.as_ref())
+}
+
+/// Rename with `renameat2()`, whose flags allow refusing to replace the
+/// destination or exchanging the two paths.
+pub fn rename_with_flags<P: AsRef<Path>, Q: AsRef<Path>>(
+    from: P,
+    to: Q,
+    flags: RenameFlags,
+) -> io::Result<()> {
+    fs_imp::rename2(from.as_ref(), to.as_ref(), flags.bits())
+}
+
+/// Return the absolute path of `path` with all symbolic links, "." and ".."
+/// components resolved (`realpath()`).
+pub fn canonicalize<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
+    fs_imp::canonicalize(path.as_ref())
+}
+
+pub fn set_permissions<P: AsRef<Path>>(path: P, perm: Permissions) -> io::Result<()> {
+    fs_imp::set_perm(path.as_ref(), &perm.0)
+}
+
+/// Change the owner and/or group of `path`, following symbolic links. A
+/// `None` leaves that ID unchanged.
+pub fn chown<P: AsRef<Path>>(path: P, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+    fs_imp::chown(path.as_ref(), raw_id(uid), raw_id(gid))
+}
+
+/// Like `chown`, but change a symbolic link itself.
+pub fn lchown<P: AsRef<Path>>(path: P, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+    fs_imp::lchown(path.as_ref(), raw_id(uid), raw_id(gid))
+}
+
+pub fn fchown<F: AsRawFd>(fd: &F, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+    fs_imp::fchown(fd.as_raw_fd(), raw_id(uid), raw_id(gid))
+}
+
+/// The kernel treats an ID of -1 as "no change".
+pub(crate) fn raw_id(id: Option<u32>) -> u32 {
+    id.unwrap_or(u32::MAX)
+}
+
+/// Change the last access and modification times of `path`, following
+/// symbolic links (`utimensat()`).
+pub fn set_times<P: AsRef<Path>>(path: P, times: FileTimes) -> io::Result<()> {
+    fs_imp::set_times_at(libc::AT_FDCWD, path.as_ref(), times.0, 0)
+}
+
+/// Like `set_times`, but change a symbolic link itself
+/// (`AT_SYMLINK_NOFOLLOW`).
+pub fn set_times_nofollow<P: AsRef<Path>>(path: P, times: FileTimes) -> io::Result<()> {
+    let flags = libc::AT_SYMLINK_NOFOLLOW;
+    fs_imp::set_times_at(libc::AT_FDCWD, path.as_ref(), times.0, flags)
+}
+
+/// Set the process umask, returning the previous value.
+pub fn umask(mask: u32) -> u32 {
+    fs_imp::umask(mask)
+}
+
+/// Return the process umask without changing it.
+///
+/// umask() can only be queried by setting it, which races with other
+/// threads creating files, so the "Umask:" field of /proc/self/status
+/// (Linux 4.7 and later) is read instead where available.
+pub fn get_umask() -> u32 {
+    let mut status = String::new();
+    let _ = OpenOptions::new()
+        .read(true)
+        .open("/proc/self/status")
+        .and_then(|mut file| file.read_to_string(&mut status));
+    let field = status
+        .lines()
+        .find_map(|line| line.strip_prefix("Umask:"))
+        .and_then(|mask| u32::from_str_radix(mask.trim(), 8).ok());
+    match field {
+        Some(mask) => mask,
+        None => {
+            let mask = umask(0);
+            umask(mask);
+            mask
+        }
+    }
+}
+
+/// Check whether the real user and group IDs of the process may access
+/// `path` in the given way (`access()`). Fails with `EACCES` if not.
+///
+/// This is meant for set-user-ID programs; testing and then opening the
+/// file is otherwise a race that should be avoided by just opening it.
+pub fn access<P: AsRef<Path>>(path: P, mode: AccessMode) -> io::Result<()> {
+    fs_imp::access(path.as_ref(), mode.bits())
+}
+
+/// Like `access`, but check using the effective IDs, as `open()` would
+/// (`faccessat()` with `AT_EACCESS`).
+pub fn access_effective<P: AsRef<Path>>(path: P, mode: AccessMode) -> io::Result<()> {
+    fs_imp::access_at(libc::AT_FDCWD, path.as_ref(), mode.bits(), libc::AT_EACCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    const BLOCK: u64 = 64 * 1024;
+
+    fn len(mut file: &File) -> u64 {
+        file.seek(SeekFrom::End(0)).unwrap()
+    }
+
+    fn write_file(path: &Path, text: &str) {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    fn read_file(path: &Path) -> String {
+        let mut text = String::new();
+        OpenOptions::new()
+            .read(true)
+            .open(path)
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    }
+
+    #[test]
+    fn rename_flags() {
+        let dir = TempDir::new().unwrap();
+        let (a, b) = (dir.path().join("a"), dir.path().join("b"));
+        write_file(&a, "first");
+        write_file(&b, "second");
+
+        let err = rename_with_flags(&a, &b, RenameFlags::NOREPLACE).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EEXIST));
+        assert_eq!(
+            (read_file(&a), read_file(&b)),
+            ("first".into(), "second".into())
+        );
+
+        rename_with_flags(&a, &b, RenameFlags::EXCHANGE).unwrap();
+        assert_eq!(
+            (read_file(&a), read_file(&b)),
+            ("second".into(), "first".into())
+        );
+        // Both paths must exist to be exchanged
+        let c = dir.path().join("c");
+        let err = rename_with_flags(&a, &c, RenameFlags::EXCHANGE).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+
+        rename_with_flags(&a, &c, RenameFlags::NOREPLACE).unwrap();
+        assert!(symlink_metadata(&a).is_err());
+        assert_eq!(read_file(&c), "second");
+        rename(&c, &b).unwrap();
+        assert_eq!(read_file(&b), "second");
+    }
+
+    #[test]
+    fn hard_link_and_canonicalize() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("file");
+        write_file(&file, "linked");
+        let link = dir.path().join("link");
+        hard_link(&file, &link).unwrap();
+        let (meta, link_meta) = (metadata(&file).unwrap(), metadata(&link).unwrap());
+        assert_eq!((meta.ino(), meta.nlink()), (link_meta.ino(), 2));
+        let err = hard_link(&file, &link).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EEXIST));
+        remove_file(&link).unwrap();
+        assert_eq!(metadata(&file).unwrap().nlink(), 1);
+
+        let real = canonicalize(dir.path()).unwrap();
+        symlink("file", dir.path().join("sym")).unwrap();
+        let indirect = dir.path().join(".").join("sym");
+        assert_eq!(canonicalize(&indirect).unwrap(), real.join("file"));
+        assert_eq!(
+            canonicalize(real.join("file/.."))
+                .unwrap_err()
+                .raw_os_error(),
+            Some(libc::ENOTDIR)
+        );
+        let err = canonicalize(dir.path().join("missing")).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+    }
+
+    #[test]
+    fn permissions_display() {
+        for (mode, text) in [
+            (0o000, "---------"),
+            (0o644, "rw-r--r--"),
+            (0o755, "rwxr-xr-x"),
+            (0o4755, "rwsr-xr-x"),
+            (0o4644, "rwSr--r--"),
+            (0o2755, "rwxr-sr-x"),
+            (0o2644, "rw-r-Sr--"),
+            (0o1777, "rwxrwxrwt"),
+            (0o1776, "rwxrwxrwT"),
+            (0o7777, "rwsrwsrwt"),
+            (0o7000, "--S--S--T"),
+            // File type bits are discarded
+            (0o100640, "rw-r-----"),
+        ] {
+            let perm = Permissions::from_mode(mode);
+            assert_eq!(perm.to_string(), text, "{:o}", mode);
+        }
+        assert_eq!(
+            format!("{:>10}", Permissions::from_mode(0o600)),
+            " rw-------"
+        );
+
+        let mut perm = Permissions::from_mode(0o755);
+        perm.set_setuid(true);
+        perm.set_sticky(true);
+        assert_eq!(
+            (perm.mode(), perm.is_setuid(), perm.is_setgid()),
+            (0o5755, true, false)
+        );
+        perm.set_readonly(true);
+        assert_eq!(perm.to_string(), "r-sr-xr-t");
+        assert!(perm.readonly());
+    }
+
+    fn at(sec: u64, nsec: u32) -> SystemTime {
+        UNIX_EPOCH + Duration::new(sec, nsec)
+    }
+
+    #[test]
+    fn set_times() {
+        let tmp = NamedTempFile::new().unwrap();
+        let file = tmp.as_file();
+        let times = FileTimes::new().set_accessed(at(1_000_000_000, 123_456_789));
+        file.set_times(times.set_modified(at(1_500_000_000, 987_654_321)))
+            .unwrap();
+        let meta = file.metadata().unwrap();
+        assert_eq!(meta.accessed(), at(1_000_000_000, 123_456_789));
+        assert_eq!(meta.modified(), at(1_500_000_000, 987_654_321));
+
+        // Omitted times are left alone
+        file.set_modified(at(2_000_000_000, 1)).unwrap();
+        let meta = metadata(tmp.path()).unwrap();
+        assert_eq!(meta.accessed(), at(1_000_000_000, 123_456_789));
+        assert_eq!(meta.modified(), at(2_000_000_000, 1));
+
+        let before = SystemTime::now() - Duration::from_secs(1);
+        super::set_times(tmp.path(), FileTimes::new().set_accessed_now()).unwrap();
+        let meta = metadata(tmp.path()).unwrap();
+        assert!(meta.accessed() >= before);
+        assert_eq!(meta.modified(), at(2_000_000_000, 1));
+    }
+
+    #[test]
+    fn set_times_before_epoch() {
+        let tmp = NamedTempFile::new().unwrap();
+        let early = UNIX_EPOCH - Duration::new(1, 250_000_000);
+        let times = FileTimes::new().set_accessed(early);
+        match tmp
+            .as_file()
+            .set_times(times.set_modified(UNIX_EPOCH - Duration::from_secs(2)))
+        {
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) => return,
+            result => result.unwrap(),
+        }
+        let meta = tmp.as_file().metadata().unwrap();
+        assert_eq!(meta.accessed(), early);
+        assert_eq!(meta.modified(), UNIX_EPOCH - Duration::from_secs(2));
+    }
+
+    #[test]
+    fn set_times_nofollow() {
+        let tmp = NamedTempFile::new().unwrap();
+        tmp.as_file().set_modified(at(1_000_000_000, 0)).unwrap();
+        let dir = TempDir::new().unwrap();
+        let link = dir.path().join("link");
+        symlink(tmp.path(), &link).unwrap();
+
+        let times = FileTimes::new().set_modified(at(1_200_000_000, 5));
+        super::set_times_nofollow(&link, times).unwrap();
+        assert_eq!(
+            symlink_metadata(&link).unwrap().modified(),
+            at(1_200_000_000, 5)
+        );
+        assert_eq!(
+            metadata(tmp.path()).unwrap().modified(),
+            at(1_000_000_000, 0)
+        );
+
+        super::set_times(&link, FileTimes::new().set_modified(at(1_300_000_000, 0))).unwrap();
+        assert_eq!(
+            metadata(tmp.path()).unwrap().modified(),
+            at(1_300_000_000, 0)
+        );
+        assert_eq!(
+            symlink_metadata(&link).unwrap().modified(),
+            at(1_200_000_000, 5)
+        );
+    }
+
+    #[test]
+    fn umask_round_trip() {
+        // The umask applies to every thread, so change it in a child
+        let pid = unsafe { libc::fork() };
+        assert_ne!(pid, -1);
+        if pid == 0 {
+            let old = umask(0o027);
+            let ok = get_umask() == 0o027 && umask(old) == 0o027 && get_umask() == old;
+            unsafe { libc::_exit(if ok { 0 } else { 1 }) };
+        }
+        let mut status = 0;
+        assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+        assert_eq!(status, 0);
+    }
+
+    #[test]
+    fn chown_no_change() {
+        let tmp = NamedTempFile::new().unwrap();
+        let meta = metadata(tmp.path()).unwrap();
+        chown(tmp.path(), None, None).unwrap();
+        lchown(tmp.path(), None, None).unwrap();
+        fchown(tmp.as_file(), None, None).unwrap();
+        let after = metadata(tmp.path()).unwrap();
+        assert_eq!((after.uid(), after.gid()), (meta.uid(), meta.gid()));
+        // Changing to the current owner needs no privilege
+        chown(tmp.path(), Some(meta.uid()), Some(meta.gid())).unwrap();
+    }
+
+    #[test]
+    fn access_checks() {
+        let tmp = NamedTempFile::new().unwrap();
+        access(tmp.path(), AccessMode::READ | AccessMode::WRITE).unwrap();
+        access_effective(tmp.path(), AccessMode::EXISTS).unwrap();
+        // Even root is only granted execute permission if an execute bit
+        // is set
+        for check in [access::<&Path>, access_effective::<&Path>] {
+            let err = check(tmp.path(), AccessMode::EXECUTE).unwrap_err();
+            assert_eq!(err.raw_os_error(), Some(libc::EACCES));
+        }
+        let err = access(tmp.path().with_extension("missing"), AccessMode::EXISTS).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+
+        set_permissions(tmp.path(), Permissions::from_mode(0o000)).unwrap();
+        if OpenOptions::new().read(true).open(tmp.path()).is_ok() {
+            return; // Privileged, so the permission bits are not checked
+        }
+        for check in [access::<&Path>, access_effective::<&Path>] {
+            let err = check(tmp.path(), AccessMode::READ).unwrap_err();
+            assert_eq!(err.raw_os_error(), Some(libc::EACCES));
+        }
+        access(tmp.path(), AccessMode::EXISTS).unwrap();
+    }
+
+    #[test]
+    fn read_long_link() {
+        let dir = TempDir::new().unwrap();
+        let link = dir.path().join("link");
+        // The longest target symlink() accepts (PATH_MAX - 1), well past the
+        // initial buffer
+        let target: PathBuf = "x".repeat(4095).into();
+        symlink(&target, &link).unwrap();
+        assert_eq!(read_link(&link).unwrap(), target);
+
+        let short = dir.path().join("short");
+        symlink("abc", &short).unwrap();
+        assert_eq!(read_link(&short).unwrap(), Path::new("abc"));
+    }
+
+    #[test]
+    fn remove_dir_all_symlink() {
+        let dir = TempDir::new().unwrap();
+        let target = TempDir::new_in(dir.path()).unwrap().keep();
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(target.join("file"))
+            .unwrap();
+        let link = dir.path().join("link");
+        symlink(&target, &link).unwrap();
+
+        // The link is removed, not the directory it points to
+        remove_dir_all(&link).unwrap();
+        assert!(symlink_metadata(&link).is_err());
+        assert!(metadata(target.join("file")).unwrap().is_file());
+
+        // Nor is a link followed further down the tree
+        let tree = TempDir::new_in(dir.path()).unwrap().keep();
+        symlink(&target, tree.join("link")).unwrap();
+        remove_dir_all(&tree).unwrap();
+        assert!(symlink_metadata(&tree).is_err());
+        assert!(metadata(target.join("file")).unwrap().is_file());
+
+        remove_dir_all(&target).unwrap();
+        assert!(symlink_metadata(&target).is_err());
+        let err = remove_dir_all(&target).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+    }
+
+    #[test]
+    fn sparse_file() {
+        let mut file = tempfile().unwrap();
+        file.write_all(&vec![1; 4 * BLOCK as usize]).unwrap();
+        let punch = AllocateFlags::PUNCH_HOLE | AllocateFlags::KEEP_SIZE;
+        match file.allocate(BLOCK, 2 * BLOCK, punch) {
+            Err(e) if e.raw_os_error() == Some(libc::EOPNOTSUPP) => return,
+            result => result.unwrap(),
+        }
+        assert_eq!(len(&file), 4 * BLOCK);
+
+        // Extending with set_len() leaves another hole at the end
+        file.set_len(6 * BLOCK).unwrap();
+        let extents: Vec<Range<u64>> = file.data_extents().collect::<io::Result<_>>().unwrap();
+        if extents.len() == 1 && extents[0] == (0..6 * BLOCK) {
+            return; // The filesystem does not report holes
+        }
+        assert_eq!(extents, [0..BLOCK, 3 * BLOCK..4 * BLOCK]);
+        assert_eq!(file.seek_data(BLOCK).unwrap(), Some(3 * BLOCK));
+        assert_eq!(file.seek_hole(0).unwrap(), BLOCK);
+        assert_eq!(file.seek_data(4 * BLOCK).unwrap(), None);
+        assert_eq!(file.seek_hole(5 * BLOCK).unwrap(), 5 * BLOCK);
+
+        // A hole reads back as zeros
+        let mut buf = [1; 16];
+        file.seek(SeekFrom::Start(BLOCK)).unwrap();
+        file.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [0; 16]);
+
+        file.set_len(BLOCK / 2).unwrap();
+        assert_eq!(len(&file), BLOCK / 2);
+        let extents: Vec<Range<u64>> = file.data_extents().collect::<io::Result<_>>().unwrap();
+        assert_eq!(extents.len(), 1);
+        assert_eq!(extents[0], 0..BLOCK / 2);
+    }
+
+    #[test]
+    fn allocate_extends() {
+        let file = tempfile().unwrap();
+        match file.allocate(0, BLOCK, AllocateFlags::empty()) {
+            Err(e) if e.raw_os_error() == Some(libc::EOPNOTSUPP) => return,
+            result => result.unwrap(),
+        }
+        assert_eq!(len(&file), BLOCK);
+        file.allocate(0, 2 * BLOCK, AllocateFlags::KEEP_SIZE)
+            .unwrap();
+        assert_eq!(len(&file), BLOCK);
+        // The kernel rejects PUNCH_HOLE without KEEP_SIZE
+        let err = file
+            .allocate(0, BLOCK, AllocateFlags::PUNCH_HOLE)
+            .unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EOPNOTSUPP));
+    }
+
+    #[test]
+    fn sync() {
+        let mut file = tempfile().unwrap();
+        file.write_all(&vec![7; BLOCK as usize]).unwrap();
+        file.sync_data().unwrap();
+        file.sync_all().unwrap();
+        file.syncfs().unwrap();
+
+        // The values from linux/fs.h
+        assert_eq!(SyncRangeFlags::WAIT_BEFORE.bits(), 1);
+        assert_eq!(SyncRangeFlags::WRITE.bits(), 2);
+        assert_eq!(SyncRangeFlags::WAIT_AFTER.bits(), 4);
+        let all = SyncRangeFlags::WAIT_BEFORE | SyncRangeFlags::WRITE | SyncRangeFlags::WAIT_AFTER;
+        file.sync_range(0, 0, all).unwrap();
+        file.sync_range(0, BLOCK / 2, SyncRangeFlags::WRITE)
+            .unwrap();
+        file.sync_range(0, 0, SyncRangeFlags::empty()).unwrap();
+        let err = file.sync_range(0, 0, SyncRangeFlags(8)).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+    }
+
+    #[test]
+    fn advise() {
+        let file = tempfile().unwrap();
+        // posix_fadvise() fails with EINVAL for an unknown advice value
+        for advice in [
+            Advice::Normal,
+            Advice::Sequential,
+            Advice::Random,
+            Advice::NoReuse,
+            Advice::WillNeed,
+            Advice::DontNeed,
+        ] {
+            file.advise(0, 0, advice).unwrap();
+        }
+        file.advise(BLOCK, BLOCK, Advice::WillNeed).unwrap();
+    }
+}
@@ -0,0 +1,460 @@
+use super::xattr::{self, SetFlags};
+use std::fmt;
+use std::io;
+use std::ops::BitOr;
+use std::path::Path;
+
+const ACCESS_XATTR: &str = "system.posix_acl_access";
+const DEFAULT_XATTR: &str = "system.posix_acl_default";
+
+// linux/posix_acl_xattr.h
+const ACL_XATTR_VERSION: u32 = 0x0002;
+const ACL_UNDEFINED_ID: u32 = u32::MAX;
+
+const ACL_USER_OBJ: u16 = 0x01;
+const ACL_USER: u16 = 0x02;
+const ACL_GROUP_OBJ: u16 = 0x04;
+const ACL_GROUP: u16 = 0x08;
+const ACL_MASK: u16 = 0x10;
+const ACL_OTHER: u16 = 0x20;
+
+/// The kind of an ACL entry, with the user or group ID it applies to for
+/// `User` and `Group` entries.
+///
+/// The derived ordering is the one the kernel requires entries to be
+/// stored in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tag {
+    UserObj,
+    User(u32),
+    GroupObj,
+    Group(u32),
+    Mask,
+    Other,
+}
+
+impl Tag {
+    fn to_raw(self) -> (u16, u32) {
+        match self {
+            Tag::UserObj => (ACL_USER_OBJ, ACL_UNDEFINED_ID),
+            Tag::User(uid) => (ACL_USER, uid),
+            Tag::GroupObj => (ACL_GROUP_OBJ, ACL_UNDEFINED_ID),
+            Tag::Group(gid) => (ACL_GROUP, gid),
+            Tag::Mask => (ACL_MASK, ACL_UNDEFINED_ID),
+            Tag::Other => (ACL_OTHER, ACL_UNDEFINED_ID),
+        }
+    }
+
+    fn from_raw(tag: u16, id: u32) -> Option<Tag> {
+        Some(match tag {
+            ACL_USER_OBJ => Tag::UserObj,
+            ACL_USER => Tag::User(id),
+            ACL_GROUP_OBJ => Tag::GroupObj,
+            ACL_GROUP => Tag::Group(id),
+            ACL_MASK => Tag::Mask,
+            ACL_OTHER => Tag::Other,
+            _ => return None,
+        })
+    }
+
+    /// Whether the entry belongs to the group class, whose permissions
+    /// are limited by the `Mask` entry.
+    pub fn is_group_class(self) -> bool {
+        matches!(self, Tag::User(_) | Tag::GroupObj | Tag::Group(_))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Perms(u16);
+
+impl Perms {
+    pub const READ: Perms = Perms(4);
+    pub const WRITE: Perms = Perms(2);
+    pub const EXECUTE: Perms = Perms(1);
+
+    pub const fn empty() -> Perms {
+        Perms(0)
+    }
+
+    /// Take the permissions from the low three bits of `bits`, as in one
+    /// class of a file mode.
+    pub const fn from_bits(bits: u32) -> Perms {
+        Perms((bits & 0o7) as u16)
+    }
+
+    pub const fn bits(self) -> u32 {
+        self.0 as u32
+    }
+
+    pub const fn contains(self, other: Perms) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn intersection(self, other: Perms) -> Perms {
+        Perms(self.0 & other.0)
+    }
+}
+
+impl BitOr for Perms {
+    type Output = Perms;
+
+    fn bitor(self, rhs: Perms) -> Perms {
+        Perms(self.0 | rhs.0)
+    }
+}
+
+/// Renders as `ls` does, e.g. `r-x`.
+impl fmt::Display for Perms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bit = |perm, c| if self.contains(perm) { c } else { '-' };
+        let s: String = [
+            bit(Perms::READ, 'r'),
+            bit(Perms::WRITE, 'w'),
+            bit(Perms::EXECUTE, 'x'),
+        ]
+        .iter()
+        .collect();
+        f.pad(&s)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub tag: Tag,
+    pub perms: Perms,
+}
+
+/// A POSIX access control list, as stored in the
+/// `system.posix_acl_access` and `system.posix_acl_default` extended
+/// attributes.
+///
+/// Entries are kept sorted by tag, with at most one entry per tag.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Acl {
+    entries: Vec<Entry>,
+}
+
+impl Acl {
+    pub fn new() -> Acl {
+        Acl::default()
+    }
+
+    /// The minimal ACL equivalent to the permission bits of `mode`.
+    pub fn from_mode(mode: u32) -> Acl {
+        let mut acl = Acl::new();
+        acl.set(Tag::UserObj, Perms::from_bits(mode >> 6));
+        acl.set(Tag::GroupObj, Perms::from_bits(mode >> 3));
+        acl.set(Tag::Other, Perms::from_bits(mode));
+        acl
+    }
+
+    /// Decode the binary extended attribute format: a little-endian
+    /// version word followed by 8-byte (tag, perm, id) entries.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Acl> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed ACL");
+        if bytes.len() < 4 || !(bytes.len() - 4).is_multiple_of(8) {
+            return Err(invalid());
+        }
+        let (header, body) = bytes.split_at(4);
+        if u32::from_le_bytes(header.try_into().unwrap()) != ACL_XATTR_VERSION {
+            return Err(invalid());
+        }
+        let mut acl = Acl::new();
+        for raw in body.chunks_exact(8) {
+            let tag = u16::from_le_bytes([raw[0], raw[1]]);
+            let perm = u16::from_le_bytes([raw[2], raw[3]]);
+            let id = u32::from_le_bytes([raw[4], raw[5], raw[6], raw[7]]);
+            let tag = Tag::from_raw(tag, id).ok_or_else(invalid)?;
+            if acl.get(tag).is_some() {
+                return Err(invalid());
+            }
+            acl.set(tag, Perms::from_bits(perm as u32));
+        }
+        Ok(acl)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + 8 * self.entries.len());
+        bytes.extend_from_slice(&ACL_XATTR_VERSION.to_le_bytes());
+        for entry in &self.entries {
+            let (tag, id) = entry.tag.to_raw();
+            bytes.extend_from_slice(&tag.to_le_bytes());
+            bytes.extend_from_slice(&entry.perms.0.to_le_bytes());
+            bytes.extend_from_slice(&id.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn get(&self, tag: Tag) -> Option<Perms> {
+        self.entries
+            .binary_search_by(|entry| entry.tag.cmp(&tag))
+            .ok()
+            .map(|i| self.entries[i].perms)
+    }
+
+    /// Add an entry for `tag`, or replace the permissions of the existing
+    /// one.
+    pub fn set(&mut self, tag: Tag, perms: Perms) {
+        let entry = Entry { tag, perms };
+        match self.entries.binary_search_by(|entry| entry.tag.cmp(&tag)) {
+            Ok(i) => self.entries[i] = entry,
+            Err(i) => self.entries.insert(i, entry),
+        }
+    }
+
+    pub fn remove(&mut self, tag: Tag) -> Option<Perms> {
+        let i = self
+            .entries
+            .binary_search_by(|entry| entry.tag.cmp(&tag))
+            .ok()?;
+        Some(self.entries.remove(i).perms)
+    }
+
+    pub fn mask(&self) -> Option<Perms> {
+        self.get(Tag::Mask)
+    }
+
+    /// Set the `Mask` entry to the union of the group class entries, as
+    /// `acl_calc_mask()` does. An ACL with `User` or `Group` entries must
+    /// have a mask.
+    pub fn calc_mask(&mut self) {
+        let mask = self
+            .entries
+            .iter()
+            .filter(|entry| entry.tag.is_group_class())
+            .fold(Perms::empty(), |mask, entry| mask | entry.perms);
+        self.set(Tag::Mask, mask);
+    }
+
+    /// The permissions an entry actually grants: those of a group class
+    /// entry are limited by the mask, if there is one.
+    pub fn effective(&self, entry: &Entry) -> Perms {
+        match self.mask() {
+            Some(mask) if entry.tag.is_group_class() => entry.perms.intersection(mask),
+            _ => entry.perms,
+        }
+    }
+
+    /// Whether the ACL is well formed: it has exactly one each of the
+    /// `UserObj`, `GroupObj` and `Other` entries, and a `Mask` if it has
+    /// any `User` or `Group` entries.
+    pub fn is_valid(&self) -> bool {
+        let has = |tag| self.get(tag).is_some();
+        let named = self
+            .entries
+            .iter()
+            .any(|entry| matches!(entry.tag, Tag::User(_) | Tag::Group(_)));
+        has(Tag::UserObj) && has(Tag::GroupObj) && has(Tag::Other) && (has(Tag::Mask) || !named)
+    }
+
+    /// Decide whether a process with effective user ID `uid` and the group
+    /// IDs `gids` (effective and supplementary) would be granted `want` on
+    /// a file owned by `owner` and `group`, following the kernel's
+    /// algorithm. Privileged processes, which bypass the check, are not
+    /// considered.
+    ///
+    /// Only the first of the owner, named user, group and other classes
+    /// that matches the process is consulted. Within the group class,
+    /// access is granted if any one matching entry grants all of `want`.
+    pub fn check_access(
+        &self,
+        owner: u32,
+        group: u32,
+        uid: u32,
+        gids: &[u32],
+        want: Perms,
+    ) -> bool {
+        if uid == owner {
+            return self.get(Tag::UserObj).unwrap_or_default().contains(want);
+        }
+        let mut group_matched = false;
+        for entry in &self.entries {
+            let matches = match entry.tag {
+                Tag::User(id) if id == uid => {
+                    return self.effective(entry).contains(want);
+                }
+                Tag::GroupObj => gids.contains(&group),
+                Tag::Group(id) => gids.contains(&id),
+                _ => false,
+            };
+            if matches {
+                if self.effective(entry).contains(want) {
+                    return true;
+                }
+                group_matched = true;
+            }
+        }
+        if group_matched {
+            return false;
+        }
+        self.get(Tag::Other).unwrap_or_default().contains(want)
+    }
+}
+
+/// Return the access ACL of `path`. A file without an extended ACL gets
+/// the minimal ACL equivalent to its mode, as with `acl_get_file()`.
+pub fn get<P: AsRef<Path>>(path: P) -> io::Result<Acl> {
+    let path = path.as_ref();
+    match xattr::get(path, ACCESS_XATTR)? {
+        Some(bytes) => Acl::from_bytes(&bytes),
+        None => super::metadata(path).map(|m| Acl::from_mode(m.mode())),
+    }
+}
+
+/// Return the default ACL of directory `path`, which is given to files
+/// created in it, or `None` if it has none.
+pub fn get_default<P: AsRef<Path>>(path: P) -> io::Result<Option<Acl>> {
+    xattr::get(path, DEFAULT_XATTR)?
+        .map(|bytes| Acl::from_bytes(&bytes))
+        .transpose()
+}
+
+/// Set the access ACL of `path`. The kernel also updates the file mode to
+/// match the `UserObj`, `Other` and `Mask` (or `GroupObj`) entries, and
+/// rejects an ACL that is not valid with `EINVAL`.
+pub fn set<P: AsRef<Path>>(path: P, acl: &Acl) -> io::Result<()> {
+    xattr::set(path, ACCESS_XATTR, &acl.to_bytes(), SetFlags::empty())
+}
+
+pub fn set_default<P: AsRef<Path>>(path: P, acl: &Acl) -> io::Result<()> {
+    xattr::set(path, DEFAULT_XATTR, &acl.to_bytes(), SetFlags::empty())
+}
+
+pub fn remove_default<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    xattr::remove(path, DEFAULT_XATTR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RW: Perms = Perms(6);
+    const RX: Perms = Perms(5);
+
+    fn raw_entry(tag: u16, perm: u16, id: u32) -> Vec<u8> {
+        let mut bytes = tag.to_le_bytes().to_vec();
+        bytes.extend(perm.to_le_bytes());
+        bytes.extend(id.to_le_bytes());
+        bytes
+    }
+
+    /// user::rw-, user:1000:rw-, group::r-x, group:2000:-w-, mask::r-x,
+    /// other::r--. The checks below take the file to be owned by 500:600.
+    fn sample() -> Acl {
+        let mut acl = Acl::from_mode(0o654);
+        acl.set(Tag::User(1000), RW);
+        acl.set(Tag::Group(2000), Perms::WRITE);
+        acl.set(Tag::Mask, RX);
+        acl
+    }
+
+    #[test]
+    fn xattr_bytes() {
+        let mut bytes = 2u32.to_le_bytes().to_vec();
+        bytes.extend(raw_entry(ACL_USER_OBJ, 6, u32::MAX));
+        bytes.extend(raw_entry(ACL_USER, 6, 1000));
+        bytes.extend(raw_entry(ACL_GROUP_OBJ, 5, u32::MAX));
+        bytes.extend(raw_entry(ACL_GROUP, 2, 2000));
+        bytes.extend(raw_entry(ACL_MASK, 5, u32::MAX));
+        bytes.extend(raw_entry(ACL_OTHER, 4, u32::MAX));
+        assert_eq!(sample().to_bytes(), bytes);
+        assert_eq!(Acl::from_bytes(&bytes).unwrap(), sample());
+    }
+
+    #[test]
+    fn xattr_round_trip() {
+        for acl in [Acl::from_mode(0o750), sample(), Acl::new()] {
+            assert_eq!(Acl::from_bytes(&acl.to_bytes()).unwrap(), acl);
+        }
+        // Entries are sorted whatever order they are stored in
+        let mut bytes = 2u32.to_le_bytes().to_vec();
+        bytes.extend(raw_entry(ACL_OTHER, 0, u32::MAX));
+        bytes.extend(raw_entry(ACL_USER_OBJ, 7, u32::MAX));
+        bytes.extend(raw_entry(ACL_GROUP_OBJ, 5, u32::MAX));
+        let acl = Acl::from_bytes(&bytes).unwrap();
+        assert_eq!(acl, Acl::from_mode(0o750));
+        assert!(acl.is_valid());
+    }
+
+    #[test]
+    fn xattr_malformed() {
+        let good = sample().to_bytes();
+        assert!(Acl::from_bytes(&good[..good.len() - 1]).is_err());
+        assert!(Acl::from_bytes(&[2, 0]).is_err());
+        let mut bad_version = good.clone();
+        bad_version[0] = 1;
+        assert!(Acl::from_bytes(&bad_version).is_err());
+        let mut bad_tag = good.clone();
+        bad_tag[4] = 0x40;
+        assert!(Acl::from_bytes(&bad_tag).is_err());
+        let mut duplicate = good.clone();
+        duplicate.extend(raw_entry(ACL_OTHER, 0, u32::MAX));
+        assert!(Acl::from_bytes(&duplicate).is_err());
+    }
+
+    #[test]
+    fn mask() {
+        let mut acl = sample();
+        assert!(acl.is_valid());
+        assert_eq!(
+            acl.effective(&Entry {
+                tag: Tag::User(1000),
+                perms: RW
+            }),
+            Perms::READ
+        );
+        assert_eq!(
+            acl.effective(&Entry {
+                tag: Tag::UserObj,
+                perms: RW
+            }),
+            RW
+        );
+        acl.calc_mask();
+        assert_eq!(acl.mask(), Some(Perms(7)));
+        acl.remove(Tag::Mask);
+        assert!(!acl.is_valid());
+    }
+
+    #[test]
+    fn check_access_owner_and_other() {
+        let acl = sample();
+        // The owner class ignores the mask and is never combined with others
+        assert!(acl.check_access(500, 600, 500, &[600], RW));
+        assert!(!acl.check_access(500, 600, 500, &[600], Perms::EXECUTE));
+        // Other is not limited by the mask either
+        assert!(acl.check_access(500, 600, 700, &[700], Perms::READ));
+        assert!(!acl.check_access(500, 600, 700, &[700], Perms::WRITE));
+    }
+
+    #[test]
+    fn check_access_named_user() {
+        let acl = sample();
+        // user:1000:rw- is masked to r--, and matching it ends the search
+        // even though group::r-x would grant execute
+        assert!(acl.check_access(500, 600, 1000, &[600], Perms::READ));
+        assert!(!acl.check_access(500, 600, 1000, &[600], Perms::WRITE));
+        assert!(!acl.check_access(500, 600, 1000, &[600], Perms::EXECUTE));
+    }
+
+    #[test]
+    fn check_access_group_class() {
+        let acl = sample();
+        // Any one matching group entry must grant everything wanted
+        assert!(acl.check_access(500, 600, 700, &[600, 2000], RX));
+        assert!(!acl.check_access(500, 600, 700, &[600, 2000], Perms::READ | Perms::WRITE));
+        // group:2000:-w- is masked to nothing, and a matching group class
+        // entry that denies does not fall through to other::r--
+        assert!(!acl.check_access(500, 600, 700, &[2000], Perms::WRITE));
+        assert!(!acl.check_access(500, 600, 700, &[2000], Perms::READ));
+
+        let mut acl = sample();
+        acl.set(Tag::Mask, Perms(7));
+        assert!(acl.check_access(500, 600, 700, &[2000], Perms::WRITE));
+        assert!(acl.check_access(500, 600, 1000, &[], RW));
+    }
+}
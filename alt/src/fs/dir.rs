@@ -0,0 +1,312 @@
+use super::{raw_id, AccessMode, File, Metadata, OpenOptions, Permissions, RenameFlags};
+use crate::os::fd::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+use crate::sys::fs as fs_imp;
+use std::io;
+use std::ops::BitOr;
+use std::path::{Path, PathBuf};
+
+/// A handle on a directory, opened with `O_PATH | O_DIRECTORY`, against
+/// which relative paths are resolved by the `*at()` system calls.
+///
+/// Holding the directory open, rather than its path, means operations keep
+/// referring to the same directory even if it is renamed or a component of
+/// its path is replaced by a symbolic link.
+pub struct Dir {
+    fd: OwnedFd,
+}
+
+impl Dir {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Dir> {
+        fs_imp::open_dir_at(libc::AT_FDCWD, path.as_ref()).map(|fd| Dir { fd })
+    }
+
+    pub fn open_dir_at<P: AsRef<Path>>(&self, path: P) -> io::Result<Dir> {
+        fs_imp::open_dir_at(self.as_raw_fd(), path.as_ref()).map(|fd| Dir { fd })
+    }
+
+    /// Open a directory using `openat2()`, constraining how `path` is
+    /// resolved. Fails with `ENOSYS` on kernels before 5.6.
+    pub fn open_dir_resolve<P: AsRef<Path>>(
+        &self,
+        path: P,
+        resolve: ResolveFlags,
+    ) -> io::Result<Dir> {
+        fs_imp::open_dir_at2(self.as_raw_fd(), path.as_ref(), resolve.bits()).map(|fd| Dir { fd })
+    }
+
+    pub fn open_at<P: AsRef<Path>>(&self, path: P, opts: &OpenOptions) -> io::Result<File> {
+        fs_imp::File::open_at(self.as_raw_fd(), path.as_ref(), &opts.0).map(|inner| File { inner })
+    }
+
+    /// Like `open_at`, but resolve `path` with `openat2()`.
+    pub fn open_resolve<P: AsRef<Path>>(
+        &self,
+        path: P,
+        opts: &OpenOptions,
+        resolve: ResolveFlags,
+    ) -> io::Result<File> {
+        fs_imp::File::open_at2(self.as_raw_fd(), path.as_ref(), &opts.0, resolve.bits())
+            .map(|inner| File { inner })
+    }
+
+    pub fn stat_at<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        fs_imp::stat_at(self.as_raw_fd(), path.as_ref(), true).map(Metadata)
+    }
+
+    /// Like `stat_at`, but do not follow a trailing symbolic link
+    /// (`AT_SYMLINK_NOFOLLOW`).
+    pub fn symlink_metadata_at<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        fs_imp::stat_at(self.as_raw_fd(), path.as_ref(), false).map(Metadata)
+    }
+
+    pub fn mkdir_at<P: AsRef<Path>>(&self, path: P, mode: u32) -> io::Result<()> {
+        fs_imp::mkdir_at(self.as_raw_fd(), path.as_ref(), mode)
+    }
+
+    pub fn unlink_at<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs_imp::unlink_at(self.as_raw_fd(), path.as_ref(), 0)
+    }
+
+    pub fn remove_dir_at<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs_imp::unlink_at(self.as_raw_fd(), path.as_ref(), libc::AT_REMOVEDIR)
+    }
+
+    /// Rename `from`, relative to this directory, to `to`, relative to
+    /// `to_dir` (`renameat2()`).
+    pub fn rename_at<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Dir,
+        to: Q,
+        flags: RenameFlags,
+    ) -> io::Result<()> {
+        fs_imp::rename_at(
+            self.as_raw_fd(),
+            from.as_ref(),
+            to_dir.as_raw_fd(),
+            to.as_ref(),
+            flags.bits(),
+        )
+    }
+
+    pub fn set_permissions_at<P: AsRef<Path>>(&self, path: P, perm: Permissions) -> io::Result<()> {
+        fs_imp::set_perm_at(self.as_raw_fd(), path.as_ref(), &perm.0)
+    }
+
+    /// Change ownership as `alt::fs::chown` does, following a trailing
+    /// symbolic link.
+    pub fn chown_at<P: AsRef<Path>>(
+        &self,
+        path: P,
+        uid: Option<u32>,
+        gid: Option<u32>,
+    ) -> io::Result<()> {
+        fs_imp::chown_at(self.as_raw_fd(), path.as_ref(), raw_id(uid), raw_id(gid), 0)
+    }
+
+    pub fn lchown_at<P: AsRef<Path>>(
+        &self,
+        path: P,
+        uid: Option<u32>,
+        gid: Option<u32>,
+    ) -> io::Result<()> {
+        let (uid, gid) = (raw_id(uid), raw_id(gid));
+        let flags = libc::AT_SYMLINK_NOFOLLOW;
+        fs_imp::chown_at(self.as_raw_fd(), path.as_ref(), uid, gid, flags)
+    }
+
+    /// Check access using the real IDs, as `alt::fs::access` does.
+    pub fn access_at<P: AsRef<Path>>(&self, path: P, mode: AccessMode) -> io::Result<()> {
+        fs_imp::access_at(self.as_raw_fd(), path.as_ref(), mode.bits(), 0)
+    }
+
+    /// Check access using the effective IDs (`AT_EACCESS`).
+    pub fn access_effective_at<P: AsRef<Path>>(&self, path: P, mode: AccessMode) -> io::Result<()> {
+        let flags = libc::AT_EACCESS;
+        fs_imp::access_at(self.as_raw_fd(), path.as_ref(), mode.bits(), flags)
+    }
+
+    pub fn readlink_at<P: AsRef<Path>>(&self, path: P) -> io::Result<PathBuf> {
+        fs_imp::readlink_at(self.as_raw_fd(), path.as_ref())
+    }
+}
+
+impl AsRawFd for Dir {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl FromRawFd for Dir {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Dir {
+            fd: FromRawFd::from_raw_fd(fd),
+        }
+    }
+}
+
+impl IntoRawFd for Dir {
+    fn into_raw_fd(self) -> RawFd {
+        self.fd.into_raw_fd()
+    }
+}
+
+/// Restrictions on path resolution for `openat2()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResolveFlags(u64);
+
+impl ResolveFlags {
+    /// Fail with `EXDEV` if resolution crosses a mount point.
+    pub const NO_XDEV: ResolveFlags = ResolveFlags(libc::RESOLVE_NO_XDEV);
+    /// Refuse to follow `/proc/PID/fd`-style "magic" links.
+    pub const NO_MAGICLINKS: ResolveFlags = ResolveFlags(libc::RESOLVE_NO_MAGICLINKS);
+    /// Fail with `ELOOP` if any component is a symbolic link.
+    pub const NO_SYMLINKS: ResolveFlags = ResolveFlags(libc::RESOLVE_NO_SYMLINKS);
+    /// Fail with `EXDEV` if resolution would escape the starting directory,
+    /// whether through `..`, an absolute path or a symbolic link.
+    pub const BENEATH: ResolveFlags = ResolveFlags(libc::RESOLVE_BENEATH);
+    /// Treat the starting directory as the root, as if by `chroot()`.
+    pub const IN_ROOT: ResolveFlags = ResolveFlags(libc::RESOLVE_IN_ROOT);
+    /// Fail with `EAGAIN` unless resolution can be done from the dentry
+    /// cache alone.
+    pub const CACHED: ResolveFlags = ResolveFlags(libc::RESOLVE_CACHED);
+
+    pub const fn empty() -> ResolveFlags {
+        ResolveFlags(0)
+    }
+
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub const fn contains(self, other: ResolveFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for ResolveFlags {
+    type Output = ResolveFlags;
+
+    fn bitor(self, rhs: ResolveFlags) -> ResolveFlags {
+        ResolveFlags(self.0 | rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::{symlink, TempDir};
+    use std::io::{Read, Write};
+
+    fn read_only() -> OpenOptions {
+        let mut opts = OpenOptions::new();
+        opts.read(true);
+        opts
+    }
+
+    #[test]
+    fn relative_operations() {
+        let tmp = TempDir::new().unwrap();
+        let dir = Dir::open(tmp.path()).unwrap();
+        dir.mkdir_at("sub", 0o755).unwrap();
+        let sub = dir.open_dir_at("sub").unwrap();
+        let mut opts = OpenOptions::new();
+        opts.write(true).create(true).mode(0o640);
+        sub.open_at("file", &opts)
+            .unwrap()
+            .write_all(b"data")
+            .unwrap();
+
+        assert_eq!(dir.stat_at("sub/file").unwrap().len(), 4);
+        dir.set_permissions_at("sub/file", Permissions::from_mode(0o600))
+            .unwrap();
+        assert_eq!(sub.stat_at("file").unwrap().permissions().mode(), 0o600);
+        sub.access_at("file", AccessMode::READ).unwrap();
+
+        symlink("sub/file", tmp.path().join("link")).unwrap();
+        assert!(dir
+            .symlink_metadata_at("link")
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert!(dir.stat_at("link").unwrap().is_file());
+        assert_eq!(dir.readlink_at("link").unwrap(), Path::new("sub/file"));
+
+        // The handle keeps referring to the directory after a rename
+        dir.rename_at("sub", &dir, "renamed", RenameFlags::empty())
+            .unwrap();
+        let mut buf = String::new();
+        sub.open_at("file", &read_only())
+            .unwrap()
+            .read_to_string(&mut buf)
+            .unwrap();
+        assert_eq!(buf, "data");
+
+        let err = dir.remove_dir_at("renamed").unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOTEMPTY));
+        sub.unlink_at("file").unwrap();
+        dir.remove_dir_at("renamed").unwrap();
+        dir.unlink_at("link").unwrap();
+        let err = dir.stat_at("renamed").err().unwrap();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+    }
+
+    #[test]
+    fn resolve_beneath() {
+        let tmp = TempDir::new().unwrap();
+        let top = Dir::open(tmp.path()).unwrap();
+        top.mkdir_at("root", 0o755).unwrap();
+        top.mkdir_at("root/sub", 0o755).unwrap();
+        top.mkdir_at("outside", 0o755).unwrap();
+        let mut opts = OpenOptions::new();
+        opts.write(true).create(true);
+        top.open_at("root/file", &opts).unwrap();
+        top.open_at("outside/secret", &opts).unwrap();
+        let root_path = tmp.path().join("root");
+        symlink("sub", root_path.join("inner")).unwrap();
+        symlink("../outside/secret", root_path.join("up")).unwrap();
+        symlink("/file", root_path.join("abs")).unwrap();
+
+        let root = Dir::open(&root_path).unwrap();
+        let beneath = ResolveFlags::BENEATH;
+        match root.open_resolve("file", &read_only(), beneath) {
+            Err(e) if e.raw_os_error() == Some(libc::ENOSYS) => return,
+            result => drop(result.unwrap()),
+        }
+        root.open_resolve("sub/../file", &read_only(), beneath)
+            .unwrap();
+        root.open_dir_resolve("inner", beneath).unwrap();
+
+        // Escapes through "..", an absolute path or a symbolic link
+        for path in [
+            "../outside/secret",
+            "sub/../../outside/secret",
+            "/etc/passwd",
+            "up",
+            "abs",
+        ] {
+            let err = root
+                .open_resolve(path, &read_only(), beneath)
+                .err()
+                .unwrap();
+            assert_eq!(err.raw_os_error(), Some(libc::EXDEV), "{}", path);
+        }
+
+        // IN_ROOT resolves the same paths as if root were "/"
+        let in_root = ResolveFlags::IN_ROOT;
+        root.open_resolve("abs", &read_only(), in_root).unwrap();
+        let err = root
+            .open_resolve("up", &read_only(), in_root)
+            .err()
+            .unwrap();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+
+        let err = root
+            .open_dir_resolve("inner", ResolveFlags::NO_SYMLINKS)
+            .err()
+            .unwrap();
+        assert_eq!(err.raw_os_error(), Some(libc::ELOOP));
+        assert!((beneath | ResolveFlags::NO_SYMLINKS).contains(beneath));
+    }
+}
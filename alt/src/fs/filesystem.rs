@@ -0,0 +1,386 @@
+use super::OpenOptions;
+use crate::os::fd::AsRawFd;
+use crate::sys::fs as fs_imp;
+use std::ffi::OsString;
+use std::io::{self, Read};
+use std::ops::BitOr;
+use std::os::unix::ffi::OsStringExt;
+use std::path::{Path, PathBuf};
+
+/// Statistics for a mounted filesystem, from `statvfs()`.
+///
+/// Block counts are in units of `fragment_size`.
+#[derive(Clone)]
+pub struct FsStats(libc::statvfs64);
+
+impl FsStats {
+    /// The preferred I/O block size.
+    pub fn block_size(&self) -> u64 {
+        self.0.f_bsize
+    }
+
+    /// The fundamental block size, in which block counts are given.
+    pub fn fragment_size(&self) -> u64 {
+        self.0.f_frsize
+    }
+
+    pub fn blocks(&self) -> u64 {
+        self.0.f_blocks
+    }
+
+    /// Free blocks, including those reserved for privileged processes.
+    pub fn blocks_free(&self) -> u64 {
+        self.0.f_bfree
+    }
+
+    /// Free blocks available to unprivileged processes.
+    pub fn blocks_available(&self) -> u64 {
+        self.0.f_bavail
+    }
+
+    pub fn files(&self) -> u64 {
+        self.0.f_files
+    }
+
+    pub fn files_free(&self) -> u64 {
+        self.0.f_ffree
+    }
+
+    pub fn files_available(&self) -> u64 {
+        self.0.f_favail
+    }
+
+    pub fn fsid(&self) -> u64 {
+        self.0.f_fsid
+    }
+
+    pub fn flags(&self) -> MountFlags {
+        MountFlags(self.0.f_flag)
+    }
+
+    pub fn name_max(&self) -> u64 {
+        self.0.f_namemax
+    }
+
+    pub fn total_space(&self) -> u64 {
+        self.blocks() * self.fragment_size()
+    }
+
+    /// The number of bytes an unprivileged process can still write.
+    pub fn available_space(&self) -> u64 {
+        self.blocks_available() * self.fragment_size()
+    }
+
+    pub fn as_inner(&self) -> &libc::statvfs64 {
+        &self.0
+    }
+}
+
+/// The mount flags reported in `FsStats::flags`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MountFlags(u64);
+
+impl MountFlags {
+    pub const RDONLY: MountFlags = MountFlags(libc::ST_RDONLY);
+    /// Set-user-ID and set-group-ID bits are ignored.
+    pub const NOSUID: MountFlags = MountFlags(libc::ST_NOSUID);
+    pub const NODEV: MountFlags = MountFlags(libc::ST_NODEV);
+    pub const NOEXEC: MountFlags = MountFlags(libc::ST_NOEXEC);
+    pub const SYNCHRONOUS: MountFlags = MountFlags(libc::ST_SYNCHRONOUS);
+    pub const MANDLOCK: MountFlags = MountFlags(libc::ST_MANDLOCK);
+    pub const NOATIME: MountFlags = MountFlags(libc::ST_NOATIME);
+    pub const NODIRATIME: MountFlags = MountFlags(libc::ST_NODIRATIME);
+    pub const RELATIME: MountFlags = MountFlags(libc::ST_RELATIME);
+
+    pub const fn empty() -> MountFlags {
+        MountFlags(0)
+    }
+
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub const fn contains(self, other: MountFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for MountFlags {
+    type Output = MountFlags;
+
+    fn bitor(self, rhs: MountFlags) -> MountFlags {
+        MountFlags(self.0 | rhs.0)
+    }
+}
+
+/// Return statistics for the filesystem containing `path`.
+pub fn statvfs<P: AsRef<Path>>(path: P) -> io::Result<FsStats> {
+    fs_imp::statvfs(path.as_ref()).map(FsStats)
+}
+
+pub fn fstatvfs<F: AsRawFd>(fd: &F) -> io::Result<FsStats> {
+    fs_imp::fstatvfs(fd.as_raw_fd()).map(FsStats)
+}
+
+/// Linux-specific filesystem statistics, from `statfs()`. Unlike
+/// `FsStats`, these include the filesystem type.
+#[derive(Clone)]
+pub struct FsInfo(libc::statfs64);
+
+impl FsInfo {
+    /// The filesystem magic number, such as `libc::EXT4_SUPER_MAGIC`.
+    pub fn fs_type(&self) -> i64 {
+        self.0.f_type
+    }
+
+    pub fn block_size(&self) -> u64 {
+        self.0.f_bsize as u64
+    }
+
+    pub fn fragment_size(&self) -> u64 {
+        self.0.f_frsize as u64
+    }
+
+    pub fn blocks(&self) -> u64 {
+        self.0.f_blocks
+    }
+
+    pub fn blocks_free(&self) -> u64 {
+        self.0.f_bfree
+    }
+
+    pub fn blocks_available(&self) -> u64 {
+        self.0.f_bavail
+    }
+
+    pub fn files(&self) -> u64 {
+        self.0.f_files
+    }
+
+    pub fn files_free(&self) -> u64 {
+        self.0.f_ffree
+    }
+
+    pub fn fsid(&self) -> [i32; 2] {
+        self.0.f_fsid.__val
+    }
+
+    pub fn name_max(&self) -> u64 {
+        self.0.f_namelen as u64
+    }
+
+    pub fn flags(&self) -> MountFlags {
+        MountFlags(self.0.f_flags as u64)
+    }
+
+    pub fn as_inner(&self) -> &libc::statfs64 {
+        &self.0
+    }
+}
+
+pub fn statfs<P: AsRef<Path>>(path: P) -> io::Result<FsInfo> {
+    fs_imp::statfs(path.as_ref()).map(FsInfo)
+}
+
+pub fn fstatfs<F: AsRawFd>(fd: &F) -> io::Result<FsInfo> {
+    fs_imp::fstatfs(fd.as_raw_fd()).map(FsInfo)
+}
+
+/// A line of `/proc/PID/mountinfo` (see proc(5)).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MountEntry {
+    pub mount_id: u32,
+    pub parent_id: u32,
+    pub major: u32,
+    pub minor: u32,
+    /// The directory within the filesystem that forms the root of this
+    /// mount, which differs from "/" for bind mounts.
+    pub root: PathBuf,
+    pub mount_point: PathBuf,
+    /// Per-mount options, such as "rw" or "nosuid".
+    pub mount_options: Vec<String>,
+    /// Tagged fields such as "shared:1" or "master:2".
+    pub optional_fields: Vec<String>,
+    pub fs_type: String,
+    pub source: OsString,
+    /// Per-superblock options.
+    pub super_options: Vec<String>,
+}
+
+impl MountEntry {
+    pub fn parse(line: &[u8]) -> io::Result<MountEntry> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed mountinfo line");
+        let mut fields = line.split(|&b| b == b' ');
+        let mut next = || fields.next().ok_or_else(invalid);
+
+        let number = |field: &[u8]| -> io::Result<u32> {
+            std::str::from_utf8(field)
+                .ok()
+                .and_then(|field| field.parse().ok())
+                .ok_or_else(invalid)
+        };
+        let mount_id = number(next()?)?;
+        let parent_id = number(next()?)?;
+        let (major, minor) = {
+            let dev = next()?;
+            let colon = dev.iter().position(|&b| b == b':').ok_or_else(invalid)?;
+            (number(&dev[..colon])?, number(&dev[colon + 1..])?)
+        };
+        let root = PathBuf::from(unescape(next()?));
+        let mount_point = PathBuf::from(unescape(next()?));
+        let mount_options = options(next()?);
+
+        let mut optional_fields = Vec::new();
+        loop {
+            match next()? {
+                b"-" => break,
+                field => optional_fields.push(String::from_utf8_lossy(field).into_owned()),
+            }
+        }
+
+        let fs_type = String::from_utf8_lossy(next()?).into_owned();
+        let source = unescape(next()?);
+        let super_options = options(next()?);
+
+        Ok(MountEntry {
+            mount_id,
+            parent_id,
+            major,
+            minor,
+            root,
+            mount_point,
+            mount_options,
+            optional_fields,
+            fs_type,
+            source,
+            super_options,
+        })
+    }
+
+    pub fn has_option(&self, option: &str) -> bool {
+        self.mount_options.iter().any(|o| o == option)
+    }
+}
+
+fn options(field: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(field)
+        .split(',')
+        .map(String::from)
+        .collect()
+}
+
+/// Undo the octal escaping (e.g. "\040" for a space) that the kernel
+/// applies to white space and backslashes in paths.
+fn unescape(field: &[u8]) -> OsString {
+    let mut bytes = Vec::with_capacity(field.len());
+    let mut i = 0;
+    while i < field.len() {
+        let octal = field
+            .get(i + 1..i + 4)
+            .filter(|digits| field[i] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d)));
+        match octal {
+            Some(digits) => {
+                let value = digits.iter().fold(0u32, |n, d| n * 8 + (d - b'0') as u32);
+                bytes.push(value as u8);
+                i += 4;
+            }
+            None => {
+                bytes.push(field[i]);
+                i += 1;
+            }
+        }
+    }
+    OsString::from_vec(bytes)
+}
+
+/// Return the mounts visible to this process, parsed from
+/// `/proc/self/mountinfo`, in mount order.
+pub fn mounts() -> io::Result<Vec<MountEntry>> {
+    let mut buf = Vec::new();
+    OpenOptions::new()
+        .read(true)
+        .open("/proc/self/mountinfo")?
+        .read_to_end(&mut buf)?;
+    buf.split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(MountEntry::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::ffi::OsStrExt;
+
+    #[test]
+    fn parse_mount_entry() {
+        let line = b"36 35 98:0 /mnt1 /mnt2 rw,noatime - ext3 /dev/root rw,errors=continue";
+        let entry = MountEntry::parse(line).unwrap();
+        assert_eq!(
+            entry,
+            MountEntry {
+                mount_id: 36,
+                parent_id: 35,
+                major: 98,
+                minor: 0,
+                root: "/mnt1".into(),
+                mount_point: "/mnt2".into(),
+                mount_options: vec!["rw".into(), "noatime".into()],
+                optional_fields: vec![],
+                fs_type: "ext3".into(),
+                source: "/dev/root".into(),
+                super_options: vec!["rw".into(), "errors=continue".into()],
+            }
+        );
+        assert!(entry.has_option("noatime"));
+        assert!(!entry.has_option("ro"));
+    }
+
+    #[test]
+    fn optional_fields() {
+        let line =
+            b"25 1 0:22 / /sys rw shared:7 master:1 propagate_from:2 unbindable - sysfs sysfs rw";
+        let entry = MountEntry::parse(line).unwrap();
+        assert_eq!(
+            entry.optional_fields,
+            ["shared:7", "master:1", "propagate_from:2", "unbindable"]
+        );
+        assert_eq!(entry.fs_type, "sysfs");
+    }
+
+    #[test]
+    fn escaped_fields() {
+        let line = b"40 25 8:1 / /media/my\\040disk\\134x ro - vfat /dev/\xff\\011 ro";
+        let entry = MountEntry::parse(line).unwrap();
+        assert_eq!(entry.mount_point, Path::new("/media/my disk\\x"));
+        // A non-UTF-8 source survives as bytes
+        assert_eq!(entry.source.as_bytes(), b"/dev/\xff\t");
+
+        // Only a backslash followed by three octal digits is an escape
+        assert_eq!(unescape(b"a\\08b\\"), "a\\08b\\");
+        assert_eq!(unescape(b"\\04"), "\\04");
+    }
+
+    #[test]
+    fn malformed_lines() {
+        for line in [
+            &b""[..],
+            b"36",
+            b"36 35 98:0 /mnt1 /mnt2 rw",
+            b"36 35 98:0 /mnt1 /mnt2 rw shared:1",
+            b"36 35 98:0 /mnt1 /mnt2 rw -",
+            b"36 35 98:0 /mnt1 /mnt2 rw - ext3 /dev/root",
+            b"36 35 980 /mnt1 /mnt2 rw - ext3 /dev/root rw",
+            b"x 35 98:0 /mnt1 /mnt2 rw - ext3 /dev/root rw",
+        ] {
+            let err = MountEntry::parse(line).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn mounts_include_root() {
+        let mounts = mounts().unwrap();
+        assert!(mounts.iter().any(|m| m.mount_point == Path::new("/")));
+    }
+}
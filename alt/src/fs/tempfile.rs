@@ -0,0 +1,363 @@
+use super::File;
+use crate::os::fd::{AsRawFd, RawFd};
+use crate::sys::fs as fs_imp;
+use std::env;
+use std::error;
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+
+fn template(dir: &Path) -> PathBuf {
+    dir.join(".tmpXXXXXX")
+}
+
+/// Create an anonymous temporary file in `env::temp_dir()`.
+///
+/// The file has no name, so it disappears once the last descriptor for it
+/// is closed, even if the process is killed.
+pub fn tempfile() -> io::Result<File> {
+    tempfile_in(env::temp_dir())
+}
+
+/// Create an anonymous temporary file in `dir`. `O_TMPFILE` is used where
+/// the filesystem supports it; otherwise a file is created with `mkstemp()`
+/// and immediately unlinked.
+pub fn tempfile_in<P: AsRef<Path>>(dir: P) -> io::Result<File> {
+    let dir = dir.as_ref();
+    match fs_imp::open_tmpfile(dir, 0o600) {
+        Ok(inner) => return Ok(File { inner }),
+        Err(e) => match e.raw_os_error() {
+            Some(libc::EOPNOTSUPP | libc::EISDIR | libc::ENOENT) => {}
+            _ => return Err(e),
+        },
+    }
+
+    let (inner, path) = fs_imp::mkstemp(&template(dir))?;
+    fs_imp::unlink(&path)?;
+    Ok(File { inner })
+}
+
+/// Deletes the file at `path`, if any, when dropped.
+struct TempPath {
+    path: PathBuf,
+}
+
+impl TempPath {
+    fn keep(mut self) -> PathBuf {
+        mem::take(&mut self.path)
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if !self.path.as_os_str().is_empty() {
+            let _ = fs_imp::unlink(&self.path);
+        }
+    }
+}
+
+/// A temporary file with a name, created with `mkstemp()` and removed when
+/// dropped unless it is persisted or kept.
+pub struct NamedTempFile {
+    path: TempPath,
+    file: File,
+}
+
+impl NamedTempFile {
+    pub fn new() -> io::Result<NamedTempFile> {
+        NamedTempFile::new_in(env::temp_dir())
+    }
+
+    pub fn new_in<P: AsRef<Path>>(dir: P) -> io::Result<NamedTempFile> {
+        let (inner, path) = fs_imp::mkstemp(&template(dir.as_ref()))?;
+        Ok(NamedTempFile {
+            path: TempPath { path },
+            file: File { inner },
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path.path
+    }
+
+    pub fn as_file(&self) -> &File {
+        &self.file
+    }
+
+    pub fn as_file_mut(&mut self) -> &mut File {
+        &mut self.file
+    }
+
+    /// Move the file to `new_path` with `rename()`, atomically replacing any
+    /// file already there. Create the temporary file in the same directory
+    /// as `new_path`, as the rename cannot cross filesystems.
+    ///
+    /// On failure the temporary file is handed back with the error, so the
+    /// caller can retry.
+    pub fn persist<P: AsRef<Path>>(self, new_path: P) -> Result<File, PersistError> {
+        match fs_imp::rename(&self.path.path, new_path.as_ref()) {
+            Ok(()) => {
+                let NamedTempFile { path, file } = self;
+                path.keep();
+                Ok(file)
+            }
+            Err(error) => Err(PersistError { error, file: self }),
+        }
+    }
+
+    /// Like `persist`, but fail with `EEXIST` instead of replacing an
+    /// existing file. The file is linked to `new_path` with `linkat()` and
+    /// its temporary name then removed.
+    pub fn persist_noclobber<P: AsRef<Path>>(self, new_path: P) -> Result<File, PersistError> {
+        match fs_imp::link(&self.path.path, new_path.as_ref()) {
+            Ok(()) => {
+                let NamedTempFile { path, file } = self;
+                drop(path);
+                Ok(file)
+            }
+            Err(error) => Err(PersistError { error, file: self }),
+        }
+    }
+
+    /// Stop the file from being removed on drop, returning it and its path.
+    pub fn keep(self) -> (File, PathBuf) {
+        let NamedTempFile { path, file } = self;
+        (file, path.keep())
+    }
+}
+
+impl Read for NamedTempFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+}
+
+impl Write for NamedTempFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Seek for NamedTempFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.file.seek(pos)
+    }
+}
+
+impl AsRawFd for NamedTempFile {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+/// The error from `NamedTempFile::persist`, which still owns the
+/// temporary file.
+pub struct PersistError {
+    pub error: io::Error,
+    pub file: NamedTempFile,
+}
+
+impl fmt::Debug for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistError")
+            .field("error", &self.error)
+            .field("path", &self.file.path())
+            .finish()
+    }
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to persist temporary file: {}", self.error)
+    }
+}
+
+impl error::Error for PersistError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<PersistError> for io::Error {
+    fn from(err: PersistError) -> io::Error {
+        err.error
+    }
+}
+
+/// A directory created with `mkdtemp()` and removed, along with its
+/// contents, when dropped.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new() -> io::Result<TempDir> {
+        TempDir::new_in(env::temp_dir())
+    }
+
+    pub fn new_in<P: AsRef<Path>>(dir: P) -> io::Result<TempDir> {
+        let path = fs_imp::mkdtemp(&template(dir.as_ref()))?;
+        Ok(TempDir { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Stop the directory from being removed on drop, returning its path.
+    pub fn keep(mut self) -> PathBuf {
+        mem::take(&mut self.path)
+    }
+
+    /// Remove the directory now, reporting any error that dropping it would
+    /// ignore.
+    pub fn close(mut self) -> io::Result<()> {
+        let path = mem::take(&mut self.path);
+        fs_imp::remove_dir_all(&path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if !self.path.as_os_str().is_empty() {
+            let _ = fs_imp::remove_dir_all(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::{self, OpenOptions};
+
+    fn touch(path: &Path) {
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(path)
+            .unwrap();
+    }
+
+    fn exists(path: &Path) -> bool {
+        match OpenOptions::new().read(true).open(path) {
+            Ok(_) => true,
+            Err(e) if e.raw_os_error() == Some(libc::ENOENT) => false,
+            Err(e) => panic!("{}: {}", path.display(), e),
+        }
+    }
+
+    fn contents(path: &Path) -> String {
+        let mut buf = String::new();
+        OpenOptions::new()
+            .read(true)
+            .open(path)
+            .unwrap()
+            .read_to_string(&mut buf)
+            .unwrap();
+        buf
+    }
+
+    #[test]
+    fn unnamed() {
+        let dir = TempDir::new().unwrap();
+        let mut file = tempfile_in(dir.path()).unwrap();
+        // Nothing is left in the directory, whichever way the file was made,
+        // so it can be removed
+        fs::remove_dir(dir.path()).unwrap();
+
+        file.write_all(b"scratch").unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut buf = String::new();
+        file.read_to_string(&mut buf).unwrap();
+        assert_eq!(buf, "scratch");
+    }
+
+    #[test]
+    fn named() {
+        let dir = TempDir::new().unwrap();
+        let file = NamedTempFile::new_in(dir.path()).unwrap();
+        let path = file.path().to_path_buf();
+        assert_eq!(path.parent(), Some(dir.path()));
+        assert!(exists(&path));
+        drop(file);
+        assert!(!exists(&path));
+
+        let (_file, path) = NamedTempFile::new_in(dir.path()).unwrap().keep();
+        assert!(exists(&path));
+    }
+
+    #[test]
+    fn persist() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("target");
+        touch(&target);
+
+        let mut file = NamedTempFile::new_in(dir.path()).unwrap();
+        file.write_all(b"new").unwrap();
+        let temp_path = file.path().to_path_buf();
+        file.persist(&target).unwrap();
+        assert_eq!(contents(&target), "new");
+        assert!(!exists(&temp_path));
+
+        // A failed rename hands the file back, still under its temporary name
+        let file = NamedTempFile::new_in(dir.path()).unwrap();
+        let Err(err) = file.persist(dir.path().join("missing/target")) else {
+            panic!("persisted to a missing directory");
+        };
+        assert_eq!(err.error.raw_os_error(), Some(libc::ENOENT));
+        let file = err.file;
+        assert!(exists(file.path()));
+        let retry = dir.path().join("retry");
+        file.persist(&retry).unwrap();
+        assert!(exists(&retry));
+    }
+
+    #[test]
+    fn persist_noclobber() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("target");
+        touch(&target);
+
+        let mut file = NamedTempFile::new_in(dir.path()).unwrap();
+        file.write_all(b"new").unwrap();
+        let Err(err) = file.persist_noclobber(&target) else {
+            panic!("replaced an existing file");
+        };
+        assert_eq!(err.error.raw_os_error(), Some(libc::EEXIST));
+        assert_eq!(contents(&target), "");
+        let io_err = io::Error::from(err);
+        assert_eq!(io_err.raw_os_error(), Some(libc::EEXIST));
+
+        let file = NamedTempFile::new_in(dir.path()).unwrap();
+        let temp_path = file.path().to_path_buf();
+        let other = dir.path().join("other");
+        file.persist_noclobber(&other).unwrap();
+        assert!(exists(&other));
+        assert!(!exists(&temp_path));
+    }
+
+    #[test]
+    fn temp_dir() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().to_path_buf();
+        let sub = TempDir::new_in(&path).unwrap().keep();
+        touch(&sub.join("file"));
+        drop(dir);
+        assert!(!exists(&path));
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().to_path_buf();
+        dir.close().unwrap();
+        assert!(!exists(&path));
+
+        let path = TempDir::new().unwrap().keep();
+        assert!(exists(&path));
+        fs::remove_dir(&path).unwrap();
+    }
+}
@@ -0,0 +1,284 @@
+use crate::os::fd::AsRawFd;
+use crate::sys::fs::xattr::{self as xattr_imp, Target};
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::ops::BitOr;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::Path;
+
+/// Return the value of attribute `name` of `path`, following symbolic
+/// links, or `None` if the file has no such attribute.
+pub fn get<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N) -> io::Result<Option<Vec<u8>>> {
+    optional(xattr_imp::get(Target::Path(path.as_ref()), name.as_ref()))
+}
+
+/// Like `get`, but read an attribute of a symbolic link itself.
+pub fn lget<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N) -> io::Result<Option<Vec<u8>>> {
+    optional(xattr_imp::get(Target::Link(path.as_ref()), name.as_ref()))
+}
+
+pub fn fget<F: AsRawFd, N: AsRef<OsStr>>(fd: &F, name: N) -> io::Result<Option<Vec<u8>>> {
+    optional(xattr_imp::get(Target::Fd(fd.as_raw_fd()), name.as_ref()))
+}
+
+fn optional(result: io::Result<Vec<u8>>) -> io::Result<Option<Vec<u8>>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.raw_os_error() == Some(libc::ENODATA) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Create or replace attribute `name` of `path`, following symbolic links.
+/// `flags` can restrict the call to one or the other.
+pub fn set<P: AsRef<Path>, N: AsRef<OsStr>>(
+    path: P,
+    name: N,
+    value: &[u8],
+    flags: SetFlags,
+) -> io::Result<()> {
+    xattr_imp::set(
+        Target::Path(path.as_ref()),
+        name.as_ref(),
+        value,
+        flags.bits(),
+    )
+}
+
+pub fn lset<P: AsRef<Path>, N: AsRef<OsStr>>(
+    path: P,
+    name: N,
+    value: &[u8],
+    flags: SetFlags,
+) -> io::Result<()> {
+    xattr_imp::set(
+        Target::Link(path.as_ref()),
+        name.as_ref(),
+        value,
+        flags.bits(),
+    )
+}
+
+pub fn fset<F: AsRawFd, N: AsRef<OsStr>>(
+    fd: &F,
+    name: N,
+    value: &[u8],
+    flags: SetFlags,
+) -> io::Result<()> {
+    xattr_imp::set(
+        Target::Fd(fd.as_raw_fd()),
+        name.as_ref(),
+        value,
+        flags.bits(),
+    )
+}
+
+/// Return the names of the attributes of `path` that the caller may see:
+/// `trusted.*` names, for example, are only listed for privileged
+/// processes.
+pub fn list<P: AsRef<Path>>(path: P) -> io::Result<Names> {
+    xattr_imp::list(Target::Path(path.as_ref())).map(Names::new)
+}
+
+pub fn llist<P: AsRef<Path>>(path: P) -> io::Result<Names> {
+    xattr_imp::list(Target::Link(path.as_ref())).map(Names::new)
+}
+
+pub fn flist<F: AsRawFd>(fd: &F) -> io::Result<Names> {
+    xattr_imp::list(Target::Fd(fd.as_raw_fd())).map(Names::new)
+}
+
+/// Remove attribute `name`, failing with `ENODATA` if it does not exist.
+pub fn remove<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N) -> io::Result<()> {
+    xattr_imp::remove(Target::Path(path.as_ref()), name.as_ref())
+}
+
+pub fn lremove<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N) -> io::Result<()> {
+    xattr_imp::remove(Target::Link(path.as_ref()), name.as_ref())
+}
+
+pub fn fremove<F: AsRawFd, N: AsRef<OsStr>>(fd: &F, name: N) -> io::Result<()> {
+    xattr_imp::remove(Target::Fd(fd.as_raw_fd()), name.as_ref())
+}
+
+/// The attribute names returned by `list`.
+pub struct Names {
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl Names {
+    fn new(buf: Vec<u8>) -> Names {
+        Names { buf, pos: 0 }
+    }
+}
+
+impl Iterator for Names {
+    type Item = OsString;
+
+    fn next(&mut self) -> Option<OsString> {
+        let rest = self.buf.get(self.pos..).filter(|rest| !rest.is_empty())?;
+        let len = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+        self.pos += len + 1;
+        Some(OsString::from_vec(rest[..len].to_vec()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SetFlags(i32);
+
+impl SetFlags {
+    /// Fail with `EEXIST` if the attribute already exists.
+    pub const CREATE: SetFlags = SetFlags(libc::XATTR_CREATE);
+    /// Fail with `ENODATA` if the attribute does not exist.
+    pub const REPLACE: SetFlags = SetFlags(libc::XATTR_REPLACE);
+
+    pub const fn empty() -> SetFlags {
+        SetFlags(0)
+    }
+
+    pub const fn bits(self) -> i32 {
+        self.0
+    }
+
+    pub const fn contains(self, other: SetFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for SetFlags {
+    type Output = SetFlags;
+
+    fn bitor(self, rhs: SetFlags) -> SetFlags {
+        SetFlags(self.0 | rhs.0)
+    }
+}
+
+/// The namespace of an attribute, given by the prefix of its name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Namespace {
+    /// Arbitrary attributes, subject to the file permissions; only
+    /// allowed on regular files and directories.
+    User,
+    /// Like `User`, but only accessible with `CAP_SYS_ADMIN`.
+    Trusted,
+    /// Used by security modules, and for file capabilities.
+    Security,
+    /// Used by the kernel itself, such as for access control lists.
+    System,
+}
+
+impl Namespace {
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Namespace::User => "user.",
+            Namespace::Trusted => "trusted.",
+            Namespace::Security => "security.",
+            Namespace::System => "system.",
+        }
+    }
+}
+
+/// Split an attribute name into its namespace and the rest of the name,
+/// returning `None` if the namespace is not one the kernel recognizes.
+pub fn split_name(name: &OsStr) -> Option<(Namespace, &OsStr)> {
+    let name = name.as_bytes();
+    [
+        Namespace::User,
+        Namespace::Trusted,
+        Namespace::Security,
+        Namespace::System,
+    ]
+    .into_iter()
+    .find_map(|ns| {
+        let rest = name.strip_prefix(ns.prefix().as_bytes())?;
+        Some((ns, OsStr::from_bytes(rest)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::NamedTempFile;
+
+    #[test]
+    fn split_names() {
+        fn split(name: &str) -> Option<(Namespace, &OsStr)> {
+            split_name(OsStr::new(name))
+        }
+        assert_eq!(
+            split("user.mime_type"),
+            Some((Namespace::User, OsStr::new("mime_type")))
+        );
+        assert_eq!(
+            split("trusted.x"),
+            Some((Namespace::Trusted, OsStr::new("x")))
+        );
+        assert_eq!(
+            split("security.capability"),
+            Some((Namespace::Security, OsStr::new("capability")))
+        );
+        assert_eq!(
+            split("system.posix_acl_access"),
+            Some((Namespace::System, OsStr::new("posix_acl_access")))
+        );
+        assert_eq!(split("user."), Some((Namespace::User, OsStr::new(""))));
+        assert_eq!(split("user"), None);
+        assert_eq!(split("other.x"), None);
+        assert_eq!(split("User.x"), None);
+    }
+
+    #[test]
+    fn names() {
+        let names: Vec<OsString> =
+            Names::new(b"user.a\0security.selinux\0user.b\0".to_vec()).collect();
+        assert_eq!(names, ["user.a", "security.selinux", "user.b"]);
+        assert_eq!(Names::new(Vec::new()).count(), 0);
+        // Tolerate a list that is missing its final NUL
+        let names: Vec<OsString> = Names::new(b"user.a\0user.b".to_vec()).collect();
+        assert_eq!(names, ["user.a", "user.b"]);
+    }
+
+    #[test]
+    fn missing_attribute() {
+        assert_eq!(optional(Ok(b"v".to_vec())).unwrap(), Some(b"v".to_vec()));
+        let enodata = io::Error::from_raw_os_error(libc::ENODATA);
+        assert_eq!(optional(Err(enodata)).unwrap(), None);
+        let eacces = io::Error::from_raw_os_error(libc::EPERM);
+        assert_eq!(
+            optional(Err(eacces)).unwrap_err().raw_os_error(),
+            Some(libc::EPERM)
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let file = NamedTempFile::new().unwrap();
+        match set(file.path(), "user.x", b"one", SetFlags::empty()) {
+            Err(e) if e.raw_os_error() == Some(libc::ENOTSUP) => return,
+            result => result.unwrap(),
+        }
+        assert_eq!(get(file.path(), "user.x").unwrap(), Some(b"one".to_vec()));
+        assert_eq!(get(file.path(), "user.missing").unwrap(), None);
+
+        let err = set(file.path(), "user.x", b"two", SetFlags::CREATE).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EEXIST));
+        let err = fset(file.as_file(), "user.y", b"", SetFlags::REPLACE).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENODATA));
+        fset(file.as_file(), "user.y", b"", SetFlags::CREATE).unwrap();
+        assert_eq!(fget(file.as_file(), "user.y").unwrap(), Some(Vec::new()));
+
+        let mut names: Vec<OsString> = flist(file.as_file())
+            .unwrap()
+            .filter(|name| matches!(split_name(name), Some((Namespace::User, _))))
+            .collect();
+        names.sort();
+        assert_eq!(names, ["user.x", "user.y"]);
+
+        remove(file.path(), "user.x").unwrap();
+        fremove(file.as_file(), "user.y").unwrap();
+        assert_eq!(get(file.path(), "user.x").unwrap(), None);
+        let err = remove(file.path(), "user.x").unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENODATA));
+    }
+}
@@ -0,0 +1,7 @@
+mod buffered;
+mod copy;
+mod stdio;
+
+pub use buffered::*;
+pub use copy::*;
+pub use stdio::*;
@@ -0,0 +1,415 @@
+use std::cmp;
+use std::io::{self, BufRead, Read, Seek, SeekFrom, Write};
+
+/// Same as glibc's `BUFSIZ`.
+pub const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+
+const LINE_BUF_SIZE: usize = 1024;
+
+/// The buffering modes of `setvbuf()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BufferMode {
+    /// Write when the buffer fills (`_IOFBF`).
+    Full,
+    /// Additionally write whenever a newline is written (`_IOLBF`).
+    Line,
+    /// Pass every write straight through (`_IONBF`).
+    Unbuffered,
+}
+
+pub struct BufReader<R> {
+    inner: R,
+    buf: Box<[u8]>,
+    pos: usize,
+    filled: usize,
+}
+
+impl<R: Read> BufReader<R> {
+    pub fn new(inner: R) -> BufReader<R> {
+        BufReader::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+}
+
+impl<R> BufReader<R> {
+    pub fn with_capacity(capacity: usize, inner: R) -> BufReader<R> {
+        BufReader {
+            inner,
+            buf: vec![0; capacity].into_boxed_slice(),
+            pos: 0,
+            filled: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the reader. Any data still in the buffer is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..self.filled]
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    fn discard_buffer(&mut self) {
+        self.pos = 0;
+        self.filled = 0;
+    }
+}
+
+impl<R: Read> Read for BufReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Bypass our buffer entirely for reads at least as large as it
+        if self.pos == self.filled && buf.len() >= self.capacity() {
+            self.discard_buffer();
+            return self.inner.read(buf);
+        }
+        let n = {
+            let mut rem = self.fill_buf()?;
+            rem.read(buf)?
+        };
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: Read> BufRead for BufReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos >= self.filled {
+            self.filled = self.inner.read(&mut self.buf)?;
+            self.pos = 0;
+        }
+        Ok(self.buffer())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = cmp::min(self.pos + amt, self.filled);
+    }
+}
+
+impl<R: Seek> Seek for BufReader<R> {
+    /// Seeking always discards the buffer. `SeekFrom::Current` is relative
+    /// to the position of the next byte this reader would return, not to
+    /// the underlying file offset.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let result = if let SeekFrom::Current(n) = pos {
+            let remainder = (self.filled - self.pos) as i64;
+            let offset = n.checked_sub(remainder).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "seek offset overflowed")
+            })?;
+            self.inner.seek(SeekFrom::Current(offset))?
+        } else {
+            self.inner.seek(pos)?
+        };
+        self.discard_buffer();
+        Ok(result)
+    }
+}
+
+/// A writer that collects output in user space before passing it to the
+/// wrapped writer, in the manner of a stdio stream configured by
+/// `setvbuf()`.
+///
+/// Buffered data is written out when the buffer is flushed or dropped. Like
+/// a stdio buffer, it lives in process memory and so is duplicated by
+/// `fork()`: if both processes later flush, the output appears twice. Call
+/// `flush()` before forking, or `discard_buffer()` in the child.
+pub struct BufWriter<W: Write> {
+    inner: Option<W>,
+    buf: Vec<u8>,
+    capacity: usize,
+    mode: BufferMode,
+    // Set while the inner writer is being called, so that a panic in it
+    // does not make us write the same data again while unwinding.
+    panicked: bool,
+}
+
+impl<W: Write> BufWriter<W> {
+    pub fn new(inner: W) -> BufWriter<W> {
+        BufWriter::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    pub fn with_capacity(capacity: usize, inner: W) -> BufWriter<W> {
+        BufWriter::with_mode(BufferMode::Full, capacity, inner)
+    }
+
+    pub fn with_mode(mode: BufferMode, capacity: usize, inner: W) -> BufWriter<W> {
+        BufWriter {
+            inner: Some(inner),
+            buf: Vec::with_capacity(capacity),
+            capacity,
+            mode,
+            panicked: false,
+        }
+    }
+
+    pub fn mode(&self) -> BufferMode {
+        self.mode
+    }
+
+    /// Change the buffering mode, first writing out anything buffered.
+    pub fn set_mode(&mut self, mode: BufferMode) -> io::Result<()> {
+        self.flush_buf()?;
+        self.mode = mode;
+        Ok(())
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().unwrap()
+    }
+
+    /// Writing to the returned writer directly bypasses the buffer, so
+    /// output may appear out of order.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.as_mut().unwrap()
+    }
+
+    /// Write out the buffer and unwrap the writer. If the buffer cannot be
+    /// written, both it and the writer are lost.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.flush_buf()?;
+        Ok(self.inner.take().unwrap())
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Throw away buffered data without writing it, e.g. in the child after
+    /// a `fork()` so that only the parent emits it.
+    pub fn discard_buffer(&mut self) {
+        self.buf.clear();
+    }
+
+    fn flush_buf(&mut self) -> io::Result<()> {
+        let inner = self.inner.as_mut().unwrap();
+        let mut written = 0;
+        let mut ret = Ok(());
+        while written < self.buf.len() {
+            self.panicked = true;
+            let r = inner.write(&self.buf[written..]);
+            self.panicked = false;
+
+            match r {
+                Ok(0) => {
+                    ret = Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the buffered data",
+                    ));
+                    break;
+                }
+                Ok(n) => written += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    ret = Err(e);
+                    break;
+                }
+            }
+        }
+        self.buf.drain(..written);
+        ret
+    }
+
+    fn write_to_buf(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.buf.len() + buf.len() > self.capacity {
+            self.flush_buf()?;
+        }
+        if buf.len() >= self.capacity {
+            self.panicked = true;
+            let r = self.get_mut().write(buf);
+            self.panicked = false;
+            r
+        } else {
+            self.buf.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+    }
+}
+
+impl<W: Write> Write for BufWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.mode {
+            BufferMode::Full => self.write_to_buf(buf),
+            BufferMode::Line => {
+                let n = self.write_to_buf(buf)?;
+                if buf[..n].contains(&b'\n') {
+                    self.flush_buf()?;
+                }
+                Ok(n)
+            }
+            BufferMode::Unbuffered => {
+                self.flush_buf()?;
+                self.get_mut().write(buf)
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_buf()?;
+        self.get_mut().flush()
+    }
+}
+
+impl<W: Write + Seek> Seek for BufWriter<W> {
+    /// Seeking always writes out the buffer first.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.flush_buf()?;
+        self.get_mut().seek(pos)
+    }
+}
+
+impl<W: Write> Drop for BufWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() && !self.panicked {
+            // dtors should not panic, so we ignore a failed flush
+            let _r = self.flush_buf();
+        }
+    }
+}
+
+/// A `BufWriter` in `BufferMode::Line`, as stdio uses for terminals.
+pub struct LineWriter<W: Write>(BufWriter<W>);
+
+impl<W: Write> LineWriter<W> {
+    pub fn new(inner: W) -> LineWriter<W> {
+        LineWriter::with_capacity(LINE_BUF_SIZE, inner)
+    }
+
+    pub fn with_capacity(capacity: usize, inner: W) -> LineWriter<W> {
+        LineWriter(BufWriter::with_mode(BufferMode::Line, capacity, inner))
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.0.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        self.0.get_mut()
+    }
+
+    pub fn into_inner(self) -> io::Result<W> {
+        self.0.into_inner()
+    }
+
+    pub fn discard_buffer(&mut self) {
+        self.0.discard_buffer()
+    }
+}
+
+impl<W: Write> Write for LineWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::{self, File};
+
+    fn written(mut file: &File) -> u64 {
+        file.seek(SeekFrom::End(0)).unwrap()
+    }
+
+    #[test]
+    fn full_buffering() {
+        let file = fs::tempfile().unwrap();
+        let mut writer = BufWriter::with_capacity(8, &file);
+        writer.write_all(b"abc\n").unwrap();
+        assert_eq!((written(&file), writer.buffer()), (0, &b"abc\n"[..]));
+        // Overflowing the buffer writes out what was buffered first
+        writer.write_all(b"defgh").unwrap();
+        assert_eq!((written(&file), writer.buffer()), (4, &b"defgh"[..]));
+        // A write at least as large as the buffer bypasses it
+        writer.write_all(b"0123456789").unwrap();
+        assert_eq!(written(&file), 19);
+        writer.flush().unwrap();
+        assert!(writer.buffer().is_empty());
+
+        writer.write_all(b"xyz").unwrap();
+        drop(writer);
+        assert_eq!(written(&file), 22);
+    }
+
+    #[test]
+    fn line_buffering() {
+        let file = fs::tempfile().unwrap();
+        let mut writer = LineWriter::new(&file);
+        writer.write_all(b"partial").unwrap();
+        assert_eq!(written(&file), 0);
+        writer.write_all(b" line\nand more").unwrap();
+        // stdio writes out the whole buffer, not just up to the newline
+        assert_eq!(written(&file), 21);
+        writer.write_all(b"!").unwrap();
+        assert_eq!(written(&file), 21);
+        writer.into_inner().unwrap();
+        assert_eq!(written(&file), 22);
+    }
+
+    #[test]
+    fn unbuffered() {
+        let file = fs::tempfile().unwrap();
+        let mut writer = BufWriter::with_mode(BufferMode::Full, 64, &file);
+        writer.write_all(b"held").unwrap();
+        // Switching mode writes out what was buffered
+        writer.set_mode(BufferMode::Unbuffered).unwrap();
+        assert_eq!(written(&file), 4);
+        writer.write_all(b"x").unwrap();
+        assert_eq!(written(&file), 5);
+        assert_eq!(writer.mode(), BufferMode::Unbuffered);
+    }
+
+    #[test]
+    fn discard_buffer() {
+        let file = fs::tempfile().unwrap();
+        let mut writer = BufWriter::new(&file);
+        writer.write_all(b"duplicated after fork()").unwrap();
+        writer.discard_buffer();
+        drop(writer);
+        assert_eq!(written(&file), 0);
+    }
+
+    #[test]
+    fn reader_seek() {
+        let mut file = fs::tempfile().unwrap();
+        file.write_all(b"0123456789abcdef").unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut reader = BufReader::with_capacity(4, &file);
+
+        let mut buf = [0; 2];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!((&buf, reader.buffer()), (b"01", &b"23"[..]));
+        // Relative to the next byte the reader would return, not the file
+        assert_eq!(reader.seek(SeekFrom::Current(3)).unwrap(), 5);
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"56");
+        assert_eq!(reader.seek(SeekFrom::Current(-7)).unwrap(), 0);
+
+        reader.read_exact(&mut buf).unwrap();
+        let err = reader.seek(SeekFrom::Current(i64::MIN)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let mut rest = String::new();
+        reader.seek(SeekFrom::End(-4)).unwrap();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "cdef");
+    }
+}
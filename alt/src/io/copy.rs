@@ -0,0 +1,295 @@
+use crate::fs::File;
+use crate::limits;
+use crate::os::fd::AsRawFd;
+use crate::sys::io as io_imp;
+use libc::c_uint;
+use std::io::{self, Read, Write};
+use std::ops::BitOr;
+
+/// The largest transfer the kernel performs in a single call (`MAX_RW_COUNT`).
+const MAX_CHUNK: usize = 0x7fff_f000;
+
+/// The least buffer used when falling back to `read()`/`write()`.
+const FALLBACK_BUF_SIZE: usize = 128 * 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SpliceFlags(c_uint);
+
+impl SpliceFlags {
+    pub const MOVE: SpliceFlags = SpliceFlags(libc::SPLICE_F_MOVE);
+    pub const NONBLOCK: SpliceFlags = SpliceFlags(libc::SPLICE_F_NONBLOCK);
+    pub const MORE: SpliceFlags = SpliceFlags(libc::SPLICE_F_MORE);
+    pub const GIFT: SpliceFlags = SpliceFlags(libc::SPLICE_F_GIFT);
+
+    pub const fn empty() -> SpliceFlags {
+        SpliceFlags(0)
+    }
+
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(self, other: SpliceFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for SpliceFlags {
+    type Output = SpliceFlags;
+
+    fn bitor(self, rhs: SpliceFlags) -> SpliceFlags {
+        SpliceFlags(self.0 | rhs.0)
+    }
+}
+
+/// Copy up to `len` bytes between two files inside the kernel.
+///
+/// When an offset is `None` the file offset of that descriptor is used and
+/// advanced; otherwise the offset is read and updated in place, leaving the
+/// file offset untouched.
+pub fn copy_file_range<R: AsRawFd, W: AsRawFd>(
+    fd_in: &R,
+    off_in: Option<&mut u64>,
+    fd_out: &W,
+    off_out: Option<&mut u64>,
+    len: usize,
+) -> io::Result<usize> {
+    io_imp::copy_file_range(fd_in.as_raw_fd(), off_in, fd_out.as_raw_fd(), off_out, len)
+}
+
+pub fn sendfile<W: AsRawFd, R: AsRawFd>(
+    out_fd: &W,
+    in_fd: &R,
+    offset: Option<&mut u64>,
+    count: usize,
+) -> io::Result<usize> {
+    io_imp::sendfile(out_fd.as_raw_fd(), in_fd.as_raw_fd(), offset, count)
+}
+
+/// Move data between two descriptors, at least one of which must be a pipe.
+pub fn splice<R: AsRawFd, W: AsRawFd>(
+    fd_in: &R,
+    off_in: Option<&mut u64>,
+    fd_out: &W,
+    off_out: Option<&mut u64>,
+    len: usize,
+    flags: SpliceFlags,
+) -> io::Result<usize> {
+    io_imp::splice(
+        fd_in.as_raw_fd(),
+        off_in,
+        fd_out.as_raw_fd(),
+        off_out,
+        len,
+        flags.bits(),
+    )
+}
+
+/// Duplicate data from one pipe to another without consuming it.
+pub fn tee<R: AsRawFd, W: AsRawFd>(
+    fd_in: &R,
+    fd_out: &W,
+    len: usize,
+    flags: SpliceFlags,
+) -> io::Result<usize> {
+    io_imp::tee(fd_in.as_raw_fd(), fd_out.as_raw_fd(), len, flags.bits())
+}
+
+/// Copy the rest of `reader` to `writer`, starting at their current file
+/// offsets, and return the number of bytes copied.
+///
+/// `copy_file_range()` is tried first, then `sendfile()`, and finally a plain
+/// `read()`/`write()` loop. A method is only abandoned if its first call
+/// fails (or, for `copy_file_range()`, copies nothing, as happens for files
+/// in procfs and sysfs), so a fallback never repeats data already copied.
+pub fn copy(reader: &File, writer: &File) -> io::Result<u64> {
+    let mut written = 0u64;
+
+    loop {
+        match copy_file_range(reader, None, writer, None, MAX_CHUNK) {
+            Ok(0) if written == 0 => break,
+            Ok(0) => return Ok(written),
+            Ok(n) => written += n as u64,
+            Err(e) if written == 0 && is_unsupported(&e) => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    loop {
+        match sendfile(writer, reader, None, MAX_CHUNK) {
+            Ok(0) => return Ok(written),
+            Ok(n) => written += n as u64,
+            Err(e) if written == 0 && is_unsupported(&e) => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut reader = reader;
+    let mut writer = writer;
+    let mut buf = vec![0; fallback_buf_size(reader, writer)];
+    loop {
+        let num_read = match reader.read(&mut buf) {
+            Ok(0) => return Ok(written),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_all(&buf[..num_read])?;
+        written += num_read as u64;
+    }
+}
+
+/// Size the fallback buffer as a whole number of the larger preferred I/O
+/// block size (`st_blksize`) of the two files, using the page size for a
+/// file that reports none, so that each `read()` and `write()` covers
+/// complete blocks.
+fn fallback_buf_size(reader: &File, writer: &File) -> usize {
+    let blksize = |file: &File| {
+        file.metadata()
+            .map(|attr| attr.blksize() as usize)
+            .ok()
+            .filter(|&size| size > 0)
+            .unwrap_or_else(limits::page_size)
+    };
+    let block = blksize(reader).max(blksize(writer));
+    FALLBACK_BUF_SIZE.div_ceil(block) * block
+}
+
+fn is_unsupported(err: &io::Error) -> bool {
+    matches!(
+        err.raw_os_error(),
+        Some(
+            libc::ENOSYS
+                | libc::EXDEV
+                | libc::EINVAL
+                | libc::EOPNOTSUPP
+                | libc::EPERM
+                | libc::EBADF
+        )
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs;
+    use crate::os::fd::FromRawFd;
+    use std::io::{Seek, SeekFrom};
+    use std::thread;
+
+    fn pipe() -> (File, File) {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) }
+    }
+
+    fn pattern(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn contents(mut file: &File) -> Vec<u8> {
+        let mut buf = Vec::new();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.read_to_end(&mut buf).unwrap();
+        buf
+    }
+
+    fn temp_with(data: &[u8]) -> File {
+        let mut file = fs::tempfile().unwrap();
+        file.write_all(data).unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file
+    }
+
+    #[test]
+    fn copy_between_files() {
+        let data = pattern(300 * 1024);
+        let (mut src, dst) = (temp_with(&data), fs::tempfile().unwrap());
+        assert_eq!(copy(&src, &dst).unwrap(), data.len() as u64);
+        assert_eq!(contents(&dst), data);
+
+        // Only the rest of the input, from its file offset, is copied
+        let dst = fs::tempfile().unwrap();
+        src.seek(SeekFrom::Start(1000)).unwrap();
+        assert_eq!(copy(&src, &dst).unwrap(), data.len() as u64 - 1000);
+        assert_eq!(contents(&dst), &data[1000..]);
+        assert_eq!(copy(&src, &dst).unwrap(), 0);
+    }
+
+    #[test]
+    fn copy_from_pipe() {
+        // copy_file_range() cannot read from a pipe, so this exercises the
+        // fallbacks
+        let data = pattern(200 * 1024);
+        let (reader, mut writer) = pipe();
+        let sender = {
+            let data = data.clone();
+            thread::spawn(move || writer.write_all(&data).unwrap())
+        };
+        let dst = fs::tempfile().unwrap();
+        assert_eq!(copy(&reader, &dst).unwrap(), data.len() as u64);
+        sender.join().unwrap();
+        assert_eq!(contents(&dst), data);
+    }
+
+    #[test]
+    fn copy_to_pipe() {
+        let data = pattern(200 * 1024);
+        let src = temp_with(&data);
+        let (mut reader, writer) = pipe();
+        let receiver = thread::spawn(move || {
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf).unwrap();
+            buf
+        });
+        assert_eq!(copy(&src, &writer).unwrap(), data.len() as u64);
+        drop(writer);
+        assert_eq!(receiver.join().unwrap(), data);
+    }
+
+    #[test]
+    fn explicit_offsets() {
+        let data = pattern(4096);
+        let (src, dst) = (temp_with(&data), fs::tempfile().unwrap());
+        let (mut off_in, mut off_out) = (100, 0);
+        let n = match copy_file_range(&src, Some(&mut off_in), &dst, Some(&mut off_out), 1000) {
+            Err(e) if is_unsupported(&e) => return,
+            result => result.unwrap(),
+        };
+        assert_eq!((n, off_in, off_out), (1000, 1100, 1000));
+        assert_eq!((&src).stream_position().unwrap(), 0);
+        assert_eq!(contents(&dst), &data[100..1100]);
+
+        let dst = fs::tempfile().unwrap();
+        let mut offset = 4000;
+        assert_eq!(sendfile(&dst, &src, Some(&mut offset), 1000).unwrap(), 96);
+        assert_eq!(offset, 4096);
+        assert_eq!(contents(&dst), &data[4000..]);
+    }
+
+    #[test]
+    fn splice_and_tee() {
+        let data = pattern(1000);
+        let src = temp_with(&data);
+        let (reader, writer) = pipe();
+        let (copy_reader, copy_writer) = pipe();
+
+        let mut offset = 10;
+        let flags = SpliceFlags::empty();
+        assert_eq!(
+            splice(&src, Some(&mut offset), &writer, None, 100, flags).unwrap(),
+            100
+        );
+        assert_eq!(offset, 110);
+        assert_eq!(tee(&reader, &copy_writer, 100, flags).unwrap(), 100);
+        drop((writer, copy_writer));
+
+        let mut buf = Vec::new();
+        (&copy_reader).read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, &data[10..110]);
+        // tee() leaves the data in the first pipe
+        buf.clear();
+        (&reader).read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, &data[10..110]);
+    }
+}
@@ -0,0 +1,347 @@
+use super::{BufReader, BufWriter, BufferMode, DEFAULT_BUF_SIZE};
+use crate::os::fd::{AsRawFd, FromRawFd, RawFd};
+use crate::sys::fd::FileDesc;
+use std::io::{self, BufRead, Read, Write};
+use std::mem::ManuallyDrop;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+const STDIN_FILENO: RawFd = 0;
+const STDOUT_FILENO: RawFd = 1;
+const STDERR_FILENO: RawFd = 2;
+
+/// One of the standard file descriptors, which we use but never close.
+struct StdioRaw(ManuallyDrop<FileDesc>);
+
+impl StdioRaw {
+    fn new(fd: RawFd) -> StdioRaw {
+        StdioRaw(ManuallyDrop::new(unsafe { FileDesc::from_raw_fd(fd) }))
+    }
+}
+
+impl Read for StdioRaw {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for StdioRaw {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+static STDIN: OnceLock<Mutex<BufReader<StdioRaw>>> = OnceLock::new();
+static STDOUT: OnceLock<Mutex<BufWriter<StdioRaw>>> = OnceLock::new();
+static STDERR: OnceLock<Mutex<BufWriter<StdioRaw>>> = OnceLock::new();
+
+pub struct Stdin {
+    inner: &'static Mutex<BufReader<StdioRaw>>,
+}
+
+pub struct StdinLock<'a> {
+    inner: MutexGuard<'a, BufReader<StdioRaw>>,
+}
+
+/// A handle to standard input, buffered with `DEFAULT_BUF_SIZE` bytes.
+///
+/// Bytes already buffered here are not visible to raw reads of fd 0.
+pub fn stdin() -> Stdin {
+    let inner = STDIN.get_or_init(|| Mutex::new(BufReader::new(StdioRaw::new(STDIN_FILENO))));
+    Stdin { inner }
+}
+
+impl Stdin {
+    /// Lock the handle for exclusive use. The lock is not reentrant: taking
+    /// it again on the same thread deadlocks.
+    pub fn lock(&self) -> StdinLock<'static> {
+        StdinLock {
+            inner: lock(self.inner),
+        }
+    }
+}
+
+impl Read for Stdin {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.lock().read(buf)
+    }
+}
+
+impl AsRawFd for Stdin {
+    fn as_raw_fd(&self) -> RawFd {
+        STDIN_FILENO
+    }
+}
+
+impl Read for StdinLock<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for StdinLock<'_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+pub struct Stdout {
+    inner: &'static Mutex<BufWriter<StdioRaw>>,
+}
+
+pub struct StdoutLock<'a> {
+    inner: MutexGuard<'a, BufWriter<StdioRaw>>,
+}
+
+extern "C" fn flush_stdout() {
+    if let Some(stdout) = STDOUT.get() {
+        // Don't deadlock if exit() is called while the lock is held
+        if let Ok(mut stdout) = stdout.try_lock() {
+            let _ = stdout.flush();
+        }
+    }
+}
+
+/// A handle to standard output.
+///
+/// As with stdio, output is line buffered if fd 1 refers to a terminal and
+/// fully buffered otherwise, and anything still buffered is written out when
+/// the process calls `exit()` (including by returning from `main`). Use
+/// `set_mode` to change this, e.g. to interleave output correctly with raw
+/// writes to fd 1.
+pub fn stdout() -> Stdout {
+    let inner = STDOUT.get_or_init(|| {
+        let mode = match unsafe { libc::isatty(STDOUT_FILENO) } {
+            1 => BufferMode::Line,
+            _ => BufferMode::Full,
+        };
+        unsafe {
+            libc::atexit(flush_stdout);
+        }
+        Mutex::new(BufWriter::with_mode(
+            mode,
+            DEFAULT_BUF_SIZE,
+            StdioRaw::new(STDOUT_FILENO),
+        ))
+    });
+    Stdout { inner }
+}
+
+impl Stdout {
+    /// Lock the handle for exclusive use. The lock is not reentrant: taking
+    /// it again on the same thread deadlocks.
+    pub fn lock(&self) -> StdoutLock<'static> {
+        StdoutLock {
+            inner: lock(self.inner),
+        }
+    }
+
+    pub fn set_mode(&self, mode: BufferMode) -> io::Result<()> {
+        self.lock().inner.set_mode(mode)
+    }
+}
+
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&*self).flush()
+    }
+}
+
+impl Write for &Stdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.lock().write_all(buf)
+    }
+
+    fn write_fmt(&mut self, args: std::fmt::Arguments<'_>) -> io::Result<()> {
+        self.lock().write_fmt(args)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.lock().flush()
+    }
+}
+
+impl AsRawFd for Stdout {
+    fn as_raw_fd(&self) -> RawFd {
+        STDOUT_FILENO
+    }
+}
+
+impl Write for StdoutLock<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub struct Stderr {
+    inner: &'static Mutex<BufWriter<StdioRaw>>,
+}
+
+pub struct StderrLock<'a> {
+    inner: MutexGuard<'a, BufWriter<StdioRaw>>,
+}
+
+/// A handle to standard error. It is unbuffered unless `set_mode` says
+/// otherwise, so each write reaches fd 2 immediately.
+pub fn stderr() -> Stderr {
+    let inner = STDERR.get_or_init(|| {
+        Mutex::new(BufWriter::with_mode(
+            BufferMode::Unbuffered,
+            DEFAULT_BUF_SIZE,
+            StdioRaw::new(STDERR_FILENO),
+        ))
+    });
+    Stderr { inner }
+}
+
+impl Stderr {
+    /// Lock the handle for exclusive use. The lock is not reentrant: taking
+    /// it again on the same thread deadlocks.
+    pub fn lock(&self) -> StderrLock<'static> {
+        StderrLock {
+            inner: lock(self.inner),
+        }
+    }
+
+    /// Changing to a buffered mode leaves flushing to the caller; nothing
+    /// is written out at `exit()`.
+    pub fn set_mode(&self, mode: BufferMode) -> io::Result<()> {
+        self.lock().inner.set_mode(mode)
+    }
+}
+
+impl Write for Stderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&*self).flush()
+    }
+}
+
+impl Write for &Stderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.lock().write_all(buf)
+    }
+
+    fn write_fmt(&mut self, args: std::fmt::Arguments<'_>) -> io::Result<()> {
+        self.lock().write_fmt(args)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.lock().flush()
+    }
+}
+
+impl AsRawFd for Stderr {
+    fn as_raw_fd(&self) -> RawFd {
+        STDERR_FILENO
+    }
+}
+
+impl Write for StderrLock<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::File;
+
+    fn raw_write(fd: RawFd, buf: &[u8]) -> bool {
+        unsafe { libc::write(fd, buf.as_ptr().cast(), buf.len()) == buf.len() as isize }
+    }
+
+    fn steps(out: &Stdout, err: &Stderr) -> io::Result<bool> {
+        let (mut out, mut err) = (out, err);
+        let mut ok = true;
+
+        out.set_mode(BufferMode::Full)?;
+        out.write_all(b"1")?;
+        ok &= raw_write(STDOUT_FILENO, b"2");
+        // Standard error is unbuffered, so overtakes the buffered "1"
+        err.write_all(b"3")?;
+        out.flush()?;
+
+        out.set_mode(BufferMode::Line)?;
+        out.write_all(b"4")?;
+        ok &= raw_write(STDOUT_FILENO, b"5");
+        out.write_all(b"6\n")?;
+        ok &= raw_write(STDOUT_FILENO, b"7");
+
+        out.set_mode(BufferMode::Unbuffered)?;
+        out.write_all(b"8")?;
+        ok &= raw_write(STDOUT_FILENO, b"9");
+
+        // Left for the atexit() handler
+        out.set_mode(BufferMode::Full)?;
+        out.write_all(b"a")?;
+        ok &= raw_write(STDERR_FILENO, b"b");
+        Ok(ok)
+    }
+
+    #[test]
+    fn ordering_against_raw_writes() {
+        // Set up the handles before forking, so the child need not allocate
+        let (out, err) = (stdout(), stderr());
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+        let (mut reader, writer) =
+            unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+
+        let pid = unsafe { libc::fork() };
+        assert_ne!(pid, -1);
+        if pid == 0 {
+            let redirected = unsafe {
+                libc::dup2(writer.as_raw_fd(), STDOUT_FILENO) != -1
+                    && libc::dup2(writer.as_raw_fd(), STDERR_FILENO) != -1
+            };
+            if redirected && matches!(steps(&out, &err), Ok(true)) {
+                unsafe { libc::exit(0) };
+            }
+            unsafe { libc::_exit(1) };
+        }
+        drop(writer);
+
+        let mut output = Vec::new();
+        reader.read_to_end(&mut output).unwrap();
+        let mut status = 0;
+        assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+        assert_eq!(status, 0);
+        assert_eq!(String::from_utf8_lossy(&output), "231546\n789ba");
+    }
+}
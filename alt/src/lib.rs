@@ -1,3 +1,12 @@
+pub mod caps;
+pub mod env;
 pub mod fs;
+pub mod io;
+pub mod limits;
+pub mod net;
 pub mod os;
+pub mod process;
 pub mod sys;
+pub mod sys_info;
+pub mod time;
+pub mod users;
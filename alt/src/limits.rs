@@ -0,0 +1,493 @@
+use crate::env;
+use crate::os::fd::AsRawFd;
+use crate::sys::fs::cstr;
+use libc::{c_int, c_long, c_ulong};
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::ptr;
+use std::sync::OnceLock;
+
+/// A system limit or option queried with `sysconf()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SysVar {
+    /// The maximum size in bytes of the arguments plus environment
+    /// passed to `exec()`.
+    ArgMax,
+    /// The maximum number of processes of a real user ID
+    /// (`RLIMIT_NPROC`).
+    ChildMax,
+    /// Clock ticks per second, the unit of `times()`.
+    ClkTck,
+    NgroupsMax,
+    /// The maximum number of open files (`RLIMIT_NOFILE`).
+    OpenMax,
+    StreamMax,
+    PageSize,
+    RtsigMax,
+    SigqueueMax,
+    LineMax,
+    LoginNameMax,
+    TtyNameMax,
+    NprocessorsConf,
+    NprocessorsOnln,
+    PhysPages,
+    AvphysPages,
+    HostNameMax,
+}
+
+impl SysVar {
+    fn as_raw(self) -> c_int {
+        match self {
+            SysVar::ArgMax => libc::_SC_ARG_MAX,
+            SysVar::ChildMax => libc::_SC_CHILD_MAX,
+            SysVar::ClkTck => libc::_SC_CLK_TCK,
+            SysVar::NgroupsMax => libc::_SC_NGROUPS_MAX,
+            SysVar::OpenMax => libc::_SC_OPEN_MAX,
+            SysVar::StreamMax => libc::_SC_STREAM_MAX,
+            SysVar::PageSize => libc::_SC_PAGESIZE,
+            SysVar::RtsigMax => libc::_SC_RTSIG_MAX,
+            SysVar::SigqueueMax => libc::_SC_SIGQUEUE_MAX,
+            SysVar::LineMax => libc::_SC_LINE_MAX,
+            SysVar::LoginNameMax => libc::_SC_LOGIN_NAME_MAX,
+            SysVar::TtyNameMax => libc::_SC_TTY_NAME_MAX,
+            SysVar::NprocessorsConf => libc::_SC_NPROCESSORS_CONF,
+            SysVar::NprocessorsOnln => libc::_SC_NPROCESSORS_ONLN,
+            SysVar::PhysPages => libc::_SC_PHYS_PAGES,
+            SysVar::AvphysPages => libc::_SC_AVPHYS_PAGES,
+            SysVar::HostNameMax => libc::_SC_HOST_NAME_MAX,
+        }
+    }
+
+    /// The name of the C constant, e.g. "_SC_ARG_MAX".
+    pub fn name(self) -> &'static str {
+        match self {
+            SysVar::ArgMax => "_SC_ARG_MAX",
+            SysVar::ChildMax => "_SC_CHILD_MAX",
+            SysVar::ClkTck => "_SC_CLK_TCK",
+            SysVar::NgroupsMax => "_SC_NGROUPS_MAX",
+            SysVar::OpenMax => "_SC_OPEN_MAX",
+            SysVar::StreamMax => "_SC_STREAM_MAX",
+            SysVar::PageSize => "_SC_PAGESIZE",
+            SysVar::RtsigMax => "_SC_RTSIG_MAX",
+            SysVar::SigqueueMax => "_SC_SIGQUEUE_MAX",
+            SysVar::LineMax => "_SC_LINE_MAX",
+            SysVar::LoginNameMax => "_SC_LOGIN_NAME_MAX",
+            SysVar::TtyNameMax => "_SC_TTY_NAME_MAX",
+            SysVar::NprocessorsConf => "_SC_NPROCESSORS_CONF",
+            SysVar::NprocessorsOnln => "_SC_NPROCESSORS_ONLN",
+            SysVar::PhysPages => "_SC_PHYS_PAGES",
+            SysVar::AvphysPages => "_SC_AVPHYS_PAGES",
+            SysVar::HostNameMax => "_SC_HOST_NAME_MAX",
+        }
+    }
+}
+
+impl fmt::Display for SysVar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// A limit of a file or the filesystem holding it, queried with
+/// `pathconf()` or `fpathconf()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PathVar {
+    LinkMax,
+    MaxCanon,
+    MaxInput,
+    /// The longest file name allowed in a directory.
+    NameMax,
+    /// The longest relative path name allowed from a directory, including
+    /// the terminating null byte.
+    PathMax,
+    /// The largest write to a pipe or FIFO that is guaranteed atomic.
+    PipeBuf,
+    ChownRestricted,
+    NoTrunc,
+    Vdisable,
+}
+
+impl PathVar {
+    fn as_raw(self) -> c_int {
+        match self {
+            PathVar::LinkMax => libc::_PC_LINK_MAX,
+            PathVar::MaxCanon => libc::_PC_MAX_CANON,
+            PathVar::MaxInput => libc::_PC_MAX_INPUT,
+            PathVar::NameMax => libc::_PC_NAME_MAX,
+            PathVar::PathMax => libc::_PC_PATH_MAX,
+            PathVar::PipeBuf => libc::_PC_PIPE_BUF,
+            PathVar::ChownRestricted => libc::_PC_CHOWN_RESTRICTED,
+            PathVar::NoTrunc => libc::_PC_NO_TRUNC,
+            PathVar::Vdisable => libc::_PC_VDISABLE,
+        }
+    }
+
+    /// The name of the C constant, e.g. "_PC_NAME_MAX".
+    pub fn name(self) -> &'static str {
+        match self {
+            PathVar::LinkMax => "_PC_LINK_MAX",
+            PathVar::MaxCanon => "_PC_MAX_CANON",
+            PathVar::MaxInput => "_PC_MAX_INPUT",
+            PathVar::NameMax => "_PC_NAME_MAX",
+            PathVar::PathMax => "_PC_PATH_MAX",
+            PathVar::PipeBuf => "_PC_PIPE_BUF",
+            PathVar::ChownRestricted => "_PC_CHOWN_RESTRICTED",
+            PathVar::NoTrunc => "_PC_NO_TRUNC",
+            PathVar::Vdisable => "_PC_VDISABLE",
+        }
+    }
+}
+
+impl fmt::Display for PathVar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Call one of the `*conf()` functions, which return -1 without changing
+/// `errno` for a limit that is indeterminate (has no fixed value).
+fn conf(f: impl FnOnce() -> c_long) -> io::Result<Option<u64>> {
+    unsafe {
+        *libc::__errno_location() = 0;
+    }
+    let result = f();
+    if result == -1 {
+        match unsafe { *libc::__errno_location() } {
+            0 => Ok(None),
+            errno => Err(io::Error::from_raw_os_error(errno)),
+        }
+    } else {
+        Ok(Some(result as u64))
+    }
+}
+
+/// Return the value of a system limit, or `None` if it is indeterminate.
+pub fn sysconf(var: SysVar) -> io::Result<Option<u64>> {
+    conf(|| unsafe { libc::sysconf(var.as_raw()) })
+}
+
+/// Return a limit for the file `path`, or `None` if it is indeterminate.
+pub fn pathconf<P: AsRef<Path>>(path: P, var: PathVar) -> io::Result<Option<u64>> {
+    let path = cstr(path.as_ref())?;
+    conf(|| unsafe { libc::pathconf(path.as_ptr(), var.as_raw()) })
+}
+
+/// Return a limit for an open file, or `None` if it is indeterminate.
+pub fn fpathconf<F: AsRawFd>(fd: &F, var: PathVar) -> io::Result<Option<u64>> {
+    conf(|| unsafe { libc::fpathconf(fd.as_raw_fd(), var.as_raw()) })
+}
+
+/// Return `var`, falling back on the kernel's auxiliary vector entry
+/// `aux` and then on `default`, so that library code that sizes buffers
+/// with these never panics.
+fn cached(cell: &OnceLock<u64>, var: SysVar, aux: c_ulong, default: u64) -> u64 {
+    *cell.get_or_init(|| {
+        sysconf(var)
+            .ok()
+            .flatten()
+            .or_else(|| env::getauxval(aux).filter(|&n| n > 0))
+            .unwrap_or(default)
+    })
+}
+
+/// The size of a virtual memory page, in bytes.
+pub fn page_size() -> usize {
+    static PAGE_SIZE: OnceLock<u64> = OnceLock::new();
+    cached(&PAGE_SIZE, SysVar::PageSize, libc::AT_PAGESZ, 4096) as usize
+}
+
+/// The number of clock ticks per second (`_SC_CLK_TCK`).
+pub fn clock_ticks() -> u64 {
+    static CLK_TCK: OnceLock<u64> = OnceLock::new();
+    cached(&CLK_TCK, SysVar::ClkTck, libc::AT_CLKTCK, 100)
+}
+
+/// The number of processors currently online, which can change while the
+/// process runs.
+pub fn online_cpus() -> io::Result<usize> {
+    Ok(sysconf(SysVar::NprocessorsOnln)?.unwrap_or(1) as usize)
+}
+
+/// A resource whose consumption is limited by `setrlimit()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Resource {
+    /// CPU time in seconds.
+    Cpu,
+    /// The largest file that can be created, in bytes.
+    Fsize,
+    /// The size of the data segment, in bytes.
+    Data,
+    /// The size of the main thread's stack, in bytes.
+    Stack,
+    /// The largest core dump file, in bytes.
+    Core,
+    /// Ignored by Linux.
+    Rss,
+    /// The number of processes of the real user ID.
+    Nproc,
+    /// One more than the highest file descriptor that can be opened.
+    Nofile,
+    /// Bytes of memory that can be locked with `mlock()`.
+    Memlock,
+    /// The size of the virtual address space, in bytes.
+    As,
+    Locks,
+    /// The number of signals that can be queued for the real user ID.
+    Sigpending,
+    /// Bytes allocated to POSIX message queues of the real user ID.
+    Msgqueue,
+    /// A ceiling on the nice value, as 20 - the lowest value allowed.
+    Nice,
+    /// A ceiling on the realtime scheduling priority.
+    Rtprio,
+    /// Microseconds of CPU time a realtime thread may use without blocking.
+    Rttime,
+}
+
+const ALL: [Resource; libc::RLIMIT_NLIMITS as usize] = [
+    Resource::Cpu,
+    Resource::Fsize,
+    Resource::Data,
+    Resource::Stack,
+    Resource::Core,
+    Resource::Rss,
+    Resource::Nproc,
+    Resource::Nofile,
+    Resource::Memlock,
+    Resource::As,
+    Resource::Locks,
+    Resource::Sigpending,
+    Resource::Msgqueue,
+    Resource::Nice,
+    Resource::Rtprio,
+    Resource::Rttime,
+];
+
+impl Resource {
+    pub fn all() -> impl Iterator<Item = Resource> {
+        ALL.iter().copied()
+    }
+
+    fn as_raw(self) -> c_int {
+        match self {
+            Resource::Cpu => libc::RLIMIT_CPU,
+            Resource::Fsize => libc::RLIMIT_FSIZE,
+            Resource::Data => libc::RLIMIT_DATA,
+            Resource::Stack => libc::RLIMIT_STACK,
+            Resource::Core => libc::RLIMIT_CORE,
+            Resource::Rss => libc::RLIMIT_RSS,
+            Resource::Nproc => libc::RLIMIT_NPROC,
+            Resource::Nofile => libc::RLIMIT_NOFILE,
+            Resource::Memlock => libc::RLIMIT_MEMLOCK,
+            Resource::As => libc::RLIMIT_AS,
+            Resource::Locks => libc::RLIMIT_LOCKS,
+            Resource::Sigpending => libc::RLIMIT_SIGPENDING,
+            Resource::Msgqueue => libc::RLIMIT_MSGQUEUE,
+            Resource::Nice => libc::RLIMIT_NICE,
+            Resource::Rtprio => libc::RLIMIT_RTPRIO,
+            Resource::Rttime => libc::RLIMIT_RTTIME,
+        }
+    }
+
+    /// The name of the C constant, e.g. "RLIMIT_NOFILE".
+    pub fn name(self) -> &'static str {
+        match self {
+            Resource::Cpu => "RLIMIT_CPU",
+            Resource::Fsize => "RLIMIT_FSIZE",
+            Resource::Data => "RLIMIT_DATA",
+            Resource::Stack => "RLIMIT_STACK",
+            Resource::Core => "RLIMIT_CORE",
+            Resource::Rss => "RLIMIT_RSS",
+            Resource::Nproc => "RLIMIT_NPROC",
+            Resource::Nofile => "RLIMIT_NOFILE",
+            Resource::Memlock => "RLIMIT_MEMLOCK",
+            Resource::As => "RLIMIT_AS",
+            Resource::Locks => "RLIMIT_LOCKS",
+            Resource::Sigpending => "RLIMIT_SIGPENDING",
+            Resource::Msgqueue => "RLIMIT_MSGQUEUE",
+            Resource::Nice => "RLIMIT_NICE",
+            Resource::Rtprio => "RLIMIT_RTPRIO",
+            Resource::Rttime => "RLIMIT_RTTIME",
+        }
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// The soft and hard limits on a resource, as in `struct rlimit`, with
+/// `None` standing for `RLIM_INFINITY`.
+///
+/// Only the soft limit is enforced. An unprivileged process may lower its
+/// hard limit, irreversibly, and set the soft limit anywhere up to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rlimit {
+    pub soft: Option<u64>,
+    pub hard: Option<u64>,
+}
+
+impl Rlimit {
+    pub const UNLIMITED: Rlimit = Rlimit {
+        soft: None,
+        hard: None,
+    };
+
+    pub const fn new(soft: Option<u64>, hard: Option<u64>) -> Rlimit {
+        Rlimit { soft, hard }
+    }
+
+    fn from_raw(rlim: libc::rlimit) -> Rlimit {
+        let value = |v| {
+            if v == libc::RLIM_INFINITY {
+                None
+            } else {
+                Some(v)
+            }
+        };
+        Rlimit {
+            soft: value(rlim.rlim_cur),
+            hard: value(rlim.rlim_max),
+        }
+    }
+
+    fn as_raw(&self) -> libc::rlimit {
+        libc::rlimit {
+            rlim_cur: self.soft.unwrap_or(libc::RLIM_INFINITY),
+            rlim_max: self.hard.unwrap_or(libc::RLIM_INFINITY),
+        }
+    }
+}
+
+fn limit(f: &mut fmt::Formatter<'_>, value: Option<u64>) -> fmt::Result {
+    match value {
+        Some(value) => write!(f, "{}", value),
+        None => f.write_str("unlimited"),
+    }
+}
+
+/// Renders as e.g. "soft=1024; hard=unlimited".
+impl fmt::Display for Rlimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("soft=")?;
+        limit(f, self.soft)?;
+        f.write_str("; hard=")?;
+        limit(f, self.hard)
+    }
+}
+
+pub fn getrlimit(resource: Resource) -> io::Result<Rlimit> {
+    let mut rlim = Rlimit::UNLIMITED.as_raw();
+    let result = unsafe { libc::getrlimit(resource.as_raw(), &mut rlim) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(Rlimit::from_raw(rlim))
+    }
+}
+
+/// Set the limits on `resource`. Raising the hard limit requires
+/// `CAP_SYS_RESOURCE`.
+pub fn setrlimit(resource: Resource, limit: &Rlimit) -> io::Result<()> {
+    let result = unsafe { libc::setrlimit(resource.as_raw(), &limit.as_raw()) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Get, and if `new` is given also set, the limits on `resource` of
+/// process `pid` (0 meaning the caller), returning the previous limits.
+///
+/// Acting on another process requires `CAP_SYS_RESOURCE` or matching
+/// user and group IDs.
+pub fn prlimit(pid: u32, resource: Resource, new: Option<&Rlimit>) -> io::Result<Rlimit> {
+    let new = new.map(Rlimit::as_raw);
+    let new_ptr = new.as_ref().map_or(ptr::null(), |rlim| rlim as *const _);
+    let mut old = Rlimit::UNLIMITED.as_raw();
+    let result = unsafe { libc::prlimit(pid as libc::pid_t, resource.as_raw(), new_ptr, &mut old) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(Rlimit::from_raw(old))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conf_results() {
+        assert_eq!(conf(|| 42).unwrap(), Some(42));
+        // -1 is an indeterminate limit unless errno was set
+        assert_eq!(conf(|| -1).unwrap(), None);
+        let err = conf(|| {
+            unsafe {
+                *libc::__errno_location() = libc::EINVAL;
+            }
+            -1
+        })
+        .unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+    }
+
+    #[test]
+    fn system_limits() {
+        assert_eq!(sysconf(SysVar::PageSize).unwrap(), Some(page_size() as u64));
+        assert_eq!(sysconf(SysVar::ClkTck).unwrap(), Some(clock_ticks()));
+        assert!(online_cpus().unwrap() >= 1);
+        assert!(pathconf("/", PathVar::NameMax).unwrap().is_some());
+        let err = pathconf("/nonexistent/path", PathVar::NameMax).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+        assert_eq!(SysVar::ArgMax.to_string(), "_SC_ARG_MAX");
+        assert_eq!(PathVar::PipeBuf.to_string(), "_PC_PIPE_BUF");
+    }
+
+    #[test]
+    fn rlimit_raw() {
+        let raw = libc::rlimit {
+            rlim_cur: 1024,
+            rlim_max: libc::RLIM_INFINITY,
+        };
+        let rlim = Rlimit::from_raw(raw);
+        assert_eq!(rlim, Rlimit::new(Some(1024), None));
+        let raw = rlim.as_raw();
+        assert_eq!((raw.rlim_cur, raw.rlim_max), (1024, libc::RLIM_INFINITY));
+        let raw = Rlimit::UNLIMITED.as_raw();
+        assert_eq!(
+            (raw.rlim_cur, raw.rlim_max),
+            (libc::RLIM_INFINITY, libc::RLIM_INFINITY)
+        );
+    }
+
+    #[test]
+    fn rlimit_display() {
+        assert_eq!(
+            Rlimit::new(Some(1024), None).to_string(),
+            "soft=1024; hard=unlimited"
+        );
+        assert_eq!(
+            Rlimit::new(Some(0), Some(4096)).to_string(),
+            "soft=0; hard=4096"
+        );
+        assert_eq!(
+            Rlimit::UNLIMITED.to_string(),
+            "soft=unlimited; hard=unlimited"
+        );
+        assert_eq!(Resource::Nofile.to_string(), "RLIMIT_NOFILE");
+        assert_eq!(Resource::all().count(), libc::RLIMIT_NLIMITS as usize);
+    }
+
+    #[test]
+    fn get_limits() {
+        let rlim = getrlimit(Resource::Nofile).unwrap();
+        assert!(rlim.soft.is_some());
+        assert!(rlim.hard.is_none() || rlim.soft <= rlim.hard);
+        assert_eq!(prlimit(0, Resource::Nofile, None).unwrap(), rlim);
+    }
+}
@@ -0,0 +1,505 @@
+use crate::sys::net::{sockaddr_to_addr, socket_addr_to_raw};
+use libc::{c_char, c_int, sockaddr, sockaddr_storage, socklen_t};
+use std::error;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::io;
+use std::mem;
+use std::net::SocketAddr;
+use std::ptr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Family {
+    Inet,
+    Inet6,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SockType {
+    Stream,
+    Dgram,
+}
+
+/// Errors returned by `getaddrinfo()` and `getnameinfo()`.
+///
+/// These functions do not report failures through `errno`; the one
+/// exception is `EAI_SYSTEM`, which is mapped to `System`.
+/// `InvalidInput` is reported without calling them, for a host or service
+/// containing a NUL byte.
+#[derive(Debug)]
+pub enum LookupError {
+    AddrFamily,
+    Again,
+    BadFlags,
+    Fail,
+    Family,
+    Memory,
+    NoData,
+    NoName,
+    Overflow,
+    Service,
+    SockType,
+    System(io::Error),
+    InvalidInput,
+    Unknown(i32),
+}
+
+impl LookupError {
+    fn from_raw(code: c_int) -> LookupError {
+        match code {
+            libc::EAI_ADDRFAMILY => LookupError::AddrFamily,
+            libc::EAI_AGAIN => LookupError::Again,
+            libc::EAI_BADFLAGS => LookupError::BadFlags,
+            libc::EAI_FAIL => LookupError::Fail,
+            libc::EAI_FAMILY => LookupError::Family,
+            libc::EAI_MEMORY => LookupError::Memory,
+            libc::EAI_NODATA => LookupError::NoData,
+            libc::EAI_NONAME => LookupError::NoName,
+            libc::EAI_OVERFLOW => LookupError::Overflow,
+            libc::EAI_SERVICE => LookupError::Service,
+            libc::EAI_SOCKTYPE => LookupError::SockType,
+            libc::EAI_SYSTEM => LookupError::System(io::Error::last_os_error()),
+            code => LookupError::Unknown(code),
+        }
+    }
+
+    /// The `EAI_*` code, or `None` for `InvalidInput`, which does not come
+    /// from the C library.
+    pub fn raw_code(&self) -> Option<i32> {
+        let code = match *self {
+            LookupError::AddrFamily => libc::EAI_ADDRFAMILY,
+            LookupError::Again => libc::EAI_AGAIN,
+            LookupError::BadFlags => libc::EAI_BADFLAGS,
+            LookupError::Fail => libc::EAI_FAIL,
+            LookupError::Family => libc::EAI_FAMILY,
+            LookupError::Memory => libc::EAI_MEMORY,
+            LookupError::NoData => libc::EAI_NODATA,
+            LookupError::NoName => libc::EAI_NONAME,
+            LookupError::Overflow => libc::EAI_OVERFLOW,
+            LookupError::Service => libc::EAI_SERVICE,
+            LookupError::SockType => libc::EAI_SOCKTYPE,
+            LookupError::System(_) => libc::EAI_SYSTEM,
+            LookupError::InvalidInput => return None,
+            LookupError::Unknown(code) => code,
+        };
+        Some(code)
+    }
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let LookupError::System(ref err) = *self {
+            return err.fmt(f);
+        }
+        let Some(code) = self.raw_code() else {
+            return f.write_str("host or service contained an unexpected NUL byte");
+        };
+        let msg = unsafe { CStr::from_ptr(libc::gai_strerror(code)) };
+        f.write_str(&msg.to_string_lossy())
+    }
+}
+
+impl error::Error for LookupError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            LookupError::System(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<LookupError> for io::Error {
+    fn from(err: LookupError) -> io::Error {
+        match err {
+            LookupError::System(err) => err,
+            LookupError::NoName | LookupError::NoData => {
+                io::Error::new(io::ErrorKind::NotFound, err)
+            }
+            LookupError::InvalidInput => io::Error::new(io::ErrorKind::InvalidInput, err),
+            err => io::Error::other(err),
+        }
+    }
+}
+
+/// The addresses produced by `getaddrinfo()`.
+///
+/// Owns the list returned by the C library and releases it with
+/// `freeaddrinfo()` when dropped.
+pub struct LookupAddrs {
+    original: *mut libc::addrinfo,
+    cur: *mut libc::addrinfo,
+}
+
+impl Iterator for LookupAddrs {
+    type Item = SocketAddr;
+
+    fn next(&mut self) -> Option<SocketAddr> {
+        loop {
+            let cur = unsafe { self.cur.as_ref()? };
+            self.cur = cur.ai_next;
+            let mut storage: sockaddr_storage = unsafe { mem::zeroed() };
+            let len = (cur.ai_addrlen as usize).min(mem::size_of::<sockaddr_storage>());
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    cur.ai_addr as *const u8,
+                    &mut storage as *mut sockaddr_storage as *mut u8,
+                    len,
+                );
+            }
+            match sockaddr_to_addr(&storage, len) {
+                Ok(addr) => return Some(addr),
+                Err(_) => continue,
+            }
+        }
+    }
+}
+
+impl Drop for LookupAddrs {
+    fn drop(&mut self) {
+        unsafe { libc::freeaddrinfo(self.original) }
+    }
+}
+
+pub struct LookupOptions {
+    family: Option<Family>,
+    socktype: Option<SockType>,
+    passive: bool,
+    numeric_host: bool,
+    numeric_serv: bool,
+    addr_config: bool,
+}
+
+impl LookupOptions {
+    pub fn new() -> Self {
+        LookupOptions {
+            family: None,
+            socktype: None,
+            passive: false,
+            numeric_host: false,
+            numeric_serv: false,
+            addr_config: false,
+        }
+    }
+
+    pub fn family(&mut self, family: Family) -> &mut Self {
+        self.family = Some(family);
+        self
+    }
+
+    pub fn socktype(&mut self, socktype: SockType) -> &mut Self {
+        self.socktype = Some(socktype);
+        self
+    }
+
+    /// Return wildcard addresses suitable for `bind()` when `host` is
+    /// `None` (`AI_PASSIVE`).
+    pub fn passive(&mut self, passive: bool) -> &mut Self {
+        self.passive = passive;
+        self
+    }
+
+    pub fn numeric_host(&mut self, numeric_host: bool) -> &mut Self {
+        self.numeric_host = numeric_host;
+        self
+    }
+
+    pub fn numeric_serv(&mut self, numeric_serv: bool) -> &mut Self {
+        self.numeric_serv = numeric_serv;
+        self
+    }
+
+    pub fn addr_config(&mut self, addr_config: bool) -> &mut Self {
+        self.addr_config = addr_config;
+        self
+    }
+
+    pub fn lookup(
+        &self,
+        host: Option<&str>,
+        service: Option<&str>,
+    ) -> Result<LookupAddrs, LookupError> {
+        let host = host.map(to_c_string).transpose()?;
+        let service = service.map(to_c_string).transpose()?;
+
+        let mut hints: libc::addrinfo = unsafe { mem::zeroed() };
+        hints.ai_family = match self.family {
+            None => libc::AF_UNSPEC,
+            Some(Family::Inet) => libc::AF_INET,
+            Some(Family::Inet6) => libc::AF_INET6,
+        };
+        hints.ai_socktype = match self.socktype {
+            None => 0,
+            Some(SockType::Stream) => libc::SOCK_STREAM,
+            Some(SockType::Dgram) => libc::SOCK_DGRAM,
+        };
+        hints.ai_flags = self.get_flags();
+
+        let mut res = ptr::null_mut();
+        let result = unsafe {
+            libc::getaddrinfo(
+                host.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+                service.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+                &hints,
+                &mut res,
+            )
+        };
+        if result != 0 {
+            Err(LookupError::from_raw(result))
+        } else {
+            Ok(LookupAddrs {
+                original: res,
+                cur: res,
+            })
+        }
+    }
+
+    fn get_flags(&self) -> c_int {
+        let mut flags = 0;
+        if self.passive {
+            flags |= libc::AI_PASSIVE;
+        }
+        if self.numeric_host {
+            flags |= libc::AI_NUMERICHOST;
+        }
+        if self.numeric_serv {
+            flags |= libc::AI_NUMERICSERV;
+        }
+        if self.addr_config {
+            flags |= libc::AI_ADDRCONFIG;
+        }
+        flags
+    }
+}
+
+impl Default for LookupOptions {
+    fn default() -> Self {
+        LookupOptions::new()
+    }
+}
+
+pub fn lookup(host: Option<&str>, service: Option<&str>) -> Result<LookupAddrs, LookupError> {
+    LookupOptions::new().lookup(host, service)
+}
+
+pub struct NameInfoOptions {
+    numeric_host: bool,
+    numeric_serv: bool,
+    name_required: bool,
+    no_fqdn: bool,
+    datagram: bool,
+}
+
+impl NameInfoOptions {
+    pub fn new() -> Self {
+        NameInfoOptions {
+            numeric_host: false,
+            numeric_serv: false,
+            name_required: false,
+            no_fqdn: false,
+            datagram: false,
+        }
+    }
+
+    pub fn numeric_host(&mut self, numeric_host: bool) -> &mut Self {
+        self.numeric_host = numeric_host;
+        self
+    }
+
+    pub fn numeric_serv(&mut self, numeric_serv: bool) -> &mut Self {
+        self.numeric_serv = numeric_serv;
+        self
+    }
+
+    /// Fail with `NoName` instead of returning the numeric address when
+    /// the host name cannot be determined (`NI_NAMEREQD`).
+    pub fn name_required(&mut self, name_required: bool) -> &mut Self {
+        self.name_required = name_required;
+        self
+    }
+
+    pub fn no_fqdn(&mut self, no_fqdn: bool) -> &mut Self {
+        self.no_fqdn = no_fqdn;
+        self
+    }
+
+    /// Look the service up as a UDP rather than a TCP port (`NI_DGRAM`).
+    pub fn datagram(&mut self, datagram: bool) -> &mut Self {
+        self.datagram = datagram;
+        self
+    }
+
+    /// Return the host and service names for `addr`.
+    pub fn lookup(&self, addr: &SocketAddr) -> Result<(String, String), LookupError> {
+        let (storage, len) = socket_addr_to_raw(addr);
+        let mut host = [0 as c_char; libc::NI_MAXHOST];
+        let mut service = [0 as c_char; libc::NI_MAXSERV];
+        let result = unsafe {
+            libc::getnameinfo(
+                &storage as *const sockaddr_storage as *const sockaddr,
+                len,
+                host.as_mut_ptr(),
+                host.len() as socklen_t,
+                service.as_mut_ptr(),
+                service.len() as socklen_t,
+                self.get_flags(),
+            )
+        };
+        if result != 0 {
+            return Err(LookupError::from_raw(result));
+        }
+        let (host, service) = unsafe {
+            (
+                CStr::from_ptr(host.as_ptr()),
+                CStr::from_ptr(service.as_ptr()),
+            )
+        };
+        Ok((
+            host.to_string_lossy().into_owned(),
+            service.to_string_lossy().into_owned(),
+        ))
+    }
+
+    fn get_flags(&self) -> c_int {
+        let mut flags = 0;
+        if self.numeric_host {
+            flags |= libc::NI_NUMERICHOST;
+        }
+        if self.numeric_serv {
+            flags |= libc::NI_NUMERICSERV;
+        }
+        if self.name_required {
+            flags |= libc::NI_NAMEREQD;
+        }
+        if self.no_fqdn {
+            flags |= libc::NI_NOFQDN;
+        }
+        if self.datagram {
+            flags |= libc::NI_DGRAM;
+        }
+        flags
+    }
+}
+
+impl Default for NameInfoOptions {
+    fn default() -> Self {
+        NameInfoOptions::new()
+    }
+}
+
+pub fn lookup_name(addr: &SocketAddr) -> Result<(String, String), LookupError> {
+    NameInfoOptions::new().lookup(addr)
+}
+
+fn to_c_string(s: &str) -> Result<CString, LookupError> {
+    CString::new(s).map_err(|_| LookupError::InvalidInput)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    const LOOPBACK: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    #[test]
+    fn numeric_host_and_service() {
+        let mut opts = LookupOptions::new();
+        opts.numeric_host(true)
+            .numeric_serv(true)
+            .socktype(SockType::Stream);
+        let addrs: Vec<SocketAddr> = opts
+            .lookup(Some("127.0.0.1"), Some("8080"))
+            .unwrap()
+            .collect();
+        assert_eq!(addrs, [SocketAddr::new(LOOPBACK, 8080)]);
+        let addrs: Vec<SocketAddr> = opts.lookup(Some("::1"), Some("53")).unwrap().collect();
+        assert_eq!(addrs, ["[::1]:53".parse().unwrap()]);
+
+        assert!(matches!(
+            opts.lookup(Some("localhost"), Some("80")),
+            Err(LookupError::NoName)
+        ));
+        assert!(matches!(
+            opts.lookup(Some("127.0.0.1"), Some("http")),
+            Err(LookupError::NoName)
+        ));
+        opts.family(Family::Inet6);
+        assert!(opts.lookup(Some("127.0.0.1"), Some("80")).is_err());
+    }
+
+    #[test]
+    fn passive() {
+        let mut opts = LookupOptions::new();
+        opts.passive(true)
+            .numeric_serv(true)
+            .family(Family::Inet)
+            .socktype(SockType::Dgram);
+        let addrs: Vec<SocketAddr> = opts.lookup(None, Some("9000")).unwrap().collect();
+        assert_eq!(addrs, ["0.0.0.0:9000".parse().unwrap()]);
+    }
+
+    #[test]
+    fn localhost() {
+        let mut opts = LookupOptions::new();
+        opts.family(Family::Inet).socktype(SockType::Stream);
+        let addrs: Vec<SocketAddr> = opts
+            .lookup(Some("localhost"), Some("22"))
+            .unwrap()
+            .collect();
+        assert!(addrs.contains(&SocketAddr::new(LOOPBACK, 22)));
+        assert!(addrs.iter().all(|addr| addr.ip().is_loopback()));
+    }
+
+    #[test]
+    fn interior_nul() {
+        let err = lookup(Some("local\0host"), None).err().unwrap();
+        assert!(matches!(err, LookupError::InvalidInput));
+        assert_eq!(err.raw_code(), None);
+        assert_eq!(
+            err.to_string(),
+            "host or service contained an unexpected NUL byte"
+        );
+        assert_eq!(io::Error::from(err).kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn name_info() {
+        let addr = SocketAddr::new(LOOPBACK, 8080);
+        let mut opts = NameInfoOptions::new();
+        opts.numeric_host(true).numeric_serv(true);
+        assert_eq!(
+            opts.lookup(&addr).unwrap(),
+            ("127.0.0.1".to_string(), "8080".to_string())
+        );
+
+        let mut opts = NameInfoOptions::new();
+        opts.name_required(true).numeric_serv(true);
+        assert_eq!(
+            opts.lookup(&addr).unwrap(),
+            ("localhost".to_string(), "8080".to_string())
+        );
+
+        // TEST-NET-1 has no name, so it is only found numerically
+        let addr = "192.0.2.1:1".parse().unwrap();
+        let mut opts = NameInfoOptions::new();
+        opts.numeric_host(true).numeric_serv(true);
+        assert_eq!(opts.lookup(&addr).unwrap().0, "192.0.2.1");
+    }
+
+    #[test]
+    fn error_messages() {
+        assert_eq!(
+            LookupError::from_raw(libc::EAI_NONAME).raw_code(),
+            Some(libc::EAI_NONAME)
+        );
+        assert!(matches!(
+            LookupError::from_raw(-999),
+            LookupError::Unknown(-999)
+        ));
+        assert!(!LookupError::NoName.to_string().is_empty());
+        assert_eq!(
+            io::Error::from(LookupError::NoName).kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+}
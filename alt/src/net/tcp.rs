@@ -0,0 +1,326 @@
+use super::each_addr;
+use crate::os::fd::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use crate::sys::net::{buffer_size, getsockopt, setsockopt, Socket};
+use libc::c_int;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, ToSocketAddrs};
+
+pub struct TcpStream(Socket);
+
+impl TcpStream {
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<TcpStream> {
+        each_addr(addr, TcpStream::_connect)
+    }
+
+    fn _connect(addr: &SocketAddr) -> io::Result<TcpStream> {
+        let sock = Socket::new(addr, libc::SOCK_STREAM)?;
+        sock.connect(addr)?;
+        Ok(TcpStream(sock))
+    }
+
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.0.peer_addr()
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.socket_addr()
+    }
+
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.0.shutdown(how)
+    }
+
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.peek(buf)
+    }
+
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        setsockopt(
+            &self.0,
+            libc::IPPROTO_TCP,
+            libc::TCP_NODELAY,
+            nodelay as c_int,
+        )
+    }
+
+    pub fn nodelay(&self) -> io::Result<bool> {
+        let raw: c_int = getsockopt(&self.0, libc::IPPROTO_TCP, libc::TCP_NODELAY)?;
+        Ok(raw != 0)
+    }
+
+    pub fn set_keepalive(&self, keepalive: bool) -> io::Result<()> {
+        setsockopt(
+            &self.0,
+            libc::SOL_SOCKET,
+            libc::SO_KEEPALIVE,
+            keepalive as c_int,
+        )
+    }
+
+    pub fn keepalive(&self) -> io::Result<bool> {
+        let raw: c_int = getsockopt(&self.0, libc::SOL_SOCKET, libc::SO_KEEPALIVE)?;
+        Ok(raw != 0)
+    }
+
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        setsockopt(
+            &self.0,
+            libc::SOL_SOCKET,
+            libc::SO_RCVBUF,
+            buffer_size(size)?,
+        )
+    }
+
+    /// Note that the kernel doubles the value passed to
+    /// `set_recv_buffer_size` to allow space for bookkeeping overhead, and
+    /// reports the doubled value here.
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        let raw: c_int = getsockopt(&self.0, libc::SOL_SOCKET, libc::SO_RCVBUF)?;
+        Ok(raw as usize)
+    }
+
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        setsockopt(
+            &self.0,
+            libc::SOL_SOCKET,
+            libc::SO_SNDBUF,
+            buffer_size(size)?,
+        )
+    }
+
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        let raw: c_int = getsockopt(&self.0, libc::SOL_SOCKET, libc::SO_SNDBUF)?;
+        Ok(raw as usize)
+    }
+
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        setsockopt(&self.0, libc::IPPROTO_IP, libc::IP_TTL, ttl as c_int)
+    }
+
+    pub fn ttl(&self) -> io::Result<u32> {
+        let raw: c_int = getsockopt(&self.0, libc::IPPROTO_IP, libc::IP_TTL)?;
+        Ok(raw as u32)
+    }
+
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.0.take_error()
+    }
+}
+
+impl Read for TcpStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+}
+
+impl Read for &TcpStream {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for TcpStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Write for &TcpStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AsRawFd for TcpStream {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl FromRawFd for TcpStream {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self(FromRawFd::from_raw_fd(fd))
+    }
+}
+
+impl IntoRawFd for TcpStream {
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
+pub struct TcpListener(Socket);
+
+impl TcpListener {
+    /// Like the standard library, this sets `SO_REUSEADDR` before binding so
+    /// that a restarted server does not fail with `EADDRINUSE` while old
+    /// connections linger in `TIME_WAIT`.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<TcpListener> {
+        each_addr(addr, TcpListener::_bind)
+    }
+
+    fn _bind(addr: &SocketAddr) -> io::Result<TcpListener> {
+        let sock = Socket::new(addr, libc::SOCK_STREAM)?;
+        setsockopt(&sock, libc::SOL_SOCKET, libc::SO_REUSEADDR, 1 as c_int)?;
+        sock.bind(addr)?;
+        sock.listen(libc::SOMAXCONN)?;
+        Ok(TcpListener(sock))
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.socket_addr()
+    }
+
+    pub fn accept(&self) -> io::Result<(TcpStream, SocketAddr)> {
+        let (sock, addr) = self.0.accept()?;
+        Ok((TcpStream(sock), addr))
+    }
+
+    pub fn incoming(&self) -> Incoming<'_> {
+        Incoming { listener: self }
+    }
+
+    pub fn reuse_address(&self) -> io::Result<bool> {
+        let raw: c_int = getsockopt(&self.0, libc::SOL_SOCKET, libc::SO_REUSEADDR)?;
+        Ok(raw != 0)
+    }
+
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        setsockopt(&self.0, libc::IPPROTO_IP, libc::IP_TTL, ttl as c_int)
+    }
+
+    pub fn ttl(&self) -> io::Result<u32> {
+        let raw: c_int = getsockopt(&self.0, libc::IPPROTO_IP, libc::IP_TTL)?;
+        Ok(raw as u32)
+    }
+
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.0.take_error()
+    }
+}
+
+impl AsRawFd for TcpListener {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl FromRawFd for TcpListener {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self(FromRawFd::from_raw_fd(fd))
+    }
+}
+
+impl IntoRawFd for TcpListener {
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
+pub struct Incoming<'a> {
+    listener: &'a TcpListener,
+}
+
+impl Iterator for Incoming<'_> {
+    type Item = io::Result<TcpStream>;
+
+    fn next(&mut self) -> Option<io::Result<TcpStream>> {
+        Some(self.listener.accept().map(|p| p.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn loopback_echo() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        assert!(addr.ip().is_loopback() && addr.port() != 0);
+        assert!(listener.reuse_address().unwrap());
+
+        let server = thread::spawn(move || {
+            let (mut stream, peer) = listener.accept().unwrap();
+            assert_eq!(stream.peer_addr().unwrap(), peer);
+            let mut buf = Vec::new();
+            stream.read_to_end(&mut buf).unwrap();
+            stream.write_all(&buf).unwrap();
+        });
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), addr);
+        stream.write_all(b"hello, world").unwrap();
+        stream.shutdown(Shutdown::Write).unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+        assert_eq!(reply, "hello, world");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn peek_and_options() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+
+        (&client).write_all(b"abc").unwrap();
+        let mut buf = [0; 8];
+        assert_eq!(server.peek(&mut buf).unwrap(), 3);
+        assert_eq!((&server).read(&mut buf).unwrap(), 3);
+        assert_eq!(&buf[..3], b"abc");
+
+        client.set_nodelay(true).unwrap();
+        assert!(client.nodelay().unwrap());
+        client.set_keepalive(true).unwrap();
+        assert!(client.keepalive().unwrap());
+        client.set_ttl(42).unwrap();
+        assert_eq!(client.ttl().unwrap(), 42);
+        client.set_recv_buffer_size(8192).unwrap();
+        assert!(client.recv_buffer_size().unwrap() >= 8192);
+        let err = client.set_send_buffer_size(1 << 40).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(client.take_error().unwrap().is_none());
+    }
+
+    #[test]
+    fn connect_refused() {
+        // Bind a port, then close it so that nothing listens there
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let err = TcpStream::connect(addr).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+    }
+
+    #[test]
+    fn loopback_v6() {
+        let Ok(listener) = TcpListener::bind("[::1]:0") else {
+            return; // No IPv6 in this environment
+        };
+        let addr = listener.local_addr().unwrap();
+        assert!(addr.is_ipv6());
+        let mut client = TcpStream::connect(addr).unwrap();
+        let (mut server, peer) = listener.accept().unwrap();
+        assert_eq!(peer, client.local_addr().unwrap());
+        client.write_all(b"v6").unwrap();
+        let mut buf = [0; 2];
+        server.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"v6");
+    }
+}
@@ -0,0 +1,196 @@
+use super::each_addr;
+use crate::os::fd::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use crate::sys::net::{buffer_size, getsockopt, setsockopt, Socket};
+use libc::c_int;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+
+pub struct UdpSocket(Socket);
+
+impl UdpSocket {
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<UdpSocket> {
+        each_addr(addr, UdpSocket::_bind)
+    }
+
+    fn _bind(addr: &SocketAddr) -> io::Result<UdpSocket> {
+        let sock = Socket::new(addr, libc::SOCK_DGRAM)?;
+        sock.bind(addr)?;
+        Ok(UdpSocket(sock))
+    }
+
+    pub fn connect<A: ToSocketAddrs>(&self, addr: A) -> io::Result<()> {
+        each_addr(addr, |addr| self.0.connect(addr))
+    }
+
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.0.recv_from(buf)
+    }
+
+    pub fn peek_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.0.peek_from(buf)
+    }
+
+    pub fn send_to<A: ToSocketAddrs>(&self, buf: &[u8], addr: A) -> io::Result<usize> {
+        match addr.to_socket_addrs()?.next() {
+            Some(addr) => self.0.send_to(buf, &addr),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no addresses to send data to",
+            )),
+        }
+    }
+
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.peek(buf)
+    }
+
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.0.peer_addr()
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.socket_addr()
+    }
+
+    pub fn set_broadcast(&self, broadcast: bool) -> io::Result<()> {
+        setsockopt(
+            &self.0,
+            libc::SOL_SOCKET,
+            libc::SO_BROADCAST,
+            broadcast as c_int,
+        )
+    }
+
+    pub fn broadcast(&self) -> io::Result<bool> {
+        let raw: c_int = getsockopt(&self.0, libc::SOL_SOCKET, libc::SO_BROADCAST)?;
+        Ok(raw != 0)
+    }
+
+    pub fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        setsockopt(
+            &self.0,
+            libc::SOL_SOCKET,
+            libc::SO_RCVBUF,
+            buffer_size(size)?,
+        )
+    }
+
+    pub fn recv_buffer_size(&self) -> io::Result<usize> {
+        let raw: c_int = getsockopt(&self.0, libc::SOL_SOCKET, libc::SO_RCVBUF)?;
+        Ok(raw as usize)
+    }
+
+    pub fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        setsockopt(
+            &self.0,
+            libc::SOL_SOCKET,
+            libc::SO_SNDBUF,
+            buffer_size(size)?,
+        )
+    }
+
+    pub fn send_buffer_size(&self) -> io::Result<usize> {
+        let raw: c_int = getsockopt(&self.0, libc::SOL_SOCKET, libc::SO_SNDBUF)?;
+        Ok(raw as usize)
+    }
+
+    pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
+        setsockopt(
+            &self.0,
+            libc::IPPROTO_IPV6,
+            libc::IPV6_V6ONLY,
+            only_v6 as c_int,
+        )
+    }
+
+    pub fn only_v6(&self) -> io::Result<bool> {
+        let raw: c_int = getsockopt(&self.0, libc::IPPROTO_IPV6, libc::IPV6_V6ONLY)?;
+        Ok(raw != 0)
+    }
+
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        setsockopt(&self.0, libc::IPPROTO_IP, libc::IP_TTL, ttl as c_int)
+    }
+
+    pub fn ttl(&self) -> io::Result<u32> {
+        let raw: c_int = getsockopt(&self.0, libc::IPPROTO_IP, libc::IP_TTL)?;
+        Ok(raw as u32)
+    }
+
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.0.take_error()
+    }
+}
+
+impl AsRawFd for UdpSocket {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl FromRawFd for UdpSocket {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self(FromRawFd::from_raw_fd(fd))
+    }
+}
+
+impl IntoRawFd for UdpSocket {
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_send_to() {
+        let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let (a_addr, b_addr) = (a.local_addr().unwrap(), b.local_addr().unwrap());
+
+        assert_eq!(a.send_to(b"ping", b_addr).unwrap(), 4);
+        let mut buf = [0; 16];
+        assert_eq!(b.peek_from(&mut buf).unwrap(), (4, a_addr));
+        assert_eq!(b.recv_from(&mut buf).unwrap(), (4, a_addr));
+        assert_eq!(&buf[..4], b"ping");
+
+        b.send_to(b"pong", a_addr).unwrap();
+        assert_eq!(a.recv_from(&mut buf).unwrap(), (4, b_addr));
+        assert_eq!(&buf[..4], b"pong");
+    }
+
+    #[test]
+    fn connected() {
+        let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+        assert_eq!(
+            a.peer_addr().unwrap_err().kind(),
+            io::ErrorKind::NotConnected
+        );
+        a.connect(b.local_addr().unwrap()).unwrap();
+        assert_eq!(a.peer_addr().unwrap(), b.local_addr().unwrap());
+
+        a.send(b"datagram").unwrap();
+        let mut buf = [0; 4];
+        // A short buffer truncates the datagram, and the rest is lost
+        assert_eq!(b.recv(&mut buf).unwrap(), 4);
+        assert_eq!(&buf, b"data");
+
+        b.set_broadcast(true).unwrap();
+        assert!(b.broadcast().unwrap());
+        b.set_ttl(7).unwrap();
+        assert_eq!(b.ttl().unwrap(), 7);
+        assert!(b.take_error().unwrap().is_none());
+    }
+}
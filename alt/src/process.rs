@@ -0,0 +1,43 @@
+pub mod credentials;
+mod rusage;
+mod wait;
+
+use crate::sys::process as process_imp;
+use std::io;
+
+pub use rusage::*;
+pub use wait::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fork {
+    Parent(i32),
+    Child,
+}
+
+/// Create a child process that is a copy of the caller, returning the
+/// child's PID in the parent.
+///
+/// Everything in process memory is copied, including data waiting in
+/// user-space buffers such as `alt::io::BufWriter`; flush them first unless
+/// both processes are meant to write it out.
+///
+/// # Safety
+///
+/// If other threads exist, the child contains only the calling thread, and
+/// any lock another thread held (including the allocator's) stays locked
+/// forever. Until it calls `exec()` or exits, the child of a multithreaded
+/// parent must restrict itself to async-signal-safe operations.
+pub unsafe fn fork() -> io::Result<Fork> {
+    match process_imp::fork()? {
+        0 => Ok(Fork::Child),
+        pid => Ok(Fork::Parent(pid)),
+    }
+}
+
+pub fn id() -> u32 {
+    unsafe { libc::getpid() as u32 }
+}
+
+pub fn parent_id() -> u32 {
+    unsafe { libc::getppid() as u32 }
+}
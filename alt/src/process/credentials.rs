@@ -0,0 +1,341 @@
+use crate::fs::raw_id;
+use crate::users;
+use libc::{gid_t, uid_t};
+use std::fmt;
+use std::io;
+
+/// The real, effective and saved set IDs of a process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResIds {
+    pub real: u32,
+    pub effective: u32,
+    pub saved: u32,
+}
+
+pub fn getuid() -> u32 {
+    unsafe { libc::getuid() }
+}
+
+pub fn geteuid() -> u32 {
+    unsafe { libc::geteuid() }
+}
+
+pub fn getgid() -> u32 {
+    unsafe { libc::getgid() }
+}
+
+pub fn getegid() -> u32 {
+    unsafe { libc::getegid() }
+}
+
+pub fn getresuid() -> io::Result<ResIds> {
+    let (mut real, mut effective, mut saved) = (0, 0, 0);
+    let result = unsafe { libc::getresuid(&mut real, &mut effective, &mut saved) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(ResIds {
+            real,
+            effective,
+            saved,
+        })
+    }
+}
+
+pub fn getresgid() -> io::Result<ResIds> {
+    let (mut real, mut effective, mut saved) = (0, 0, 0);
+    let result = unsafe { libc::getresgid(&mut real, &mut effective, &mut saved) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(ResIds {
+            real,
+            effective,
+            saved,
+        })
+    }
+}
+
+/// Change any of the real, effective and saved set user IDs; `None` leaves
+/// that ID as it is. An unprivileged process may only set each to one of
+/// its current three.
+pub fn setresuid(real: Option<u32>, effective: Option<u32>, saved: Option<u32>) -> io::Result<()> {
+    let result = unsafe { libc::setresuid(raw_id(real), raw_id(effective), raw_id(saved)) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn setresgid(real: Option<u32>, effective: Option<u32>, saved: Option<u32>) -> io::Result<()> {
+    let result = unsafe { libc::setresgid(raw_id(real), raw_id(effective), raw_id(saved)) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Return the filesystem user ID, which is used for permission checks
+/// and normally follows the effective user ID.
+///
+/// setfsuid() always returns the previous value and never reports errors,
+/// so it is queried by asking for an invalid ID, which changes nothing.
+pub fn getfsuid() -> u32 {
+    unsafe { libc::setfsuid(u32::MAX) as u32 }
+}
+
+pub fn getfsgid() -> u32 {
+    unsafe { libc::setfsgid(u32::MAX) as u32 }
+}
+
+/// Set the filesystem user ID, failing with `EPERM` if the change did not
+/// take effect.
+pub fn setfsuid(fsuid: u32) -> io::Result<()> {
+    unsafe { libc::setfsuid(fsuid) };
+    if getfsuid() == fsuid {
+        Ok(())
+    } else {
+        Err(io::Error::from_raw_os_error(libc::EPERM))
+    }
+}
+
+pub fn setfsgid(fsgid: u32) -> io::Result<()> {
+    unsafe { libc::setfsgid(fsgid) };
+    if getfsgid() == fsgid {
+        Ok(())
+    } else {
+        Err(io::Error::from_raw_os_error(libc::EPERM))
+    }
+}
+
+/// Return the supplementary group IDs. Whether the effective group ID is
+/// included is unspecified.
+pub fn getgroups() -> io::Result<Vec<u32>> {
+    loop {
+        let len = unsafe { libc::getgroups(0, std::ptr::null_mut()) };
+        if len == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut groups: Vec<gid_t> = vec![0; len as usize];
+        let len = unsafe { libc::getgroups(len, groups.as_mut_ptr()) };
+        if len == -1 {
+            let err = io::Error::last_os_error();
+            // The list grew between the two calls
+            if err.raw_os_error() == Some(libc::EINVAL) {
+                continue;
+            }
+            return Err(err);
+        }
+        groups.truncate(len as usize);
+        return Ok(groups);
+    }
+}
+
+/// Replace the supplementary group IDs (requires `CAP_SETGID`).
+pub fn setgroups(groups: &[u32]) -> io::Result<()> {
+    let result = unsafe { libc::setgroups(groups.len(), groups.as_ptr()) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Permanently give up the privileges of a set-user-ID or set-group-ID
+/// program by setting all of the user and group IDs to the real ones.
+///
+/// Group IDs are changed first, while the process may still have the
+/// privilege to do so, and supplementary groups are cleared if the process
+/// is privileged. The result is then checked, since a partial change would
+/// leave a saved ID through which privileges could be regained.
+pub fn drop_privileges() -> io::Result<()> {
+    let uid = getresuid()?;
+    let gid = getresgid()?;
+    if uid.effective == 0 && uid.real != 0 {
+        setgroups(&[gid.real])?;
+    }
+    setresgid(Some(gid.real), Some(gid.real), Some(gid.real))?;
+    setresuid(Some(uid.real), Some(uid.real), Some(uid.real))?;
+
+    let same = |ids: ResIds, id| ids.real == id && ids.effective == id && ids.saved == id;
+    if !same(getresuid()?, uid.real) || !same(getresgid()?, gid.real) {
+        return Err(io::Error::from_raw_os_error(libc::EPERM));
+    }
+    Ok(())
+}
+
+/// A snapshot of the credentials of the calling process.
+///
+/// `Display` prints them as TLPI's `idshow` program does, with names from
+/// the user and group databases.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Credentials {
+    pub uid: ResIds,
+    pub fsuid: u32,
+    pub gid: ResIds,
+    pub fsgid: u32,
+    pub groups: Vec<u32>,
+}
+
+impl Credentials {
+    pub fn current() -> io::Result<Credentials> {
+        Ok(Credentials {
+            uid: getresuid()?,
+            fsuid: getfsuid(),
+            gid: getresgid()?,
+            fsgid: getfsgid(),
+            groups: getgroups()?,
+        })
+    }
+}
+
+impl fmt::Display for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let user = |uid: uid_t| users::get_user_by_uid(uid).ok().flatten().map(|pw| pw.name);
+        let group = |gid: gid_t| {
+            users::get_group_by_gid(gid)
+                .ok()
+                .flatten()
+                .map(|gr| gr.name)
+        };
+        let name = |name: Option<String>| name.unwrap_or_else(|| "???".into());
+
+        write!(f, "UID: ")?;
+        write!(
+            f,
+            "real={} ({}); ",
+            name(user(self.uid.real)),
+            self.uid.real
+        )?;
+        write!(
+            f,
+            "eff={} ({}); ",
+            name(user(self.uid.effective)),
+            self.uid.effective
+        )?;
+        write!(
+            f,
+            "saved={} ({}); ",
+            name(user(self.uid.saved)),
+            self.uid.saved
+        )?;
+        writeln!(f, "fs={} ({}); ", name(user(self.fsuid)), self.fsuid)?;
+
+        write!(f, "GID: ")?;
+        write!(
+            f,
+            "real={} ({}); ",
+            name(group(self.gid.real)),
+            self.gid.real
+        )?;
+        write!(
+            f,
+            "eff={} ({}); ",
+            name(group(self.gid.effective)),
+            self.gid.effective
+        )?;
+        write!(
+            f,
+            "saved={} ({}); ",
+            name(group(self.gid.saved)),
+            self.gid.saved
+        )?;
+        writeln!(f, "fs={} ({}); ", name(group(self.fsgid)), self.fsgid)?;
+
+        write!(f, "Supplementary groups ({}): ", self.groups.len())?;
+        for &gid in &self.groups {
+            write!(f, "{} ({}) ", name(group(gid)), gid)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{self, Fork};
+
+    const NOBODY: u32 = 65534;
+
+    #[test]
+    fn fs_ids_follow_effective() {
+        assert_eq!(getfsuid(), geteuid());
+        assert_eq!(getfsgid(), getegid());
+    }
+
+    #[test]
+    fn current() {
+        let creds = Credentials::current().unwrap();
+        assert_eq!(creds.uid, getresuid().unwrap());
+        assert_eq!(creds.gid, getresgid().unwrap());
+        assert_eq!((creds.uid.real, creds.uid.effective), (getuid(), geteuid()));
+        assert_eq!((creds.gid.real, creds.gid.effective), (getgid(), getegid()));
+        assert_eq!(creds.fsuid, creds.uid.effective);
+        assert_eq!(creds.fsgid, creds.gid.effective);
+        assert_eq!(creds.groups, getgroups().unwrap());
+
+        let text = creds.to_string();
+        assert!(text.starts_with("UID: real="), "{}", text);
+        assert_eq!(text.lines().count(), 3);
+    }
+
+    /// Run `f` in a child process, as changing the real, effective and
+    /// saved IDs applies to every thread of the test process.
+    fn in_child(f: fn() -> bool) -> bool {
+        match unsafe { process::fork() }.unwrap() {
+            Fork::Child => unsafe { libc::_exit(if f() { 0 } else { 1 }) },
+            Fork::Parent(pid) => {
+                let mut status = 0;
+                assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+                status == 0
+            }
+        }
+    }
+
+    #[test]
+    fn setfsuid_unprivileged() {
+        // The child only makes system calls, without allocating
+        assert!(in_child(|| {
+            if geteuid() == 0
+                && (setgroups(&[]).is_err()
+                    || setresgid(Some(NOBODY), Some(NOBODY), Some(NOBODY)).is_err()
+                    || setresuid(Some(NOBODY), Some(NOBODY), Some(NOBODY)).is_err())
+            {
+                return false;
+            }
+            let euid = geteuid();
+            let refused = matches!(
+                setfsuid(if euid == 0 { 1 } else { 0 }),
+                Err(e) if e.raw_os_error() == Some(libc::EPERM)
+            );
+            refused && getfsuid() == euid && setfsuid(euid).is_ok()
+        }));
+    }
+
+    #[test]
+    fn drop_privileges_to_real_ids() {
+        assert!(in_child(|| {
+            // Become a set-user-ID program: real ID nobody, effective ID
+            // unchanged, which needs privilege to arrange
+            let euid = geteuid();
+            if euid == 0 && setresuid(Some(NOBODY), Some(0), Some(0)).is_err() {
+                return false;
+            }
+            if drop_privileges().is_err() {
+                return false;
+            }
+            let uid = getresuid().unwrap_or(ResIds {
+                real: 0,
+                effective: 0,
+                saved: 0,
+            });
+            let real = getuid();
+            uid.effective == real
+                && uid.saved == real
+                && getfsuid() == real
+                && (euid != 0 || (real == NOBODY && setresuid(None, Some(0), None).is_err()))
+        }));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::limits;
+use libc::c_int;
+use std::io;
+use std::mem;
+use std::time::Duration;
+
+/// Whose resource usage `getrusage()` reports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RusageWho {
+    /// All threads of the calling process (`RUSAGE_SELF`).
+    Process,
+    /// All descendants that have terminated and been waited for, and
+    /// their own waited-for descendants (`RUSAGE_CHILDREN`).
+    Children,
+    /// The calling thread (`RUSAGE_THREAD`).
+    Thread,
+}
+
+impl RusageWho {
+    fn as_raw(self) -> c_int {
+        match self {
+            RusageWho::Process => libc::RUSAGE_SELF,
+            RusageWho::Children => libc::RUSAGE_CHILDREN,
+            RusageWho::Thread => libc::RUSAGE_THREAD,
+        }
+    }
+}
+
+/// The resource usage counters that Linux maintains in `struct rusage`;
+/// the others are always zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rusage {
+    pub user_time: Duration,
+    pub system_time: Duration,
+    /// The peak resident set size in kilobytes. For `RusageWho::Children`,
+    /// that of the largest child rather than a sum.
+    pub max_rss: u64,
+    /// Page faults serviced without I/O ("page reclaims").
+    pub minor_faults: u64,
+    /// Page faults that required I/O.
+    pub major_faults: u64,
+    /// Blocks read and written by the filesystem, in 512-byte units.
+    pub block_inputs: u64,
+    pub block_outputs: u64,
+    /// Switches because the process blocked waiting for a resource.
+    pub voluntary_switches: u64,
+    /// Switches because the time slice ran out or a higher-priority
+    /// process became runnable.
+    pub involuntary_switches: u64,
+}
+
+impl Rusage {
+    pub(crate) fn from_raw(ru: &libc::rusage) -> Rusage {
+        let time = |tv: libc::timeval| Duration::new(tv.tv_sec as u64, tv.tv_usec as u32 * 1000);
+        Rusage {
+            user_time: time(ru.ru_utime),
+            system_time: time(ru.ru_stime),
+            max_rss: ru.ru_maxrss as u64,
+            minor_faults: ru.ru_minflt as u64,
+            major_faults: ru.ru_majflt as u64,
+            block_inputs: ru.ru_inblock as u64,
+            block_outputs: ru.ru_oublock as u64,
+            voluntary_switches: ru.ru_nvcsw as u64,
+            involuntary_switches: ru.ru_nivcsw as u64,
+        }
+    }
+
+    /// The user plus system CPU time.
+    pub fn cpu_time(&self) -> Duration {
+        self.user_time + self.system_time
+    }
+}
+
+pub fn getrusage(who: RusageWho) -> io::Result<Rusage> {
+    let mut ru: libc::rusage = unsafe { mem::zeroed() };
+    let result = unsafe { libc::getrusage(who.as_raw(), &mut ru) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(Rusage::from_raw(&ru))
+    }
+}
+
+/// Process times from `times()`, converted from clock ticks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Times {
+    /// Real time elapsed since some arbitrary point in the past, useful
+    /// only as the difference between two calls.
+    pub elapsed: Duration,
+    pub user: Duration,
+    pub system: Duration,
+    /// The times of all waited-for children.
+    pub children_user: Duration,
+    pub children_system: Duration,
+}
+
+/// Convert a count of clock ticks (`_SC_CLK_TCK` per second) to a
+/// `Duration`.
+pub fn ticks_to_duration(ticks: u64) -> Duration {
+    let per_sec = limits::clock_ticks();
+    Duration::from_secs(ticks / per_sec)
+        + Duration::from_nanos(ticks % per_sec * 1_000_000_000 / per_sec)
+}
+
+pub fn times() -> io::Result<Times> {
+    let mut buf: libc::tms = unsafe { mem::zeroed() };
+    let result = unsafe { libc::times(&mut buf) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        let time = |ticks: libc::clock_t| ticks_to_duration(ticks as u64);
+        Ok(Times {
+            elapsed: time(result),
+            user: time(buf.tms_utime),
+            system: time(buf.tms_stime),
+            children_user: time(buf.tms_cutime),
+            children_system: time(buf.tms_cstime),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks() {
+        let per_sec = limits::clock_ticks();
+        assert_eq!(ticks_to_duration(0), Duration::ZERO);
+        assert_eq!(ticks_to_duration(per_sec), Duration::from_secs(1));
+        assert_eq!(
+            ticks_to_duration(1),
+            Duration::from_secs(1) / per_sec as u32
+        );
+        assert_eq!(
+            ticks_to_duration(3 * per_sec + 1),
+            Duration::from_secs(3) + ticks_to_duration(1)
+        );
+        // No overflow converting the fraction of a second
+        assert_eq!(ticks_to_duration(u64::MAX).as_secs(), u64::MAX / per_sec);
+    }
+}
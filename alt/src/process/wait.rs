@@ -0,0 +1,219 @@
+use super::Rusage;
+use libc::c_int;
+use std::fmt;
+use std::io;
+use std::mem;
+use std::ops::BitOr;
+
+/// The status of a child, as returned by `wait()` and decoded by the
+/// `W*()` macros.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExitStatus(c_int);
+
+impl ExitStatus {
+    pub const fn from_raw(status: c_int) -> ExitStatus {
+        ExitStatus(status)
+    }
+
+    pub const fn into_raw(self) -> c_int {
+        self.0
+    }
+
+    /// True if the child exited with status 0.
+    pub fn success(self) -> bool {
+        self.code() == Some(0)
+    }
+
+    /// The status passed to `exit()`, if the child exited normally.
+    pub fn code(self) -> Option<i32> {
+        if self.0 & 0x7f == 0 {
+            Some((self.0 >> 8) & 0xff)
+        } else {
+            None
+        }
+    }
+
+    /// The signal that killed the child.
+    pub fn signal(self) -> Option<i32> {
+        if ((self.0 & 0x7f) + 1) as i8 >> 1 > 0 {
+            Some(self.0 & 0x7f)
+        } else {
+            None
+        }
+    }
+
+    pub fn core_dumped(self) -> bool {
+        self.signal().is_some() && self.0 & 0x80 != 0
+    }
+
+    /// The signal that stopped the child, reported with
+    /// `WaitFlags::UNTRACED`.
+    pub fn stopped_signal(self) -> Option<i32> {
+        if self.0 & 0xff == 0x7f {
+            Some((self.0 >> 8) & 0xff)
+        } else {
+            None
+        }
+    }
+
+    /// True if the child was resumed by `SIGCONT`, reported with
+    /// `WaitFlags::CONTINUED`.
+    pub fn continued(self) -> bool {
+        self.0 == 0xffff
+    }
+}
+
+/// Renders like the TLPI `printWaitStatus()`, e.g. "exited, status=0" or
+/// "killed by signal 9 (core dumped)".
+impl fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(code) = self.code() {
+            write!(f, "exited, status={}", code)
+        } else if let Some(signal) = self.signal() {
+            write!(f, "killed by signal {}", signal)?;
+            if self.core_dumped() {
+                f.write_str(" (core dumped)")?;
+            }
+            Ok(())
+        } else if let Some(signal) = self.stopped_signal() {
+            write!(f, "stopped by signal {}", signal)
+        } else if self.continued() {
+            f.write_str("continued")
+        } else {
+            write!(f, "what happened? (status={:#x})", self.0)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WaitFlags(c_int);
+
+impl WaitFlags {
+    /// Return at once if no child has changed state.
+    pub const NOHANG: WaitFlags = WaitFlags(libc::WNOHANG);
+    /// Also report children stopped by a signal.
+    pub const UNTRACED: WaitFlags = WaitFlags(libc::WUNTRACED);
+    /// Also report stopped children resumed by `SIGCONT`.
+    pub const CONTINUED: WaitFlags = WaitFlags(libc::WCONTINUED);
+
+    pub const fn empty() -> WaitFlags {
+        WaitFlags(0)
+    }
+
+    pub const fn bits(self) -> c_int {
+        self.0
+    }
+
+    pub const fn contains(self, other: WaitFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for WaitFlags {
+    type Output = WaitFlags;
+
+    fn bitor(self, rhs: WaitFlags) -> WaitFlags {
+        WaitFlags(self.0 | rhs.0)
+    }
+}
+
+/// A child reported by `wait4()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WaitedChild {
+    pub pid: u32,
+    pub status: ExitStatus,
+    /// The resources used by the child (and its waited-for descendants)
+    /// if it terminated; all zero if it only stopped or continued.
+    pub rusage: Rusage,
+}
+
+/// Wait for a child to change state, as `waitpid()` does, also returning
+/// its resource usage.
+///
+/// `pid` selects the children as for `waitpid()`: a PID, -1 for any
+/// child, 0 for any in our process group, or -PGID for any in that group.
+/// Returns `None` only with `WaitFlags::NOHANG`, if no child has changed
+/// state yet.
+pub fn wait4(pid: i32, flags: WaitFlags) -> io::Result<Option<WaitedChild>> {
+    let mut status = 0;
+    let mut ru: libc::rusage = unsafe { mem::zeroed() };
+    let result = unsafe { libc::wait4(pid, &mut status, flags.0, &mut ru) };
+    match result {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Ok(None),
+        pid => Ok(Some(WaitedChild {
+            pid: pid as u32,
+            status: ExitStatus(status),
+            rusage: Rusage::from_raw(&ru),
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_status() {
+        // (raw, code, signal, core dumped, stopped signal, continued, text)
+        let table = [
+            (
+                0x0000,
+                Some(0),
+                None,
+                false,
+                None,
+                false,
+                "exited, status=0",
+            ),
+            (
+                0x0100,
+                Some(1),
+                None,
+                false,
+                None,
+                false,
+                "exited, status=1",
+            ),
+            (
+                0x0009,
+                None,
+                Some(9),
+                false,
+                None,
+                false,
+                "killed by signal 9",
+            ),
+            (
+                0x0089,
+                None,
+                Some(9),
+                true,
+                None,
+                false,
+                "killed by signal 9 (core dumped)",
+            ),
+            (
+                0x137f,
+                None,
+                None,
+                false,
+                Some(19),
+                false,
+                "stopped by signal 19",
+            ),
+            (0xffff, None, None, false, None, true, "continued"),
+        ];
+        for (raw, code, signal, core_dumped, stopped, continued, text) in table {
+            let status = ExitStatus::from_raw(raw);
+            assert_eq!(status.code(), code, "{:#x}", raw);
+            assert_eq!(status.signal(), signal, "{:#x}", raw);
+            assert_eq!(status.core_dumped(), core_dumped, "{:#x}", raw);
+            assert_eq!(status.stopped_signal(), stopped, "{:#x}", raw);
+            assert_eq!(status.continued(), continued, "{:#x}", raw);
+            assert_eq!(status.to_string(), text);
+            assert_eq!(status.success(), raw == 0);
+            assert_eq!(status.into_raw(), raw);
+        }
+    }
+}
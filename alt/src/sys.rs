@@ -1,2 +1,5 @@
 pub mod fd;
 pub mod fs;
+pub mod io;
+pub mod net;
+pub mod process;
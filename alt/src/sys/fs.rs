@@ -1,9 +1,15 @@
-use crate::os::fd::{AsRawFd, FromRawFd, RawFd};
+pub mod xattr;
+
+use crate::os::fd::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
 use crate::sys::fd::FileDesc;
-use libc::{c_int, mode_t, off64_t};
-use std::ffi::{CStr, CString};
+use libc::{c_char, c_int, c_uint, mode_t, off64_t};
+use std::ffi::{CStr, CString, OsString};
 use std::io::{self, SeekFrom};
-use std::path::Path;
+use std::mem;
+use std::os::unix::ffi::OsStringExt;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub struct File(FileDesc);
 
@@ -12,6 +18,28 @@ pub struct OpenOptions {
     write: bool,
     truncate: bool,
     create: bool,
+    create_new: bool,
+    mode: mode_t,
+}
+
+#[derive(Clone, Copy)]
+pub struct FileTimes {
+    accessed: libc::timespec,
+    modified: libc::timespec,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FilePermissions {
+    mode: mode_t,
+}
+
+#[derive(Clone)]
+pub struct FileAttr {
+    stat: libc::stat64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct FileType {
     mode: mode_t,
 }
 
@@ -36,6 +64,33 @@ impl File {
         }
     }
 
+    pub fn open_at(dirfd: RawFd, path: &Path, opts: &OpenOptions) -> io::Result<File> {
+        let path = cstr(path)?;
+        let flags = opts.get_access_mode()? | opts.get_creation_mode()?;
+        let fd = unsafe { libc::openat(dirfd, path.as_ptr(), flags, opts.mode as c_int) };
+        if fd == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(File(unsafe { FileDesc::from_raw_fd(fd) }))
+        }
+    }
+
+    pub fn open_at2(
+        dirfd: RawFd,
+        path: &Path,
+        opts: &OpenOptions,
+        resolve: u64,
+    ) -> io::Result<File> {
+        let flags = opts.get_access_mode()? | opts.get_creation_mode()?;
+        // openat2() rejects a mode unless a file may be created
+        let mode = match flags & libc::O_CREAT {
+            0 => 0,
+            _ => opts.mode,
+        };
+        let fd = openat2(dirfd, path, flags, mode, resolve)?;
+        Ok(File(unsafe { FileDesc::from_raw_fd(fd) }))
+    }
+
     pub fn close(self) -> io::Result<()> {
         self.0.close()
     }
@@ -53,6 +108,60 @@ impl File {
         Ok(())
     }
 
+    pub fn fsync(&self) -> io::Result<()> {
+        let result = unsafe { libc::fsync(self.as_raw_fd()) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn datasync(&self) -> io::Result<()> {
+        let result = unsafe { libc::fdatasync(self.as_raw_fd()) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn sync_range(&self, offset: u64, nbytes: u64, flags: c_uint) -> io::Result<()> {
+        let (offset, nbytes): (off64_t, off64_t) = match (offset.try_into(), nbytes.try_into()) {
+            (Ok(offset), Ok(nbytes)) => (offset, nbytes),
+            _ => return Err(io::Error::from_raw_os_error(libc::EINVAL)),
+        };
+        let result = unsafe { libc::sync_file_range(self.as_raw_fd(), offset, nbytes, flags) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn syncfs(&self) -> io::Result<()> {
+        let result = unsafe { libc::syncfs(self.as_raw_fd()) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn advise(&self, offset: u64, len: u64, advice: c_int) -> io::Result<()> {
+        let (offset, len): (off64_t, off64_t) = match (offset.try_into(), len.try_into()) {
+            (Ok(offset), Ok(len)) => (offset, len),
+            _ => return Err(io::Error::from_raw_os_error(libc::EINVAL)),
+        };
+        // posix_fadvise() returns the error number instead of setting errno
+        let result = unsafe { libc::posix_fadvise64(self.as_raw_fd(), offset, len, advice) };
+        if result != 0 {
+            Err(io::Error::from_raw_os_error(result))
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn seek(&self, pos: SeekFrom) -> io::Result<u64> {
         let (whence, pos) = match pos {
             SeekFrom::Start(off) => (libc::SEEK_SET, off as i64),
@@ -68,6 +177,82 @@ impl File {
             Ok(n as u64)
         }
     }
+
+    pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
+        let times = [times.accessed, times.modified];
+        let result = unsafe { libc::futimens(self.0.as_raw_fd(), times.as_ptr()) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn set_permissions(&self, perm: &FilePermissions) -> io::Result<()> {
+        let result = unsafe { libc::fchmod(self.0.as_raw_fd(), perm.mode) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn file_attr(&self) -> io::Result<FileAttr> {
+        let mut stat: libc::stat64 = unsafe { mem::zeroed() };
+        let result = unsafe { libc::fstat64(self.as_raw_fd(), &mut stat) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(FileAttr { stat })
+        }
+    }
+
+    pub fn seek_data(&self, offset: u64) -> io::Result<Option<u64>> {
+        let n = unsafe { libc::lseek64(self.as_raw_fd(), offset as off64_t, libc::SEEK_DATA) };
+        if n == -1 {
+            let err = io::Error::last_os_error();
+            match err.raw_os_error() {
+                Some(libc::ENXIO) => Ok(None),
+                _ => Err(err),
+            }
+        } else {
+            Ok(Some(n as u64))
+        }
+    }
+
+    pub fn seek_hole(&self, offset: u64) -> io::Result<u64> {
+        let n = unsafe { libc::lseek64(self.as_raw_fd(), offset as off64_t, libc::SEEK_HOLE) };
+        if n == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(n as u64)
+        }
+    }
+
+    pub fn truncate(&self, size: u64) -> io::Result<()> {
+        let size: off64_t = size
+            .try_into()
+            .map_err(|_| io::Error::from_raw_os_error(libc::EINVAL))?;
+        let result = unsafe { libc::ftruncate64(self.as_raw_fd(), size) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn allocate(&self, mode: c_int, offset: u64, len: u64) -> io::Result<()> {
+        let (offset, len): (off64_t, off64_t) = match (offset.try_into(), len.try_into()) {
+            (Ok(offset), Ok(len)) => (offset, len),
+            _ => return Err(io::Error::from_raw_os_error(libc::EINVAL)),
+        };
+        let result = unsafe { libc::fallocate64(self.as_raw_fd(), mode, offset, len) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl AsRawFd for File {
@@ -77,6 +262,18 @@ impl AsRawFd for File {
     }
 }
 
+impl FromRawFd for File {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self(FromRawFd::from_raw_fd(fd))
+    }
+}
+
+impl IntoRawFd for File {
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
 impl OpenOptions {
     pub fn new() -> Self {
         OpenOptions {
@@ -84,6 +281,7 @@ impl OpenOptions {
             write: false,
             truncate: false,
             create: false,
+            create_new: false,
             mode: 0o666,
         }
     }
@@ -104,6 +302,10 @@ impl OpenOptions {
         self.create = create;
     }
 
+    pub fn create_new(&mut self, create_new: bool) {
+        self.create_new = create_new;
+    }
+
     pub fn mode(&mut self, mode: u32) {
         self.mode = mode as mode_t;
     }
@@ -122,23 +324,833 @@ impl OpenOptions {
         match (self.write, ) {
             (true, ) => {}
             (false, ) => {
-                if self.truncate || self.create {
+                if self.truncate || self.create || self.create_new {
                     return Err(io::Error::from_raw_os_error(libc::EINVAL));
                 }
             }
         }
 
-        Ok(match (self.create, self.truncate, ) {
-            (false, false, ) => 0,
-            (true, false, ) => libc::O_CREAT,
-            (false, true, ) => libc::O_TRUNC,
-            (true, true, ) => libc::O_CREAT | libc::O_TRUNC,
+        Ok(match (self.create, self.truncate, self.create_new) {
+            (false, false, false) => 0,
+            (true, false, false) => libc::O_CREAT,
+            (false, true, false) => libc::O_TRUNC,
+            (true, true, false) => libc::O_CREAT | libc::O_TRUNC,
+            (_, _, true) => libc::O_CREAT | libc::O_EXCL,
         })
     }
 }
 
+impl FileAttr {
+    pub fn size(&self) -> u64 {
+        self.stat.st_size as u64
+    }
+
+    pub fn perm(&self) -> FilePermissions {
+        FilePermissions::from_mode(self.stat.st_mode)
+    }
+
+    pub fn file_type(&self) -> FileType {
+        FileType {
+            mode: self.stat.st_mode,
+        }
+    }
+
+    pub fn accessed(&self) -> SystemTime {
+        system_time(self.stat.st_atime, self.stat.st_atime_nsec)
+    }
+
+    pub fn modified(&self) -> SystemTime {
+        system_time(self.stat.st_mtime, self.stat.st_mtime_nsec)
+    }
+
+    pub fn changed(&self) -> SystemTime {
+        system_time(self.stat.st_ctime, self.stat.st_ctime_nsec)
+    }
+
+    pub fn as_inner(&self) -> &libc::stat64 {
+        &self.stat
+    }
+}
+
+impl FileTimes {
+    pub fn set_accessed(&mut self, t: SystemTime) {
+        self.accessed = timespec(t);
+    }
+
+    pub fn set_accessed_now(&mut self) {
+        self.accessed = special_timespec(libc::UTIME_NOW);
+    }
+
+    pub fn set_modified(&mut self, t: SystemTime) {
+        self.modified = timespec(t);
+    }
+
+    pub fn set_modified_now(&mut self) {
+        self.modified = special_timespec(libc::UTIME_NOW);
+    }
+}
+
+impl Default for FileTimes {
+    fn default() -> Self {
+        let omit = special_timespec(libc::UTIME_OMIT);
+        FileTimes {
+            accessed: omit,
+            modified: omit,
+        }
+    }
+}
+
+fn special_timespec(nsec: libc::c_long) -> libc::timespec {
+    libc::timespec {
+        tv_sec: 0,
+        tv_nsec: nsec,
+    }
+}
+
+fn timespec(t: SystemTime) -> libc::timespec {
+    match t.duration_since(UNIX_EPOCH) {
+        Ok(d) => libc::timespec {
+            tv_sec: d.as_secs() as libc::time_t,
+            tv_nsec: d.subsec_nanos() as libc::c_long,
+        },
+        // Before the epoch: tv_nsec must still lie in 0..1_000_000_000
+        Err(e) => {
+            let d = e.duration();
+            let (sec, nsec) = (d.as_secs() as libc::time_t, d.subsec_nanos());
+            match nsec {
+                0 => libc::timespec {
+                    tv_sec: -sec,
+                    tv_nsec: 0,
+                },
+                _ => libc::timespec {
+                    tv_sec: -sec - 1,
+                    tv_nsec: (1_000_000_000 - nsec) as libc::c_long,
+                },
+            }
+        }
+    }
+}
+
+fn system_time(sec: libc::time_t, nsec: i64) -> SystemTime {
+    if sec >= 0 {
+        UNIX_EPOCH + Duration::new(sec as u64, nsec as u32)
+    } else {
+        UNIX_EPOCH - Duration::from_secs(sec.unsigned_abs()) + Duration::from_nanos(nsec as u64)
+    }
+}
+
+impl FilePermissions {
+    pub fn from_mode(mode: u32) -> FilePermissions {
+        FilePermissions {
+            mode: mode & 0o7777,
+        }
+    }
+
+    pub fn mode(&self) -> u32 {
+        self.mode
+    }
+
+    pub fn readonly(&self) -> bool {
+        self.mode & 0o222 == 0
+    }
+
+    pub fn set_readonly(&mut self, readonly: bool) {
+        if readonly {
+            self.mode &= !0o222;
+        } else {
+            self.mode |= 0o222;
+        }
+    }
+}
+
+impl FileType {
+    pub fn is_dir(&self) -> bool {
+        self.is(libc::S_IFDIR)
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.is(libc::S_IFREG)
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.is(libc::S_IFLNK)
+    }
+
+    pub fn is(&self, mode: mode_t) -> bool {
+        self.mode & libc::S_IFMT == mode
+    }
+}
+
 impl Default for OpenOptions {
     fn default() -> Self {
         OpenOptions::new()
     }
 }
+
+pub(crate) fn cstr(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_encoded_bytes()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "file name contained an unexpected NUL byte",
+        )
+    })
+}
+
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(OsString::from_vec(bytes))
+}
+
+/// Open an unnamed regular file in the filesystem containing `dir`.
+pub fn open_tmpfile(dir: &Path, mode: u32) -> io::Result<File> {
+    let dir = cstr(dir)?;
+    let fd = unsafe {
+        libc::open(
+            dir.as_ptr(),
+            libc::O_TMPFILE | libc::O_RDWR | libc::O_CLOEXEC,
+            mode as c_int,
+        )
+    };
+    if fd == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(File(unsafe { FileDesc::from_raw_fd(fd) }))
+    }
+}
+
+/// Create and open a file whose name is `template` with its trailing
+/// "XXXXXX" replaced to make it unique.
+pub fn mkstemp(template: &Path) -> io::Result<(File, PathBuf)> {
+    let mut template = cstr(template)?.into_bytes_with_nul();
+    let fd = unsafe { libc::mkostemp(template.as_mut_ptr() as *mut c_char, libc::O_CLOEXEC) };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    template.pop();
+    let file = File(unsafe { FileDesc::from_raw_fd(fd) });
+    Ok((file, path_from_bytes(template)))
+}
+
+/// Like `mkstemp`, but create a directory (with mode 0700).
+pub fn mkdtemp(template: &Path) -> io::Result<PathBuf> {
+    let mut template = cstr(template)?.into_bytes_with_nul();
+    let result = unsafe { libc::mkdtemp(template.as_mut_ptr() as *mut c_char) };
+    if result.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    template.pop();
+    Ok(path_from_bytes(template))
+}
+
+pub fn unlink(path: &Path) -> io::Result<()> {
+    let path = cstr(path)?;
+    let result = unsafe { libc::unlink(path.as_ptr()) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn rmdir(path: &Path) -> io::Result<()> {
+    let path = cstr(path)?;
+    let result = unsafe { libc::rmdir(path.as_ptr()) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn rename(old: &Path, new: &Path) -> io::Result<()> {
+    let old = cstr(old)?;
+    let new = cstr(new)?;
+    let result = unsafe { libc::rename(old.as_ptr(), new.as_ptr()) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn link(original: &Path, link: &Path) -> io::Result<()> {
+    let original = cstr(original)?;
+    let link = cstr(link)?;
+    let result = unsafe {
+        libc::linkat(
+            libc::AT_FDCWD,
+            original.as_ptr(),
+            libc::AT_FDCWD,
+            link.as_ptr(),
+            0,
+        )
+    };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Give a name to an open file, such as one created with `O_TMPFILE`.
+pub fn link_fd(fd: RawFd, link: &Path) -> io::Result<()> {
+    let link = cstr(link)?;
+    let result = unsafe {
+        libc::linkat(
+            fd,
+            c"".as_ptr(),
+            libc::AT_FDCWD,
+            link.as_ptr(),
+            libc::AT_EMPTY_PATH,
+        )
+    };
+    if result != -1 {
+        return Ok(());
+    }
+    let err = io::Error::last_os_error();
+    if err.raw_os_error() != Some(libc::ENOENT) {
+        return Err(err);
+    }
+
+    // AT_EMPTY_PATH requires CAP_DAC_READ_SEARCH; following the /proc
+    // magic link achieves the same for unprivileged callers.
+    let proc_path = CString::new(format!("/proc/self/fd/{}", fd)).unwrap();
+    let result = unsafe {
+        libc::linkat(
+            libc::AT_FDCWD,
+            proc_path.as_ptr(),
+            libc::AT_FDCWD,
+            link.as_ptr(),
+            libc::AT_SYMLINK_FOLLOW,
+        )
+    };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn remove_dir_all(path: &Path) -> io::Result<()> {
+    let c_path = cstr(path)?;
+    match remove_dir_all_at(libc::AT_FDCWD, &c_path) {
+        // O_NOFOLLOW refuses a symbolic link given as the top-level path,
+        // which is then removed like any other link
+        Err(e) if matches!(e.raw_os_error(), Some(libc::ELOOP | libc::ENOTDIR)) => {
+            match lstat(path) {
+                Ok(attr) if attr.file_type().is_symlink() => unlink(path),
+                _ => Err(e),
+            }
+        }
+        result => result,
+    }
+}
+
+struct DirStream(*mut libc::DIR);
+
+impl Drop for DirStream {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = libc::closedir(self.0);
+        }
+    }
+}
+
+pub struct ReadDir {
+    dir: DirStream,
+    root: PathBuf,
+}
+
+pub struct DirEntry {
+    path: PathBuf,
+    ino: u64,
+    d_type: u8,
+}
+
+pub fn read_dir(path: &Path) -> io::Result<ReadDir> {
+    let cpath = cstr(path)?;
+    let fd = unsafe {
+        libc::open(
+            cpath.as_ptr(),
+            libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC,
+        )
+    };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let dirp = unsafe { libc::fdopendir(fd) };
+    if dirp.is_null() {
+        let err = io::Error::last_os_error();
+        unsafe {
+            let _ = libc::close(fd);
+        }
+        return Err(err);
+    }
+    Ok(ReadDir {
+        dir: DirStream(dirp),
+        root: path.to_path_buf(),
+    })
+}
+
+impl Iterator for ReadDir {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<DirEntry>> {
+        loop {
+            unsafe {
+                *libc::__errno_location() = 0;
+            }
+            let entry = unsafe { libc::readdir64(self.dir.0) };
+            if entry.is_null() {
+                let errno = unsafe { *libc::__errno_location() };
+                return match errno {
+                    0 => None,
+                    errno => Some(Err(io::Error::from_raw_os_error(errno))),
+                };
+            }
+
+            let (ino, d_type, name) = unsafe {
+                let entry = &*entry;
+                (
+                    entry.d_ino,
+                    entry.d_type,
+                    CStr::from_ptr(entry.d_name.as_ptr()),
+                )
+            };
+            if name == c"." || name == c".." {
+                continue;
+            }
+            let name = OsString::from_vec(name.to_bytes().to_vec());
+            return Some(Ok(DirEntry {
+                path: self.root.join(name),
+                ino,
+                d_type,
+            }));
+        }
+    }
+}
+
+impl DirEntry {
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    pub fn file_name(&self) -> OsString {
+        self.path.file_name().unwrap_or_default().to_os_string()
+    }
+
+    pub fn ino(&self) -> u64 {
+        self.ino
+    }
+
+    /// The file type from the directory entry, falling back to `lstat()`
+    /// on filesystems that do not record it. The `DT_*` values are the
+    /// `S_IF*` bits shifted down.
+    pub fn file_type(&self) -> io::Result<FileType> {
+        match self.d_type {
+            libc::DT_UNKNOWN => lstat(&self.path).map(|attr| attr.file_type()),
+            d_type => Ok(FileType {
+                mode: (d_type as mode_t) << 12,
+            }),
+        }
+    }
+}
+
+fn remove_dir_all_at(parent: RawFd, name: &CStr) -> io::Result<()> {
+    // O_NOFOLLOW makes sure we never descend through a symbolic link that
+    // has replaced a directory
+    let fd = unsafe {
+        libc::openat(
+            parent,
+            name.as_ptr(),
+            libc::O_RDONLY | libc::O_DIRECTORY | libc::O_NOFOLLOW | libc::O_CLOEXEC,
+        )
+    };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let dirp = unsafe { libc::fdopendir(fd) };
+    if dirp.is_null() {
+        let err = io::Error::last_os_error();
+        unsafe {
+            let _ = libc::close(fd);
+        }
+        return Err(err);
+    }
+    let dir = DirStream(dirp);
+
+    loop {
+        unsafe {
+            *libc::__errno_location() = 0;
+        }
+        let entry = unsafe { libc::readdir64(dir.0) };
+        if entry.is_null() {
+            let errno = unsafe { *libc::__errno_location() };
+            if errno != 0 {
+                return Err(io::Error::from_raw_os_error(errno));
+            }
+            break;
+        }
+
+        let (d_type, child) =
+            unsafe { ((*entry).d_type, CStr::from_ptr((*entry).d_name.as_ptr())) };
+        if child == c"." || child == c".." {
+            continue;
+        }
+
+        if d_type == libc::DT_DIR {
+            remove_dir_all_at(fd, child)?;
+            continue;
+        }
+
+        // When the filesystem does not report d_type, unlinkat() tells us
+        // whether the entry was a directory after all
+        let result = unsafe { libc::unlinkat(fd, child.as_ptr(), 0) };
+        if result == -1 {
+            let err = io::Error::last_os_error();
+            match err.raw_os_error() {
+                Some(libc::EISDIR) if d_type == libc::DT_UNKNOWN => remove_dir_all_at(fd, child)?,
+                _ => return Err(err),
+            }
+        }
+    }
+    drop(dir);
+
+    let result = unsafe { libc::unlinkat(parent, name.as_ptr(), libc::AT_REMOVEDIR) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn stat(path: &Path) -> io::Result<FileAttr> {
+    let path = cstr(path)?;
+    let mut stat: libc::stat64 = unsafe { mem::zeroed() };
+    let result = unsafe { libc::stat64(path.as_ptr(), &mut stat) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(FileAttr { stat })
+    }
+}
+
+pub fn lstat(path: &Path) -> io::Result<FileAttr> {
+    let path = cstr(path)?;
+    let mut stat: libc::stat64 = unsafe { mem::zeroed() };
+    let result = unsafe { libc::lstat64(path.as_ptr(), &mut stat) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(FileAttr { stat })
+    }
+}
+
+pub fn rename2(old: &Path, new: &Path, flags: c_uint) -> io::Result<()> {
+    rename_at(libc::AT_FDCWD, old, libc::AT_FDCWD, new, flags)
+}
+
+pub fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+    let original = cstr(original)?;
+    let link = cstr(link)?;
+    let result = unsafe { libc::symlink(original.as_ptr(), link.as_ptr()) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn readlink(path: &Path) -> io::Result<PathBuf> {
+    let path = cstr(path)?;
+    readlink_with(|buf: &mut Vec<u8>| unsafe {
+        libc::readlink(
+            path.as_ptr(),
+            buf.as_mut_ptr() as *mut c_char,
+            buf.capacity(),
+        )
+    })
+}
+
+/// Call a readlink()-like function with a growing buffer until the result
+/// is known not to have been truncated.
+pub fn readlink_with<F>(mut f: F) -> io::Result<PathBuf>
+where
+    F: FnMut(&mut Vec<u8>) -> libc::ssize_t,
+{
+    let mut buf = Vec::with_capacity(256);
+    loop {
+        let n = f(&mut buf);
+        if n == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let n = n as usize;
+        // readlink() silently truncates, so a full buffer may not hold the
+        // whole target
+        if n < buf.capacity() {
+            unsafe {
+                buf.set_len(n);
+            }
+            buf.shrink_to_fit();
+            return Ok(path_from_bytes(buf));
+        }
+        // len() is still 0, so reserve() must be asked for more than the
+        // current capacity
+        buf.reserve(buf.capacity() * 2);
+    }
+}
+
+pub fn canonicalize(path: &Path) -> io::Result<PathBuf> {
+    let path = cstr(path)?;
+    let resolved = unsafe { libc::realpath(path.as_ptr(), ptr::null_mut()) };
+    if resolved.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    let bytes = unsafe { CStr::from_ptr(resolved) }.to_bytes().to_vec();
+    unsafe { libc::free(resolved as *mut libc::c_void) };
+    Ok(path_from_bytes(bytes))
+}
+
+fn openat2(
+    dirfd: RawFd,
+    path: &Path,
+    flags: c_int,
+    mode: mode_t,
+    resolve: u64,
+) -> io::Result<RawFd> {
+    let path = cstr(path)?;
+    let how = libc::open_how {
+        flags: flags as u64,
+        mode: mode as u64,
+        resolve,
+    };
+    // glibc has no wrapper for openat2()
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_openat2,
+            dirfd,
+            path.as_ptr(),
+            &how as *const libc::open_how,
+            mem::size_of::<libc::open_how>(),
+        )
+    };
+    if fd == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(fd as RawFd)
+    }
+}
+
+const O_PATH_DIR: c_int = libc::O_PATH | libc::O_DIRECTORY | libc::O_CLOEXEC;
+
+pub fn open_dir_at(dirfd: RawFd, path: &Path) -> io::Result<OwnedFd> {
+    let path = cstr(path)?;
+    let fd = unsafe { libc::openat(dirfd, path.as_ptr(), O_PATH_DIR) };
+    if fd == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    }
+}
+
+pub fn open_dir_at2(dirfd: RawFd, path: &Path, resolve: u64) -> io::Result<OwnedFd> {
+    let fd = openat2(dirfd, path, O_PATH_DIR, 0, resolve)?;
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+pub fn stat_at(dirfd: RawFd, path: &Path, follow: bool) -> io::Result<FileAttr> {
+    let path = cstr(path)?;
+    let flags = if follow { 0 } else { libc::AT_SYMLINK_NOFOLLOW };
+    let mut stat: libc::stat64 = unsafe { mem::zeroed() };
+    let result = unsafe { libc::fstatat64(dirfd, path.as_ptr(), &mut stat, flags) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(FileAttr { stat })
+    }
+}
+
+pub fn mkdir_at(dirfd: RawFd, path: &Path, mode: u32) -> io::Result<()> {
+    let path = cstr(path)?;
+    let result = unsafe { libc::mkdirat(dirfd, path.as_ptr(), mode as mode_t) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn unlink_at(dirfd: RawFd, path: &Path, flags: c_int) -> io::Result<()> {
+    let path = cstr(path)?;
+    let result = unsafe { libc::unlinkat(dirfd, path.as_ptr(), flags) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn rename_at(
+    old_dirfd: RawFd,
+    old: &Path,
+    new_dirfd: RawFd,
+    new: &Path,
+    flags: c_uint,
+) -> io::Result<()> {
+    let old = cstr(old)?;
+    let new = cstr(new)?;
+    let result =
+        unsafe { libc::renameat2(old_dirfd, old.as_ptr(), new_dirfd, new.as_ptr(), flags) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn readlink_at(dirfd: RawFd, path: &Path) -> io::Result<PathBuf> {
+    let path = cstr(path)?;
+    readlink_with(|buf: &mut Vec<u8>| unsafe {
+        libc::readlinkat(
+            dirfd,
+            path.as_ptr(),
+            buf.as_mut_ptr() as *mut c_char,
+            buf.capacity(),
+        )
+    })
+}
+
+pub fn set_perm(path: &Path, perm: &FilePermissions) -> io::Result<()> {
+    let path = cstr(path)?;
+    let result = unsafe { libc::chmod(path.as_ptr(), perm.mode) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn set_perm_at(dirfd: RawFd, path: &Path, perm: &FilePermissions) -> io::Result<()> {
+    let path = cstr(path)?;
+    let result = unsafe { libc::fchmodat(dirfd, path.as_ptr(), perm.mode, 0) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn chown(path: &Path, uid: u32, gid: u32) -> io::Result<()> {
+    let path = cstr(path)?;
+    let result = unsafe { libc::chown(path.as_ptr(), uid, gid) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn lchown(path: &Path, uid: u32, gid: u32) -> io::Result<()> {
+    let path = cstr(path)?;
+    let result = unsafe { libc::lchown(path.as_ptr(), uid, gid) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn fchown(fd: RawFd, uid: u32, gid: u32) -> io::Result<()> {
+    let result = unsafe { libc::fchown(fd, uid, gid) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn chown_at(dirfd: RawFd, path: &Path, uid: u32, gid: u32, flags: c_int) -> io::Result<()> {
+    let path = cstr(path)?;
+    let result = unsafe { libc::fchownat(dirfd, path.as_ptr(), uid, gid, flags) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn set_times_at(dirfd: RawFd, path: &Path, times: FileTimes, flags: c_int) -> io::Result<()> {
+    let path = cstr(path)?;
+    let times = [times.accessed, times.modified];
+    let result = unsafe { libc::utimensat(dirfd, path.as_ptr(), times.as_ptr(), flags) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn umask(mask: u32) -> u32 {
+    unsafe { libc::umask(mask & 0o777) }
+}
+
+pub fn access(path: &Path, mode: c_int) -> io::Result<()> {
+    let path = cstr(path)?;
+    let result = unsafe { libc::access(path.as_ptr(), mode) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn access_at(dirfd: RawFd, path: &Path, mode: c_int, flags: c_int) -> io::Result<()> {
+    let path = cstr(path)?;
+    let result = unsafe { libc::faccessat(dirfd, path.as_ptr(), mode, flags) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn statvfs(path: &Path) -> io::Result<libc::statvfs64> {
+    let path = cstr(path)?;
+    let mut buf: libc::statvfs64 = unsafe { mem::zeroed() };
+    let result = unsafe { libc::statvfs64(path.as_ptr(), &mut buf) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(buf)
+    }
+}
+
+pub fn fstatvfs(fd: RawFd) -> io::Result<libc::statvfs64> {
+    let mut buf: libc::statvfs64 = unsafe { mem::zeroed() };
+    let result = unsafe { libc::fstatvfs64(fd, &mut buf) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(buf)
+    }
+}
+
+pub fn statfs(path: &Path) -> io::Result<libc::statfs64> {
+    let path = cstr(path)?;
+    let mut buf: libc::statfs64 = unsafe { mem::zeroed() };
+    let result = unsafe { libc::statfs64(path.as_ptr(), &mut buf) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(buf)
+    }
+}
+
+pub fn fstatfs(fd: RawFd) -> io::Result<libc::statfs64> {
+    let mut buf: libc::statfs64 = unsafe { mem::zeroed() };
+    let result = unsafe { libc::fstatfs64(fd, &mut buf) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(buf)
+    }
+}
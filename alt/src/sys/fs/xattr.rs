@@ -0,0 +1,126 @@
+use super::cstr;
+use crate::os::fd::RawFd;
+use libc::{c_char, c_int, c_void, size_t, ssize_t};
+use std::ffi::{CString, OsStr};
+use std::io;
+use std::path::Path;
+use std::ptr;
+
+/// The file an extended attribute call applies to, selecting between the
+/// plain, `l` (symbolic link itself) and `f` (descriptor) variants.
+#[derive(Clone, Copy)]
+pub enum Target<'a> {
+    Path(&'a Path),
+    Link(&'a Path),
+    Fd(RawFd),
+}
+
+enum RawTarget {
+    Path(CString),
+    Link(CString),
+    Fd(RawFd),
+}
+
+impl RawTarget {
+    fn new(target: Target<'_>) -> io::Result<RawTarget> {
+        Ok(match target {
+            Target::Path(path) => RawTarget::Path(cstr(path)?),
+            Target::Link(path) => RawTarget::Link(cstr(path)?),
+            Target::Fd(fd) => RawTarget::Fd(fd),
+        })
+    }
+}
+
+fn name_cstr(name: &OsStr) -> io::Result<CString> {
+    cstr(Path::new(name))
+}
+
+/// Call `f` first with an empty buffer to learn the size needed and then
+/// with a buffer of that size, starting again if the value grew in between
+/// (`ERANGE`).
+fn read_sized<F>(mut f: F) -> io::Result<Vec<u8>>
+where
+    F: FnMut(*mut c_void, size_t) -> ssize_t,
+{
+    loop {
+        let size = f(ptr::null_mut(), 0);
+        if size == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut buf = Vec::<u8>::with_capacity(size as usize);
+        let len = f(buf.as_mut_ptr() as *mut c_void, buf.capacity());
+        if len == -1 {
+            let err = io::Error::last_os_error();
+            if err.raw_os_error() == Some(libc::ERANGE) {
+                continue;
+            }
+            return Err(err);
+        }
+        unsafe { buf.set_len(len as usize) };
+        return Ok(buf);
+    }
+}
+
+pub fn get(target: Target<'_>, name: &OsStr) -> io::Result<Vec<u8>> {
+    let target = RawTarget::new(target)?;
+    let name = name_cstr(name)?;
+    read_sized(|buf, size| unsafe {
+        match target {
+            RawTarget::Path(ref path) => libc::getxattr(path.as_ptr(), name.as_ptr(), buf, size),
+            RawTarget::Link(ref path) => libc::lgetxattr(path.as_ptr(), name.as_ptr(), buf, size),
+            RawTarget::Fd(fd) => libc::fgetxattr(fd, name.as_ptr(), buf, size),
+        }
+    })
+}
+
+pub fn set(target: Target<'_>, name: &OsStr, value: &[u8], flags: c_int) -> io::Result<()> {
+    let target = RawTarget::new(target)?;
+    let name = name_cstr(name)?;
+    let (value, size) = (value.as_ptr() as *const c_void, value.len());
+    let result = unsafe {
+        match target {
+            RawTarget::Path(ref path) => {
+                libc::setxattr(path.as_ptr(), name.as_ptr(), value, size, flags)
+            }
+            RawTarget::Link(ref path) => {
+                libc::lsetxattr(path.as_ptr(), name.as_ptr(), value, size, flags)
+            }
+            RawTarget::Fd(fd) => libc::fsetxattr(fd, name.as_ptr(), value, size, flags),
+        }
+    };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Return the attribute names as a sequence of NUL-terminated strings.
+pub fn list(target: Target<'_>) -> io::Result<Vec<u8>> {
+    let target = RawTarget::new(target)?;
+    read_sized(|buf, size| unsafe {
+        let buf = buf as *mut c_char;
+        match target {
+            RawTarget::Path(ref path) => libc::listxattr(path.as_ptr(), buf, size),
+            RawTarget::Link(ref path) => libc::llistxattr(path.as_ptr(), buf, size),
+            RawTarget::Fd(fd) => libc::flistxattr(fd, buf, size),
+        }
+    })
+}
+
+pub fn remove(target: Target<'_>, name: &OsStr) -> io::Result<()> {
+    let target = RawTarget::new(target)?;
+    let name = name_cstr(name)?;
+    let result = unsafe {
+        match target {
+            RawTarget::Path(ref path) => libc::removexattr(path.as_ptr(), name.as_ptr()),
+            RawTarget::Link(ref path) => libc::lremovexattr(path.as_ptr(), name.as_ptr()),
+            RawTarget::Fd(fd) => libc::fremovexattr(fd, name.as_ptr()),
+        }
+    };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use crate::os::fd::RawFd;
+use libc::{c_uint, off64_t};
+use std::io;
+use std::ptr;
+
+fn offset_ptr(offset: Option<&mut u64>) -> *mut off64_t {
+    offset.map_or(ptr::null_mut(), |off| off as *mut u64 as *mut off64_t)
+}
+
+pub fn copy_file_range(
+    fd_in: RawFd,
+    off_in: Option<&mut u64>,
+    fd_out: RawFd,
+    off_out: Option<&mut u64>,
+    len: usize,
+) -> io::Result<usize> {
+    let num_copied = unsafe {
+        libc::copy_file_range(
+            fd_in,
+            offset_ptr(off_in),
+            fd_out,
+            offset_ptr(off_out),
+            len,
+            0,
+        )
+    };
+    if num_copied == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(num_copied as usize)
+    }
+}
+
+pub fn sendfile(
+    out_fd: RawFd,
+    in_fd: RawFd,
+    offset: Option<&mut u64>,
+    count: usize,
+) -> io::Result<usize> {
+    let num_sent = unsafe { libc::sendfile64(out_fd, in_fd, offset_ptr(offset), count) };
+    if num_sent == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(num_sent as usize)
+    }
+}
+
+pub fn splice(
+    fd_in: RawFd,
+    off_in: Option<&mut u64>,
+    fd_out: RawFd,
+    off_out: Option<&mut u64>,
+    len: usize,
+    flags: c_uint,
+) -> io::Result<usize> {
+    let num_spliced = unsafe {
+        libc::splice(
+            fd_in,
+            offset_ptr(off_in),
+            fd_out,
+            offset_ptr(off_out),
+            len,
+            flags,
+        )
+    };
+    if num_spliced == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(num_spliced as usize)
+    }
+}
+
+pub fn tee(fd_in: RawFd, fd_out: RawFd, len: usize, flags: c_uint) -> io::Result<usize> {
+    let num_duplicated = unsafe { libc::tee(fd_in, fd_out, len, flags) };
+    if num_duplicated == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(num_duplicated as usize)
+    }
+}
@@ -0,0 +1,373 @@
+use crate::os::fd::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use crate::sys::fd::FileDesc;
+use libc::{c_int, c_void, sockaddr, sockaddr_storage, socklen_t};
+use std::cmp;
+use std::io;
+use std::mem;
+use std::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+const READ_LIMIT: usize = libc::ssize_t::MAX as usize;
+const SEND_LIMIT: usize = libc::ssize_t::MAX as usize;
+
+pub struct Socket(FileDesc);
+
+impl Socket {
+    pub fn new(addr: &SocketAddr, ty: c_int) -> io::Result<Socket> {
+        let family = match *addr {
+            SocketAddr::V4(..) => libc::AF_INET,
+            SocketAddr::V6(..) => libc::AF_INET6,
+        };
+        Socket::new_raw(family, ty)
+    }
+
+    pub fn new_raw(family: c_int, ty: c_int) -> io::Result<Socket> {
+        let fd = unsafe { libc::socket(family, ty | libc::SOCK_CLOEXEC, 0) };
+        if fd == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(Socket(unsafe { FileDesc::from_raw_fd(fd) }))
+        }
+    }
+
+    pub fn bind(&self, addr: &SocketAddr) -> io::Result<()> {
+        let (addr, len) = socket_addr_to_raw(addr);
+        let result = unsafe {
+            libc::bind(
+                self.as_raw_fd(),
+                &addr as *const sockaddr_storage as *const sockaddr,
+                len,
+            )
+        };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn listen(&self, backlog: c_int) -> io::Result<()> {
+        let result = unsafe { libc::listen(self.as_raw_fd(), backlog) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn connect(&self, addr: &SocketAddr) -> io::Result<()> {
+        let (addr, len) = socket_addr_to_raw(addr);
+        let result = unsafe {
+            libc::connect(
+                self.as_raw_fd(),
+                &addr as *const sockaddr_storage as *const sockaddr,
+                len,
+            )
+        };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn accept(&self) -> io::Result<(Socket, SocketAddr)> {
+        let mut storage: sockaddr_storage = unsafe { mem::zeroed() };
+        let mut len = mem::size_of_val(&storage) as socklen_t;
+        let fd = unsafe {
+            libc::accept4(
+                self.as_raw_fd(),
+                &mut storage as *mut sockaddr_storage as *mut sockaddr,
+                &mut len,
+                libc::SOCK_CLOEXEC,
+            )
+        };
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let sock = Socket(unsafe { FileDesc::from_raw_fd(fd) });
+        let addr = sockaddr_to_addr(&storage, len as usize)?;
+        Ok((sock, addr))
+    }
+
+    pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+
+    pub fn recv_with_flags(&self, buf: &mut [u8], flags: c_int) -> io::Result<usize> {
+        let num_read = unsafe {
+            libc::recv(
+                self.as_raw_fd(),
+                buf.as_mut_ptr() as *mut c_void,
+                cmp::min(buf.len(), READ_LIMIT),
+                flags,
+            )
+        };
+        if num_read == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(num_read as usize)
+        }
+    }
+
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.recv_with_flags(buf, libc::MSG_PEEK)
+    }
+
+    pub fn recv_from_with_flags(
+        &self,
+        buf: &mut [u8],
+        flags: c_int,
+    ) -> io::Result<(usize, SocketAddr)> {
+        let mut storage: sockaddr_storage = unsafe { mem::zeroed() };
+        let mut len = mem::size_of_val(&storage) as socklen_t;
+        let num_read = unsafe {
+            libc::recvfrom(
+                self.as_raw_fd(),
+                buf.as_mut_ptr() as *mut c_void,
+                cmp::min(buf.len(), READ_LIMIT),
+                flags,
+                &mut storage as *mut sockaddr_storage as *mut sockaddr,
+                &mut len,
+            )
+        };
+        if num_read == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((num_read as usize, sockaddr_to_addr(&storage, len as usize)?))
+    }
+
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.recv_from_with_flags(buf, 0)
+    }
+
+    pub fn peek_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.recv_from_with_flags(buf, libc::MSG_PEEK)
+    }
+
+    pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
+        let num_written = unsafe {
+            libc::send(
+                self.as_raw_fd(),
+                buf.as_ptr() as *const c_void,
+                cmp::min(buf.len(), SEND_LIMIT),
+                libc::MSG_NOSIGNAL,
+            )
+        };
+        if num_written == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(num_written as usize)
+        }
+    }
+
+    pub fn send_to(&self, buf: &[u8], dst: &SocketAddr) -> io::Result<usize> {
+        let (dst, len) = socket_addr_to_raw(dst);
+        let num_written = unsafe {
+            libc::sendto(
+                self.as_raw_fd(),
+                buf.as_ptr() as *const c_void,
+                cmp::min(buf.len(), SEND_LIMIT),
+                libc::MSG_NOSIGNAL,
+                &dst as *const sockaddr_storage as *const sockaddr,
+                len,
+            )
+        };
+        if num_written == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(num_written as usize)
+        }
+    }
+
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        let how = match how {
+            Shutdown::Read => libc::SHUT_RD,
+            Shutdown::Write => libc::SHUT_WR,
+            Shutdown::Both => libc::SHUT_RDWR,
+        };
+        let result = unsafe { libc::shutdown(self.as_raw_fd(), how) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn socket_addr(&self) -> io::Result<SocketAddr> {
+        let mut storage: sockaddr_storage = unsafe { mem::zeroed() };
+        let mut len = mem::size_of_val(&storage) as socklen_t;
+        let result = unsafe {
+            libc::getsockname(
+                self.as_raw_fd(),
+                &mut storage as *mut sockaddr_storage as *mut sockaddr,
+                &mut len,
+            )
+        };
+        if result == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        sockaddr_to_addr(&storage, len as usize)
+    }
+
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        let mut storage: sockaddr_storage = unsafe { mem::zeroed() };
+        let mut len = mem::size_of_val(&storage) as socklen_t;
+        let result = unsafe {
+            libc::getpeername(
+                self.as_raw_fd(),
+                &mut storage as *mut sockaddr_storage as *mut sockaddr,
+                &mut len,
+            )
+        };
+        if result == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        sockaddr_to_addr(&storage, len as usize)
+    }
+
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        let raw: c_int = getsockopt(self, libc::SOL_SOCKET, libc::SO_ERROR)?;
+        if raw == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(io::Error::from_raw_os_error(raw)))
+        }
+    }
+}
+
+impl AsRawFd for Socket {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl FromRawFd for Socket {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self(FromRawFd::from_raw_fd(fd))
+    }
+}
+
+impl IntoRawFd for Socket {
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
+pub fn setsockopt<T>(sock: &Socket, level: c_int, name: c_int, payload: T) -> io::Result<()> {
+    let result = unsafe {
+        libc::setsockopt(
+            sock.as_raw_fd(),
+            level,
+            name,
+            &payload as *const T as *const c_void,
+            mem::size_of::<T>() as socklen_t,
+        )
+    };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Convert a `SO_RCVBUF` or `SO_SNDBUF` size to the `int` the kernel
+/// takes, rather than silently truncating it.
+pub fn buffer_size(size: usize) -> io::Result<c_int> {
+    c_int::try_from(size)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "socket buffer size too large"))
+}
+
+pub fn getsockopt<T: Copy>(sock: &Socket, level: c_int, name: c_int) -> io::Result<T> {
+    let mut payload: T = unsafe { mem::zeroed() };
+    let mut len = mem::size_of::<T>() as socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            sock.as_raw_fd(),
+            level,
+            name,
+            &mut payload as *mut T as *mut c_void,
+            &mut len,
+        )
+    };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    if len as usize != mem::size_of::<T>() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unexpected socket option length",
+        ));
+    }
+    Ok(payload)
+}
+
+pub fn socket_addr_to_raw(addr: &SocketAddr) -> (sockaddr_storage, socklen_t) {
+    let mut storage: sockaddr_storage = unsafe { mem::zeroed() };
+    let len = match *addr {
+        SocketAddr::V4(ref a) => {
+            let sin = libc::sockaddr_in {
+                sin_family: libc::AF_INET as libc::sa_family_t,
+                sin_port: a.port().to_be(),
+                sin_addr: libc::in_addr {
+                    s_addr: u32::from_ne_bytes(a.ip().octets()),
+                },
+                sin_zero: [0; 8],
+            };
+            unsafe {
+                *(&mut storage as *mut sockaddr_storage as *mut libc::sockaddr_in) = sin;
+            }
+            mem::size_of::<libc::sockaddr_in>()
+        }
+        SocketAddr::V6(ref a) => {
+            let sin6 = libc::sockaddr_in6 {
+                sin6_family: libc::AF_INET6 as libc::sa_family_t,
+                sin6_port: a.port().to_be(),
+                sin6_flowinfo: a.flowinfo(),
+                sin6_addr: libc::in6_addr {
+                    s6_addr: a.ip().octets(),
+                },
+                sin6_scope_id: a.scope_id(),
+            };
+            unsafe {
+                *(&mut storage as *mut sockaddr_storage as *mut libc::sockaddr_in6) = sin6;
+            }
+            mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+    (storage, len as socklen_t)
+}
+
+fn invalid_addr_len() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "invalid socket address length")
+}
+
+pub fn sockaddr_to_addr(storage: &sockaddr_storage, len: usize) -> io::Result<SocketAddr> {
+    match storage.ss_family as c_int {
+        libc::AF_INET => {
+            if len < mem::size_of::<libc::sockaddr_in>() {
+                return Err(invalid_addr_len());
+            }
+            let sin = unsafe { *(storage as *const sockaddr_storage as *const libc::sockaddr_in) };
+            let ip = Ipv4Addr::from(sin.sin_addr.s_addr.to_ne_bytes());
+            let port = u16::from_be(sin.sin_port);
+            Ok(SocketAddr::V4(SocketAddrV4::new(ip, port)))
+        }
+        libc::AF_INET6 => {
+            if len < mem::size_of::<libc::sockaddr_in6>() {
+                return Err(invalid_addr_len());
+            }
+            let sin6 =
+                unsafe { *(storage as *const sockaddr_storage as *const libc::sockaddr_in6) };
+            let ip = Ipv6Addr::from(sin6.sin6_addr.s6_addr);
+            let port = u16::from_be(sin6.sin6_port);
+            Ok(SocketAddr::V6(SocketAddrV6::new(
+                ip,
+                port,
+                sin6.sin6_flowinfo,
+                sin6.sin6_scope_id,
+            )))
+        }
+        _ => Err(io::Error::from_raw_os_error(libc::EINVAL)),
+    }
+}
@@ -0,0 +1,11 @@
+use libc::pid_t;
+use std::io;
+
+pub fn fork() -> io::Result<pid_t> {
+    let pid = unsafe { libc::fork() };
+    if pid == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(pid)
+    }
+}
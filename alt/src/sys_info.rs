@@ -0,0 +1,139 @@
+mod procfs;
+
+use libc::c_char;
+use std::ffi::CStr;
+use std::io;
+use std::mem;
+use std::time::Duration;
+
+pub use procfs::*;
+
+/// The identity of the running kernel and machine, from `uname()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Uname {
+    /// The kernel name, "Linux".
+    pub sysname: String,
+    /// The host name, as also returned by `gethostname()`.
+    pub nodename: String,
+    pub release: String,
+    pub version: String,
+    /// The hardware architecture, e.g. "x86_64".
+    pub machine: String,
+    /// The NIS domain name, unrelated to DNS.
+    pub domainname: String,
+}
+
+fn field(bytes: &[c_char]) -> String {
+    let bytes: Vec<u8> = bytes
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+pub fn uname() -> io::Result<Uname> {
+    let mut buf: libc::utsname = unsafe { mem::zeroed() };
+    let result = unsafe { libc::uname(&mut buf) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(Uname {
+            sysname: field(&buf.sysname),
+            nodename: field(&buf.nodename),
+            release: field(&buf.release),
+            version: field(&buf.version),
+            machine: field(&buf.machine),
+            domainname: field(&buf.domainname),
+        })
+    }
+}
+
+/// Overall system statistics, from `sysinfo()`. Memory sizes are in
+/// bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SysInfo {
+    pub uptime: Duration,
+    /// The 1, 5 and 15 minute load averages.
+    pub loads: [f64; 3],
+    pub total_ram: u64,
+    pub free_ram: u64,
+    pub shared_ram: u64,
+    pub buffer_ram: u64,
+    pub total_swap: u64,
+    pub free_swap: u64,
+    /// The number of threads on the system.
+    pub procs: u16,
+    pub total_high: u64,
+    pub free_high: u64,
+}
+
+pub fn sysinfo() -> io::Result<SysInfo> {
+    let mut info: libc::sysinfo = unsafe { mem::zeroed() };
+    let result = unsafe { libc::sysinfo(&mut info) };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let bytes = |n: libc::c_ulong| n * info.mem_unit as u64;
+    let load = |n: libc::c_ulong| n as f64 / (1u64 << libc::SI_LOAD_SHIFT) as f64;
+    Ok(SysInfo {
+        uptime: Duration::from_secs(info.uptime as u64),
+        loads: info.loads.map(load),
+        total_ram: bytes(info.totalram),
+        free_ram: bytes(info.freeram),
+        shared_ram: bytes(info.sharedram),
+        buffer_ram: bytes(info.bufferram),
+        total_swap: bytes(info.totalswap),
+        free_swap: bytes(info.freeswap),
+        procs: info.procs,
+        total_high: bytes(info.totalhigh),
+        free_high: bytes(info.freehigh),
+    })
+}
+
+/// Return the host name of the UTS namespace.
+pub fn gethostname() -> io::Result<String> {
+    let mut buf = [0 as c_char; libc::HOST_NAME_MAX as usize + 1];
+    let result = unsafe { libc::gethostname(buf.as_mut_ptr(), buf.len()) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        Ok(name.to_string_lossy().into_owned())
+    }
+}
+
+/// Set the host name of the UTS namespace, which requires
+/// `CAP_SYS_ADMIN` in the namespace.
+pub fn sethostname(name: &str) -> io::Result<()> {
+    let result = unsafe { libc::sethostname(name.as_ptr() as *const c_char, name.len()) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uname_and_hostname() {
+        let uts = uname().unwrap();
+        assert_eq!(uts.sysname, "Linux");
+        assert!(!uts.release.is_empty() && !uts.machine.is_empty());
+        assert_eq!(gethostname().unwrap(), uts.nodename);
+    }
+
+    #[test]
+    fn sysinfo_totals() {
+        let info = sysinfo().unwrap();
+        assert!(info.uptime > Duration::ZERO);
+        assert!(info.total_ram > 0 && info.free_ram <= info.total_ram);
+        assert!(info.free_swap <= info.total_swap);
+        assert!(info.procs > 0);
+        assert!(info.loads.iter().all(|&load| load >= 0.0));
+    }
+}
@@ -0,0 +1,586 @@
+use crate::fs::{self, OpenOptions};
+use crate::os::fd::RawFd;
+use crate::process::credentials::ResIds;
+use crate::process::ticks_to_duration;
+use std::ffi::OsString;
+use std::io::{self, Read};
+use std::os::unix::ffi::OsStringExt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+fn read_file<P: AsRef<Path>>(path: P) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    OpenOptions::new()
+        .read(true)
+        .open(path)?
+        .read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+fn invalid(what: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("malformed {}", what))
+}
+
+/// The parts of `/proc/PID/status` most often wanted, with the other
+/// fields available by name through `get()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProcStatus {
+    /// The command name, truncated to 15 bytes, with "\n" and "\\"
+    /// escaped.
+    pub name: String,
+    /// The state letter, e.g. 'R' (running) or 'S' (sleeping).
+    pub state: char,
+    /// The thread group ID, which is the PID seen by user space.
+    pub tgid: u32,
+    /// The thread ID.
+    pub pid: u32,
+    pub ppid: u32,
+    pub uid: ResIds,
+    pub fsuid: u32,
+    pub gid: ResIds,
+    pub fsgid: u32,
+    /// The supplementary group IDs.
+    pub groups: Vec<u32>,
+    pub threads: u32,
+    fields: Vec<(String, String)>,
+}
+
+impl ProcStatus {
+    /// Read `/proc/PID/status`.
+    pub fn of(pid: u32) -> io::Result<ProcStatus> {
+        ProcStatus::parse(&read_file(format!("/proc/{}/status", pid))?)
+    }
+
+    pub fn parse(text: &[u8]) -> io::Result<ProcStatus> {
+        let invalid = || invalid("/proc/PID/status");
+        let fields: Vec<(String, String)> = String::from_utf8_lossy(text)
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+            .collect();
+        let get = |key| {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+                .ok_or_else(invalid)
+        };
+        let numbers = |key| -> io::Result<Vec<u32>> {
+            get(key)?
+                .split_whitespace()
+                .map(|n| n.parse().map_err(|_| invalid()))
+                .collect()
+        };
+        let number = |key| -> io::Result<u32> { get(key)?.parse().map_err(|_| invalid()) };
+        let ids = |key| -> io::Result<(ResIds, u32)> {
+            match numbers(key)?[..] {
+                [real, effective, saved, fs] => Ok((
+                    ResIds {
+                        real,
+                        effective,
+                        saved,
+                    },
+                    fs,
+                )),
+                _ => Err(invalid()),
+            }
+        };
+
+        let (uid, fsuid) = ids("Uid")?;
+        let (gid, fsgid) = ids("Gid")?;
+        Ok(ProcStatus {
+            name: get("Name")?.to_string(),
+            state: get("State")?.chars().next().ok_or_else(invalid)?,
+            tgid: number("Tgid")?,
+            pid: number("Pid")?,
+            ppid: number("PPid")?,
+            uid,
+            fsuid,
+            gid,
+            fsgid,
+            groups: numbers("Groups")?,
+            threads: number("Threads")?,
+            fields,
+        })
+    }
+
+    /// The value of any field, e.g. `get("VmRSS")` gives "1234 kB".
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The value of a memory field such as "VmRSS" or "VmPeak" in bytes.
+    /// These are absent for kernel threads.
+    pub fn memory(&self, key: &str) -> Option<u64> {
+        let kb = self.get(key)?.strip_suffix(" kB")?;
+        Some(kb.trim().parse::<u64>().ok()? * 1024)
+    }
+}
+
+/// The fields of `/proc/PID/stat`, as used by ps(1), up to the CPU the
+/// process last ran on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProcStat {
+    pub pid: u32,
+    /// The command name, which unlike in `ProcStatus` is not escaped, and
+    /// may contain spaces and parentheses.
+    pub comm: String,
+    pub state: char,
+    pub ppid: u32,
+    pub pgrp: u32,
+    pub session: u32,
+    /// The device number of the controlling terminal, or 0.
+    pub tty_nr: u32,
+    /// The foreground process group of the controlling terminal, or -1.
+    pub tpgid: i32,
+    pub flags: u32,
+    pub minor_faults: u64,
+    pub children_minor_faults: u64,
+    pub major_faults: u64,
+    pub children_major_faults: u64,
+    pub user_time: Duration,
+    pub system_time: Duration,
+    pub children_user_time: Duration,
+    pub children_system_time: Duration,
+    pub priority: i64,
+    pub nice: i64,
+    pub num_threads: u32,
+    /// The time the process started, after system boot.
+    pub start_time: Duration,
+    /// The virtual memory size in bytes.
+    pub vsize: u64,
+    /// The resident set size in pages.
+    pub rss: u64,
+    pub processor: u32,
+}
+
+impl ProcStat {
+    /// Read `/proc/PID/stat`.
+    pub fn of(pid: u32) -> io::Result<ProcStat> {
+        ProcStat::parse(&read_file(format!("/proc/{}/stat", pid))?)
+    }
+
+    pub fn parse(text: &[u8]) -> io::Result<ProcStat> {
+        let invalid = || invalid("/proc/PID/stat");
+
+        // The command is in parentheses, and may itself contain ") ", so
+        // the fields resume after the last ')'
+        let open = text.iter().position(|&b| b == b'(').ok_or_else(invalid)?;
+        let close = text.iter().rposition(|&b| b == b')').ok_or_else(invalid)?;
+        if close < open {
+            return Err(invalid());
+        }
+        let pid = String::from_utf8_lossy(&text[..open]);
+        let comm = String::from_utf8_lossy(&text[open + 1..close]).into_owned();
+        let rest = String::from_utf8_lossy(&text[close + 1..]);
+        let fields: Vec<&str> = rest.split_whitespace().collect();
+
+        let ticks = |n| -> io::Result<Duration> {
+            let ticks: i64 = stat_field(&fields, n)?;
+            Ok(ticks_to_duration(ticks.max(0) as u64))
+        };
+
+        Ok(ProcStat {
+            pid: pid.trim().parse().map_err(|_| invalid())?,
+            comm,
+            state: stat_field(&fields, 3)?,
+            ppid: stat_field(&fields, 4)?,
+            pgrp: stat_field(&fields, 5)?,
+            session: stat_field(&fields, 6)?,
+            tty_nr: stat_field(&fields, 7)?,
+            tpgid: stat_field(&fields, 8)?,
+            flags: stat_field(&fields, 9)?,
+            minor_faults: stat_field(&fields, 10)?,
+            children_minor_faults: stat_field(&fields, 11)?,
+            major_faults: stat_field(&fields, 12)?,
+            children_major_faults: stat_field(&fields, 13)?,
+            user_time: ticks(14)?,
+            system_time: ticks(15)?,
+            children_user_time: ticks(16)?,
+            children_system_time: ticks(17)?,
+            priority: stat_field(&fields, 18)?,
+            nice: stat_field(&fields, 19)?,
+            num_threads: stat_field(&fields, 20)?,
+            start_time: ticks(22)?,
+            vsize: stat_field(&fields, 23)?,
+            rss: stat_field(&fields, 24)?,
+            processor: stat_field(&fields, 39)?,
+        })
+    }
+}
+
+/// Parse field `n` of `/proc/PID/stat`, numbered as in proc(5), from the
+/// fields following the command, which is field 2.
+fn stat_field<T: FromStr>(fields: &[&str], n: usize) -> io::Result<T> {
+    fields
+        .get(n - 3)
+        .and_then(|field| field.parse().ok())
+        .ok_or_else(|| invalid("/proc/PID/stat"))
+}
+
+/// An open file descriptor of a process, from `/proc/PID/fd`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FdEntry {
+    pub fd: RawFd,
+    /// What the descriptor refers to: a path, or for files without one a
+    /// description such as "pipe:[1234]" or "socket:[5678]".
+    pub target: PathBuf,
+}
+
+/// List the open file descriptors of process `pid`, in numerical order.
+/// Reading another user's descriptors requires `CAP_SYS_PTRACE`.
+pub fn fds(pid: u32) -> io::Result<Vec<FdEntry>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(format!("/proc/{}/fd", pid))? {
+        let entry = entry?;
+        let Some(fd) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse().ok())
+        else {
+            continue;
+        };
+        // The descriptor may be closed between listing and reading it
+        match fs::read_link(entry.path()) {
+            Ok(target) => entries.push(FdEntry { fd, target }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    entries.sort_by_key(|entry| entry.fd);
+    Ok(entries)
+}
+
+/// A line of `/proc/PID/maps`: one mapping in the virtual address space.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapEntry {
+    pub start: u64,
+    pub end: u64,
+    pub readable: bool,
+    pub writable: bool,
+    pub executable: bool,
+    /// True for a `MAP_SHARED` mapping, false for `MAP_PRIVATE`.
+    pub shared: bool,
+    /// The offset of the mapping in the file.
+    pub offset: u64,
+    pub major: u32,
+    pub minor: u32,
+    pub inode: u64,
+    /// The mapped file, a pseudo-path such as "[heap]" or "[stack]", or
+    /// `None` for an anonymous mapping. A file that has been removed has
+    /// " (deleted)" appended.
+    pub pathname: Option<OsString>,
+}
+
+impl MapEntry {
+    pub fn parse(line: &[u8]) -> io::Result<MapEntry> {
+        let invalid = || invalid("/proc/PID/maps line");
+        let mut rest = line;
+        let mut next = || -> io::Result<&str> {
+            let start = rest.iter().position(|&b| b != b' ').unwrap_or(rest.len());
+            let end = rest[start..]
+                .iter()
+                .position(|&b| b == b' ')
+                .map_or(rest.len(), |n| start + n);
+            let field = std::str::from_utf8(&rest[start..end]).map_err(|_| invalid())?;
+            rest = &rest[end..];
+            if field.is_empty() {
+                Err(invalid())
+            } else {
+                Ok(field)
+            }
+        };
+        let hex = |s: &str| u64::from_str_radix(s, 16).map_err(|_| invalid());
+
+        let (start, end) = next()?.split_once('-').ok_or_else(invalid)?;
+        let (start, end) = (hex(start)?, hex(end)?);
+        if end < start {
+            return Err(invalid());
+        }
+        let perms = next()?.as_bytes();
+        if perms.len() != 4 {
+            return Err(invalid());
+        }
+        let offset = hex(next()?)?;
+        let (major, minor) = next()?.split_once(':').ok_or_else(invalid)?;
+        let hex32 = |s: &str| u32::from_str_radix(s, 16).map_err(|_| invalid());
+        let (major, minor) = (hex32(major)?, hex32(minor)?);
+        let inode = next()?.parse().map_err(|_| invalid())?;
+
+        let pathname = rest
+            .iter()
+            .position(|&b| b != b' ')
+            .map(|n| OsString::from_vec(rest[n..].to_vec()));
+        Ok(MapEntry {
+            start,
+            end,
+            readable: perms[0] == b'r',
+            writable: perms[1] == b'w',
+            executable: perms[2] == b'x',
+            shared: perms[3] == b's',
+            offset,
+            major,
+            minor,
+            inode,
+            pathname,
+        })
+    }
+
+    pub fn len(&self) -> u64 {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Return the memory mappings of process `pid`, in address order.
+pub fn maps(pid: u32) -> io::Result<Vec<MapEntry>> {
+    read_file(format!("/proc/{}/maps", pid))?
+        .split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(MapEntry::parse)
+        .collect()
+}
+
+/// System memory usage from `/proc/meminfo`, in bytes, with the other
+/// fields available by name through `get()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemInfo {
+    pub total: u64,
+    pub free: u64,
+    /// An estimate of the memory available for new processes without
+    /// swapping, counting reclaimable caches.
+    pub available: u64,
+    pub buffers: u64,
+    pub cached: u64,
+    pub swap_total: u64,
+    pub swap_free: u64,
+    fields: Vec<(String, u64)>,
+}
+
+impl MemInfo {
+    pub fn current() -> io::Result<MemInfo> {
+        MemInfo::parse(&read_file("/proc/meminfo")?)
+    }
+
+    pub fn parse(text: &[u8]) -> io::Result<MemInfo> {
+        let invalid = || invalid("/proc/meminfo");
+        let mut fields = Vec::new();
+        for line in String::from_utf8_lossy(text).lines() {
+            let (key, value) = line.split_once(':').ok_or_else(invalid)?;
+            let value = value.trim();
+            let value = match value.strip_suffix(" kB") {
+                Some(kb) => kb.trim().parse::<u64>().map_err(|_| invalid())? * 1024,
+                None => value.parse().map_err(|_| invalid())?,
+            };
+            fields.push((key.to_string(), value));
+        }
+        let get = |key| {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|&(_, v)| v)
+                .ok_or_else(invalid)
+        };
+
+        Ok(MemInfo {
+            total: get("MemTotal")?,
+            free: get("MemFree")?,
+            available: get("MemAvailable")?,
+            buffers: get("Buffers")?,
+            cached: get("Cached")?,
+            swap_total: get("SwapTotal")?,
+            swap_free: get("SwapFree")?,
+            fields,
+        })
+    }
+
+    /// The value of any field, such as "Dirty" or "HugePages_Total". Sizes
+    /// are in bytes and counts as given.
+    pub fn get(&self, key: &str) -> Option<u64> {
+        self.fields.iter().find(|(k, _)| k == key).map(|&(_, v)| v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fields 3 to 52 of a `/proc/PID/stat` line, with processor 3.
+    const STAT_FIELDS: &str = "S 1 4321 4321 34816 4321 4194560 1200 3400 5 6 \
+        250 75 10 20 20 -5 3 0 123456 10485760 300 18446744073709551615 \
+        94000000000000 94000000100000 140720000000000 0 0 0 0 0 0 0 0 0 17 3 0 0 \
+        0 0 0 94000000200000 94000000300000 94000000400000 140720000001000 \
+        140720000002000 140720000002000 140720000003000 0\n";
+
+    fn stat(comm: &str) -> Vec<u8> {
+        format!("4321 ({}) {}", comm, STAT_FIELDS).into_bytes()
+    }
+
+    #[test]
+    fn stat_line() {
+        let st = ProcStat::parse(&stat("bash")).unwrap();
+        assert_eq!((st.pid, st.comm.as_str(), st.state), (4321, "bash", 'S'));
+        assert_eq!(
+            (st.ppid, st.pgrp, st.session, st.tty_nr, st.tpgid),
+            (1, 4321, 4321, 34816, 4321)
+        );
+        assert_eq!(st.flags, 4194560);
+        assert_eq!((st.minor_faults, st.children_minor_faults), (1200, 3400));
+        assert_eq!((st.major_faults, st.children_major_faults), (5, 6));
+        assert_eq!(st.user_time, ticks_to_duration(250));
+        assert_eq!(st.system_time, ticks_to_duration(75));
+        assert_eq!(st.children_user_time, ticks_to_duration(10));
+        assert_eq!(st.children_system_time, ticks_to_duration(20));
+        assert_eq!((st.priority, st.nice, st.num_threads), (20, -5, 3));
+        assert_eq!(st.start_time, ticks_to_duration(123456));
+        assert_eq!((st.vsize, st.rss, st.processor), (10485760, 300, 3));
+    }
+
+    #[test]
+    fn stat_comm_with_parentheses() {
+        for comm in ["a) (b", "x) S 1 2 (", "two words", ") )", ""] {
+            let st = ProcStat::parse(&stat(comm)).unwrap();
+            assert_eq!(st.comm, comm);
+            assert_eq!((st.pid, st.state, st.ppid, st.processor), (4321, 'S', 1, 3));
+        }
+    }
+
+    #[test]
+    fn stat_malformed() {
+        assert!(ProcStat::parse(b"4321 bash S 1").is_err());
+        assert!(ProcStat::parse(b"4321 )bash( S 1").is_err());
+        assert!(ProcStat::parse(b"4321 (bash) S 1 4321 4321 34816").is_err());
+        assert!(ProcStat::parse(&format!("x ({}) {}", "bash", STAT_FIELDS).into_bytes()).is_err());
+    }
+
+    #[test]
+    fn maps_lines() {
+        let map = MapEntry::parse(
+            b"55d0c8a00000-55d0c8a28000 r-xp 00002000 fd:01 1835023                    /usr/bin/bash",
+        )
+        .unwrap();
+        assert_eq!(
+            (map.start, map.end, map.len()),
+            (0x55d0c8a00000, 0x55d0c8a28000, 0x28000)
+        );
+        assert!(map.readable && !map.writable && map.executable && !map.shared);
+        assert_eq!(
+            (map.offset, map.major, map.minor, map.inode),
+            (0x2000, 0xfd, 1, 1835023)
+        );
+        assert_eq!(map.pathname, Some(OsString::from("/usr/bin/bash")));
+
+        let anon = MapEntry::parse(b"7f1c2a000000-7f1c2a021000 rw-s 00000000 00:00 0 ").unwrap();
+        assert!(anon.writable && anon.shared);
+        assert_eq!((anon.inode, anon.pathname), (0, None));
+        let anon = MapEntry::parse(b"7f1c2a000000-7f1c2a021000 ---p 00000000 00:00 0").unwrap();
+        assert!(!anon.readable && anon.pathname.is_none());
+
+        let deleted = MapEntry::parse(
+            b"7f1c2b000000-7f1c2b001000 rw-s 00000000 00:05 4242                       /dev/shm/my file (deleted)",
+        )
+        .unwrap();
+        assert_eq!(
+            deleted.pathname,
+            Some(OsString::from("/dev/shm/my file (deleted)"))
+        );
+
+        let stack = MapEntry::parse(b"7ffd1000-7ffd2000 rw-p 00000000 00:00 0  [stack]").unwrap();
+        assert_eq!(stack.pathname, Some(OsString::from("[stack]")));
+        let raw = MapEntry::parse(b"1000-2000 r--p 00000000 08:01 7 /tmp/\xff").unwrap();
+        assert_eq!(
+            raw.pathname,
+            Some(OsString::from_vec(b"/tmp/\xff".to_vec()))
+        );
+    }
+
+    #[test]
+    fn maps_malformed() {
+        assert!(MapEntry::parse(b"").is_err());
+        assert!(MapEntry::parse(b"1000 r--p 00000000 08:01 7").is_err());
+        assert!(MapEntry::parse(b"1000-2000 r--p 00000000 08:01").is_err());
+        assert!(MapEntry::parse(b"1000-2000 r-p 00000000 08:01 7").is_err());
+        assert!(MapEntry::parse(b"1000-zz r--p 00000000 08:01 7").is_err());
+        let err = MapEntry::parse(b"2000-1000 r--p 00000000 08:01 7").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(MapEntry::parse(b"1000-2000 r--p 00000000 100000000:01 7").is_err());
+    }
+
+    #[test]
+    fn status() {
+        let text = b"Name:\tmy\\nproc\nUmask:\t0022\nState:\tS (sleeping)\nTgid:\t4321\n\
+            Ngid:\t0\nPid:\t4322\nPPid:\t1\nTracerPid:\t0\n\
+            Uid:\t1000\t1000\t0\t1000\nGid:\t100\t100\t100\t50\nFDSize:\t64\n\
+            Groups:\t10 100 \nVmPeak:\t   10240 kB\nVmRSS:\t    1200 kB\nThreads:\t2\n";
+        let st = ProcStatus::parse(text).unwrap();
+        assert_eq!((st.name.as_str(), st.state), ("my\\nproc", 'S'));
+        assert_eq!((st.tgid, st.pid, st.ppid, st.threads), (4321, 4322, 1, 2));
+        assert_eq!(
+            st.uid,
+            ResIds {
+                real: 1000,
+                effective: 1000,
+                saved: 0
+            }
+        );
+        assert_eq!(
+            st.gid,
+            ResIds {
+                real: 100,
+                effective: 100,
+                saved: 100
+            }
+        );
+        assert_eq!((st.fsuid, st.fsgid), (1000, 50));
+        assert_eq!(st.groups, [10, 100]);
+        assert_eq!(st.get("Umask"), Some("0022"));
+        assert_eq!(st.memory("VmRSS"), Some(1200 * 1024));
+        assert_eq!(st.memory("VmSwap"), None);
+        assert_eq!(st.memory("Umask"), None);
+    }
+
+    #[test]
+    fn status_kernel_thread() {
+        // Kernel threads have no Vm* fields and an empty group list
+        let text = b"Name:\tkthreadd\nState:\tS (sleeping)\nTgid:\t2\nPid:\t2\nPPid:\t0\n\
+            Uid:\t0\t0\t0\t0\nGid:\t0\t0\t0\t0\nGroups:\t\nThreads:\t1\n";
+        let st = ProcStatus::parse(text).unwrap();
+        assert!(st.groups.is_empty());
+        assert_eq!(st.memory("VmRSS"), None);
+        assert!(ProcStatus::parse(b"Name:\tx\nState:\tR\n").is_err());
+        let bad_uid = b"Name:\tx\nState:\tR\nTgid:\t2\nPid:\t2\nPPid:\t0\n\
+            Uid:\t0\t0\t0\nGid:\t0\t0\t0\t0\nGroups:\t\nThreads:\t1\n";
+        assert!(ProcStatus::parse(bad_uid).is_err());
+    }
+
+    #[test]
+    fn meminfo() {
+        let text = b"MemTotal:       16303004 kB\nMemFree:         1234567 kB\n\
+            MemAvailable:    8000000 kB\nBuffers:          100000 kB\nCached:          5000000 kB\n\
+            SwapTotal:       2097148 kB\nSwapFree:        2097148 kB\nHugePages_Total:       4\n";
+        let info = MemInfo::parse(text).unwrap();
+        assert_eq!(info.total, 16303004 * 1024);
+        assert_eq!(
+            (info.free, info.available),
+            (1234567 * 1024, 8000000 * 1024)
+        );
+        assert_eq!((info.buffers, info.cached), (100000 * 1024, 5000000 * 1024));
+        assert_eq!(
+            (info.swap_total, info.swap_free),
+            (2097148 * 1024, 2097148 * 1024)
+        );
+        assert_eq!(info.get("HugePages_Total"), Some(4));
+        assert_eq!(info.get("Dirty"), None);
+
+        assert!(MemInfo::parse(b"MemTotal: 1 kB\n").is_err());
+        assert!(MemInfo::parse(b"MemTotal 1 kB\n").is_err());
+        assert!(MemInfo::parse(b"MemTotal: lots\n").is_err());
+    }
+}
@@ -0,0 +1,372 @@
+pub mod calendar;
+mod timer;
+mod timerfd;
+
+use libc::clockid_t;
+use std::fmt;
+use std::io;
+use std::ops::{Add, Sub};
+use std::ptr;
+use std::time::Duration;
+
+pub use timer::*;
+pub use timerfd::*;
+
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// Seconds and nanoseconds, as in `struct timespec`: either a time on some
+/// clock or a signed interval.
+///
+/// The nanoseconds are always less than one second, so the derived
+/// ordering is chronological; a negative value such as -0.25s is stored as
+/// -1s + 750000000ns.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timespec {
+    sec: i64,
+    nsec: u32,
+}
+
+impl Timespec {
+    pub const ZERO: Timespec = Timespec { sec: 0, nsec: 0 };
+    pub const MAX: Timespec = Timespec {
+        sec: i64::MAX,
+        nsec: NANOS_PER_SEC - 1,
+    };
+
+    /// Create a `Timespec`, carrying whole seconds out of `nsec`.
+    ///
+    /// # Panics
+    ///
+    /// If the carry overflows the seconds.
+    pub const fn new(sec: i64, nsec: u32) -> Timespec {
+        match sec.checked_add((nsec / NANOS_PER_SEC) as i64) {
+            Some(sec) => Timespec {
+                sec,
+                nsec: nsec % NANOS_PER_SEC,
+            },
+            None => panic!("overflow in Timespec::new"),
+        }
+    }
+
+    pub const fn sec(&self) -> i64 {
+        self.sec
+    }
+
+    pub const fn subsec_nanos(&self) -> u32 {
+        self.nsec
+    }
+
+    pub const fn as_nanos(&self) -> i128 {
+        self.sec as i128 * NANOS_PER_SEC as i128 + self.nsec as i128
+    }
+
+    pub fn from_duration(duration: Duration) -> Option<Timespec> {
+        Some(Timespec {
+            sec: duration.as_secs().try_into().ok()?,
+            nsec: duration.subsec_nanos(),
+        })
+    }
+
+    /// Convert to a `Duration`, or `None` if negative.
+    pub fn to_duration(self) -> Option<Duration> {
+        Some(Duration::new(self.sec.try_into().ok()?, self.nsec))
+    }
+
+    pub fn checked_add(self, rhs: Timespec) -> Option<Timespec> {
+        let mut sec = self.sec.checked_add(rhs.sec)?;
+        let mut nsec = self.nsec + rhs.nsec;
+        if nsec >= NANOS_PER_SEC {
+            nsec -= NANOS_PER_SEC;
+            sec = sec.checked_add(1)?;
+        }
+        Some(Timespec { sec, nsec })
+    }
+
+    pub fn checked_sub(self, rhs: Timespec) -> Option<Timespec> {
+        let mut sec = self.sec.checked_sub(rhs.sec)?;
+        let nsec = if self.nsec >= rhs.nsec {
+            self.nsec - rhs.nsec
+        } else {
+            sec = sec.checked_sub(1)?;
+            self.nsec + NANOS_PER_SEC - rhs.nsec
+        };
+        Some(Timespec { sec, nsec })
+    }
+
+    pub fn checked_add_duration(self, duration: Duration) -> Option<Timespec> {
+        self.checked_add(Timespec::from_duration(duration)?)
+    }
+
+    pub fn checked_sub_duration(self, duration: Duration) -> Option<Timespec> {
+        self.checked_sub(Timespec::from_duration(duration)?)
+    }
+
+    /// The time elapsed from `earlier` to `self`, or `None` if `earlier`
+    /// is later.
+    pub fn duration_since(self, earlier: Timespec) -> Option<Duration> {
+        self.checked_sub(earlier)?.to_duration()
+    }
+
+    pub fn from_raw(ts: libc::timespec) -> Timespec {
+        Timespec::new(ts.tv_sec, ts.tv_nsec as u32)
+    }
+
+    pub fn as_raw(&self) -> libc::timespec {
+        libc::timespec {
+            tv_sec: self.sec,
+            tv_nsec: self.nsec as libc::c_long,
+        }
+    }
+}
+
+impl Add<Duration> for Timespec {
+    type Output = Timespec;
+
+    fn add(self, rhs: Duration) -> Timespec {
+        self.checked_add_duration(rhs)
+            .expect("overflow when adding duration to Timespec")
+    }
+}
+
+impl Sub<Duration> for Timespec {
+    type Output = Timespec;
+
+    fn sub(self, rhs: Duration) -> Timespec {
+        self.checked_sub_duration(rhs)
+            .expect("overflow when subtracting duration from Timespec")
+    }
+}
+
+impl Sub for Timespec {
+    type Output = Timespec;
+
+    fn sub(self, rhs: Timespec) -> Timespec {
+        self.checked_sub(rhs)
+            .expect("overflow when subtracting Timespecs")
+    }
+}
+
+/// Renders as decimal seconds with nanosecond precision, e.g.
+/// "-0.250000000".
+impl fmt::Display for Timespec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = if self.sec < 0 && self.nsec != 0 {
+            format!("-{}.{:09}", -(self.sec + 1), NANOS_PER_SEC - self.nsec)
+        } else {
+            format!("{}.{:09}", self.sec, self.nsec)
+        };
+        f.pad(&s)
+    }
+}
+
+/// A clock, as identified to `clock_gettime()` and friends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Clock(clockid_t);
+
+impl Clock {
+    /// Wall-clock time since the Epoch; jumps when the system time is set.
+    pub const REALTIME: Clock = Clock(libc::CLOCK_REALTIME);
+    /// Time since some unspecified point, never set backwards, but not
+    /// advancing while the system is suspended.
+    pub const MONOTONIC: Clock = Clock(libc::CLOCK_MONOTONIC);
+    /// Like `MONOTONIC`, but including time spent suspended.
+    pub const BOOTTIME: Clock = Clock(libc::CLOCK_BOOTTIME);
+    /// Like `MONOTONIC`, but not subject to NTP frequency adjustment.
+    pub const MONOTONIC_RAW: Clock = Clock(libc::CLOCK_MONOTONIC_RAW);
+    /// Faster, lower-resolution versions of `REALTIME` and `MONOTONIC`.
+    pub const REALTIME_COARSE: Clock = Clock(libc::CLOCK_REALTIME_COARSE);
+    pub const MONOTONIC_COARSE: Clock = Clock(libc::CLOCK_MONOTONIC_COARSE);
+    /// International Atomic Time, which unlike `REALTIME` counts leap
+    /// seconds.
+    pub const TAI: Clock = Clock(libc::CLOCK_TAI);
+    /// CPU time consumed by all threads of the calling process.
+    pub const PROCESS_CPUTIME_ID: Clock = Clock(libc::CLOCK_PROCESS_CPUTIME_ID);
+    /// CPU time consumed by the calling thread.
+    pub const THREAD_CPUTIME_ID: Clock = Clock(libc::CLOCK_THREAD_CPUTIME_ID);
+
+    /// The CPU-time clock of process `pid`, from `clock_getcpuclockid()`.
+    pub fn process_cpu(pid: u32) -> io::Result<Clock> {
+        let mut clockid = 0;
+        match unsafe { libc::clock_getcpuclockid(pid as libc::pid_t, &mut clockid) } {
+            0 => Ok(Clock(clockid)),
+            err => Err(io::Error::from_raw_os_error(err)),
+        }
+    }
+
+    pub const fn from_raw(clockid: clockid_t) -> Clock {
+        Clock(clockid)
+    }
+
+    pub const fn as_raw(self) -> clockid_t {
+        self.0
+    }
+
+    /// Read the clock with `clock_gettime()`.
+    pub fn now(self) -> io::Result<Timespec> {
+        let mut ts = Timespec::ZERO.as_raw();
+        let result = unsafe { libc::clock_gettime(self.0, &mut ts) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(Timespec::from_raw(ts))
+        }
+    }
+
+    /// Return the resolution of the clock, from `clock_getres()`.
+    pub fn resolution(self) -> io::Result<Timespec> {
+        let mut ts = Timespec::ZERO.as_raw();
+        let result = unsafe { libc::clock_getres(self.0, &mut ts) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(Timespec::from_raw(ts))
+        }
+    }
+
+    /// Set the clock, which requires `CAP_SYS_TIME` and is only possible
+    /// for `REALTIME`.
+    pub fn set(self, time: Timespec) -> io::Result<()> {
+        let result = unsafe { libc::clock_settime(self.0, &time.as_raw()) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sleep until the clock reaches `deadline`, resuming the sleep if a
+    /// signal handler interrupts it.
+    pub fn sleep_until(self, deadline: Timespec) -> io::Result<()> {
+        let deadline = deadline.as_raw();
+        loop {
+            let err = unsafe {
+                libc::clock_nanosleep(self.0, libc::TIMER_ABSTIME, &deadline, ptr::null_mut())
+            };
+            match err {
+                0 => return Ok(()),
+                libc::EINTR => continue,
+                err => return Err(io::Error::from_raw_os_error(err)),
+            }
+        }
+    }
+
+    /// Sleep for `duration` as measured by this clock.
+    ///
+    /// The sleep is turned into one until an absolute deadline, so that
+    /// time spent in signal handlers is not added to it, as happens when
+    /// restarting a relative sleep with the remaining time.
+    pub fn sleep(self, duration: Duration) -> io::Result<()> {
+        let deadline = self
+            .now()?
+            .checked_add_duration(duration)
+            .ok_or_else(|| io::Error::from_raw_os_error(libc::EINVAL))?;
+        self.sleep_until(deadline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: Timespec = Timespec {
+        sec: i64::MIN,
+        nsec: 0,
+    };
+
+    fn ts(sec: i64, nsec: u32) -> Timespec {
+        Timespec::new(sec, nsec)
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(ts(1, 2_500_000_000), ts(3, 500_000_000));
+        assert_eq!(ts(-1, 750_000_000).as_nanos(), -250_000_000);
+        assert!(ts(-1, 999_999_999) < Timespec::ZERO);
+        assert_eq!(Timespec::from_raw(ts(7, 8).as_raw()), ts(7, 8));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow in Timespec::new")]
+    fn new_overflow() {
+        Timespec::new(i64::MAX, NANOS_PER_SEC);
+    }
+
+    #[test]
+    fn add() {
+        assert_eq!(
+            ts(1, 600_000_000).checked_add(ts(2, 700_000_000)),
+            Some(ts(4, 300_000_000))
+        );
+        assert_eq!(
+            ts(1, 400_000_000).checked_add(ts(2, 600_000_000)),
+            Some(ts(4, 0))
+        );
+        assert_eq!(
+            ts(-1, 750_000_000).checked_add(ts(0, 250_000_000)),
+            Some(Timespec::ZERO)
+        );
+        assert_eq!(Timespec::MAX.checked_add(ts(0, 1)), None);
+        assert_eq!(ts(i64::MAX, 0).checked_add(ts(1, 0)), None);
+        assert_eq!(MIN.checked_add(Timespec::MAX), Some(ts(-1, 999_999_999)));
+        assert_eq!(ts(1, 0) + Duration::from_millis(1500), ts(2, 500_000_000));
+        assert_eq!(Timespec::ZERO.checked_add_duration(Duration::MAX), None);
+    }
+
+    #[test]
+    fn sub() {
+        assert_eq!(
+            ts(4, 300_000_000).checked_sub(ts(2, 700_000_000)),
+            Some(ts(1, 600_000_000))
+        );
+        assert_eq!(ts(2, 0).checked_sub(ts(2, 0)), Some(Timespec::ZERO));
+        assert_eq!(
+            Timespec::ZERO.checked_sub(ts(0, 250_000_000)),
+            Some(ts(-1, 750_000_000))
+        );
+        assert_eq!(MIN.checked_sub(ts(0, 1)), None);
+        assert_eq!(MIN.checked_sub(ts(1, 0)), None);
+        assert_eq!(Timespec::ZERO.checked_sub(MIN), None);
+        assert_eq!(
+            Timespec::MAX.checked_sub(Timespec::MAX),
+            Some(Timespec::ZERO)
+        );
+        assert_eq!(ts(1, 0) - Duration::from_millis(1500), ts(-1, 500_000_000));
+        assert_eq!(ts(3, 0) - ts(1, 1), ts(1, 999_999_999));
+    }
+
+    #[test]
+    fn durations() {
+        let d = Duration::new(5, 123);
+        assert_eq!(Timespec::from_duration(d).unwrap().to_duration(), Some(d));
+        assert_eq!(Timespec::from_duration(Duration::MAX), None);
+        assert_eq!(ts(-1, 750_000_000).to_duration(), None);
+        assert_eq!(
+            ts(5, 0).duration_since(ts(3, 500_000_000)),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(ts(3, 0).duration_since(ts(5, 0)), None);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Timespec::ZERO.to_string(), "0.000000000");
+        assert_eq!(ts(12, 5).to_string(), "12.000000005");
+        assert_eq!(ts(-1, 750_000_000).to_string(), "-0.250000000");
+        assert_eq!(ts(-3, 0).to_string(), "-3.000000000");
+        assert_eq!(ts(-3, 1).to_string(), "-2.999999999");
+        assert_eq!(MIN.to_string(), "-9223372036854775808.000000000");
+        assert_eq!(
+            ts(i64::MIN, 1).to_string(),
+            "-9223372036854775807.999999999"
+        );
+        assert_eq!(format!("{:>14}", ts(1, 0)), "   1.000000000");
+    }
+
+    #[test]
+    fn clocks() {
+        let a = Clock::MONOTONIC.now().unwrap();
+        let b = Clock::MONOTONIC.now().unwrap();
+        assert!(b >= a);
+        assert!(Clock::MONOTONIC.resolution().unwrap() > Timespec::ZERO);
+    }
+}
@@ -0,0 +1,228 @@
+mod format;
+mod tzif;
+
+use std::sync::OnceLock;
+
+pub use tzif::*;
+
+const SECS_PER_DAY: i64 = 86400;
+
+/// A broken-down calendar time, like `struct tm`, but with the full year
+/// and months counted from 1.
+///
+/// Fields passed to `timegm()` and `mktime()` may be out of range, and are
+/// normalized as by `mktime()`: month 13 is January of the next year and
+/// day 0 is the last day of the previous month.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tm {
+    /// The year, e.g. 2024 (`tm_year` + 1900).
+    pub year: i32,
+    /// 1 to 12 (`tm_mon` + 1).
+    pub month: i32,
+    /// 1 to 31.
+    pub day: i32,
+    pub hour: i32,
+    pub minute: i32,
+    /// 0 to 60, allowing for a leap second.
+    pub second: i32,
+    /// Days since Sunday, 0 to 6.
+    pub weekday: i32,
+    /// Days since 1 January, 0 to 365.
+    pub yearday: i32,
+    pub is_dst: bool,
+    /// Seconds east of UTC (`tm_gmtoff`).
+    pub utc_offset: i32,
+    /// The time zone abbreviation, e.g. "CET" (`tm_zone`).
+    pub zone: String,
+}
+
+pub(crate) fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Days from 1970-01-01 to the given proleptic Gregorian date, with
+/// `month` from 1 to 12 (after Howard Hinnant's `days_from_civil`).
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// The inverse of `days_from_civil`, returning (year, month, day).
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// The day of the week, 0 being Sunday, of a day counted from the Epoch
+/// (which was a Thursday).
+pub(crate) fn weekday(days: i64) -> i64 {
+    (days + 4).rem_euclid(7)
+}
+
+/// Break down `t`, in seconds since the Epoch, as UTC. Returns `None` if
+/// the year does not fit in an `i32`.
+pub fn gmtime(t: i64) -> Option<Tm> {
+    let days = t.div_euclid(SECS_PER_DAY);
+    let secs = t.rem_euclid(SECS_PER_DAY) as i32;
+    let (year, month, day) = civil_from_days(days);
+    Some(Tm {
+        year: year.try_into().ok()?,
+        month: month as i32,
+        day: day as i32,
+        hour: secs / 3600,
+        minute: secs / 60 % 60,
+        second: secs % 60,
+        weekday: weekday(days) as i32,
+        yearday: (days - days_from_civil(year, 1, 1)) as i32,
+        is_dst: false,
+        utc_offset: 0,
+        zone: "GMT".to_string(),
+    })
+}
+
+/// Convert a broken-down UTC time to seconds since the Epoch, ignoring
+/// `weekday`, `yearday` and the time zone fields.
+pub fn timegm(tm: &Tm) -> i64 {
+    let month = tm.month as i64 - 1;
+    let year = tm.year as i64 + month.div_euclid(12);
+    let days = days_from_civil(year, month.rem_euclid(12) + 1, 1) + tm.day as i64 - 1;
+    days * SECS_PER_DAY + tm.hour as i64 * 3600 + tm.minute as i64 * 60 + tm.second as i64
+}
+
+/// The time zone of the process, loaded by `TimeZone::local()` on first
+/// use. Unlike `tzset()`, later changes to `TZ` are not seen.
+pub fn local_zone() -> &'static TimeZone {
+    static LOCAL: OnceLock<TimeZone> = OnceLock::new();
+    LOCAL.get_or_init(TimeZone::local)
+}
+
+/// Break down `t` in the local time zone; a thread-safe `localtime()`.
+pub fn localtime(t: i64) -> Option<Tm> {
+    local_zone().to_local(t)
+}
+
+/// Convert a broken-down local time to seconds since the Epoch, as
+/// `mktime()` does; see `TimeZone::from_local`.
+pub fn mktime(tm: &Tm) -> i64 {
+    local_zone().from_local(tm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(year: i32, month: i32, day: i32, hour: i32, minute: i32, second: i32) -> Tm {
+        Tm {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            weekday: 0,
+            yearday: 0,
+            is_dst: false,
+            utc_offset: 0,
+            zone: "GMT".to_string(),
+        }
+    }
+
+    #[test]
+    fn leap_years() {
+        assert!(is_leap(2024));
+        assert!(is_leap(2000));
+        assert!(!is_leap(1900));
+        assert!(!is_leap(2023));
+        assert!(is_leap(-4));
+    }
+
+    #[test]
+    fn epoch_is_a_thursday() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(weekday(0), 4);
+        assert_eq!(weekday(-1), 3);
+        let tm = gmtime(0).unwrap();
+        assert_eq!(
+            (tm.year, tm.month, tm.day, tm.weekday, tm.yearday),
+            (1970, 1, 1, 4, 0)
+        );
+    }
+
+    #[test]
+    fn civil_round_trip() {
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(civil_from_days(-719468), (0, 3, 1));
+        for days in (-1_000_000..1_000_000).step_by(97) {
+            let (year, month, day) = civil_from_days(days);
+            assert!((1..=12).contains(&month) && (1..=31).contains(&day));
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        for (year, month) in [(2024, 2), (2023, 2), (1900, 2), (2000, 12), (-1, 1)] {
+            let next = if month == 12 {
+                (year + 1, 1)
+            } else {
+                (year, month + 1)
+            };
+            let last = days_from_civil(next.0, next.1, 1) - 1;
+            let (_, _, day) = civil_from_days(last);
+            let expected = match month {
+                2 if is_leap(year) => 29,
+                2 => 28,
+                _ => 31,
+            };
+            assert_eq!(day, expected, "{}-{}", year, month);
+        }
+    }
+
+    #[test]
+    fn gmtime_known_time() {
+        let tm = gmtime(1234567890).unwrap();
+        assert_eq!(
+            tm,
+            Tm {
+                weekday: 5,
+                yearday: 43,
+                ..utc(2009, 2, 13, 23, 31, 30)
+            }
+        );
+        let tm = gmtime(-1).unwrap();
+        assert_eq!(
+            (tm.year, tm.month, tm.day, tm.hour, tm.second),
+            (1969, 12, 31, 23, 59)
+        );
+        assert_eq!(gmtime(i64::MAX), None);
+    }
+
+    #[test]
+    fn timegm_normalizes() {
+        assert_eq!(timegm(&utc(2009, 2, 13, 23, 31, 30)), 1234567890);
+        assert_eq!(
+            timegm(&utc(2023, 13, 1, 0, 0, 0)),
+            timegm(&utc(2024, 1, 1, 0, 0, 0))
+        );
+        assert_eq!(
+            timegm(&utc(2024, 3, 0, 0, 0, 0)),
+            timegm(&utc(2024, 2, 29, 0, 0, 0))
+        );
+        assert_eq!(
+            timegm(&utc(2024, 1, 1, -1, 0, 0)),
+            timegm(&utc(2023, 12, 31, 23, 0, 0))
+        );
+        for t in [0, -1, 951782400, 4102444800, -62167219200] {
+            assert_eq!(timegm(&gmtime(t).unwrap()), t);
+        }
+    }
+}
@@ -0,0 +1,488 @@
+use super::{civil_from_days, days_from_civil, is_leap, local_zone, timegm, weekday, Tm};
+use std::fmt::Write;
+use std::io;
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Names are indexed modulo their count, so out-of-range fields give a
+/// wrong name rather than a panic.
+fn name(names: &[&'static str], i: i32) -> &'static str {
+    names[i.rem_euclid(names.len() as i32) as usize]
+}
+
+/// The number of ISO 8601 weeks in `year`: 53 if it starts on a Thursday,
+/// or is a leap year starting on a Wednesday.
+fn iso_weeks_in_year(year: i64) -> i64 {
+    match weekday(days_from_civil(year, 1, 1)) {
+        4 => 53,
+        3 if is_leap(year) => 53,
+        _ => 52,
+    }
+}
+
+/// The ISO 8601 week-based year and week number (1 to 53), in which weeks
+/// start on Monday and week 1 contains the year's first Thursday.
+fn iso_week(tm: &Tm) -> (i64, i64) {
+    let year = tm.year as i64;
+    let monday_based = (tm.weekday as i64 + 6) % 7;
+    let week = (tm.yearday as i64 - monday_based + 10) / 7;
+    if week < 1 {
+        (year - 1, iso_weeks_in_year(year - 1))
+    } else if week > iso_weeks_in_year(year) {
+        (year + 1, 1)
+    } else {
+        (year, week)
+    }
+}
+
+impl Tm {
+    /// Format as `strftime()` does in the C locale.
+    ///
+    /// All of the C and POSIX conversions are supported, with the `E` and
+    /// `O` modifiers ignored, as are the glibc flags `-` (no padding), `_`
+    /// (pad with spaces) and `0` (pad with zeros) on numeric fields, `%k`,
+    /// `%l`, `%P` and `%s`. Unknown conversions are copied as they are.
+    pub fn format(&self, fmt: &str) -> String {
+        let mut out = String::new();
+        self.format_into(&mut out, fmt);
+        out
+    }
+
+    fn format_into(&self, out: &mut String, fmt: &str) {
+        let mut chars = fmt.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            let start = chars.as_str();
+            let mut flag = None;
+            let mut conv = chars.next();
+            while let Some(c @ ('-' | '_' | '0' | 'E' | 'O')) = conv {
+                if !matches!(c, 'E' | 'O') {
+                    flag = Some(c);
+                }
+                conv = chars.next();
+            }
+            let mut num = |value: i64, width: usize, pad: char| {
+                let pad = match flag {
+                    Some('-') => return write!(out, "{}", value).unwrap(),
+                    Some('_') => ' ',
+                    Some('0') => '0',
+                    _ => pad,
+                };
+                let digits = value.unsigned_abs().to_string();
+                if value < 0 {
+                    out.push('-');
+                }
+                for _ in digits.len() + (value < 0) as usize..width {
+                    out.push(pad);
+                }
+                out.push_str(&digits);
+            };
+            let hour12 = match self.hour % 12 {
+                0 => 12,
+                h => h,
+            } as i64;
+            match conv {
+                Some('a') => out.push_str(&name(&WEEKDAYS, self.weekday)[..3]),
+                Some('A') => out.push_str(name(&WEEKDAYS, self.weekday)),
+                Some('b' | 'h') => out.push_str(&name(&MONTHS, self.month - 1)[..3]),
+                Some('B') => out.push_str(name(&MONTHS, self.month - 1)),
+                Some('c') => self.format_into(out, "%a %b %e %H:%M:%S %Y"),
+                Some('C') => num((self.year as i64).div_euclid(100), 2, '0'),
+                Some('d') => num(self.day as i64, 2, '0'),
+                Some('D' | 'x') => self.format_into(out, "%m/%d/%y"),
+                Some('e') => num(self.day as i64, 2, ' '),
+                Some('F') => self.format_into(out, "%Y-%m-%d"),
+                Some('g') => num(iso_week(self).0.rem_euclid(100), 2, '0'),
+                Some('G') => num(iso_week(self).0, 1, '0'),
+                Some('H') => num(self.hour as i64, 2, '0'),
+                Some('I') => num(hour12, 2, '0'),
+                Some('j') => num(self.yearday as i64 + 1, 3, '0'),
+                Some('k') => num(self.hour as i64, 2, ' '),
+                Some('l') => num(hour12, 2, ' '),
+                Some('m') => num(self.month as i64, 2, '0'),
+                Some('M') => num(self.minute as i64, 2, '0'),
+                Some('n') => out.push('\n'),
+                Some('p') => out.push_str(if self.hour < 12 { "AM" } else { "PM" }),
+                Some('P') => out.push_str(if self.hour < 12 { "am" } else { "pm" }),
+                Some('r') => self.format_into(out, "%I:%M:%S %p"),
+                Some('R') => self.format_into(out, "%H:%M"),
+                Some('s') => num(timegm(self) - self.utc_offset as i64, 1, '0'),
+                Some('S') => num(self.second as i64, 2, '0'),
+                Some('t') => out.push('\t'),
+                Some('T' | 'X') => self.format_into(out, "%H:%M:%S"),
+                Some('u') => num(
+                    match self.weekday {
+                        0 => 7,
+                        d => d,
+                    } as i64,
+                    1,
+                    '0',
+                ),
+                Some('U') => num(((self.yearday + 7 - self.weekday) / 7) as i64, 2, '0'),
+                Some('V') => num(iso_week(self).1, 2, '0'),
+                Some('w') => num(self.weekday as i64, 1, '0'),
+                Some('W') => num(
+                    ((self.yearday + 7 - (self.weekday + 6) % 7) / 7) as i64,
+                    2,
+                    '0',
+                ),
+                Some('y') => num((self.year as i64).rem_euclid(100), 2, '0'),
+                Some('Y') => num(self.year as i64, 1, '0'),
+                Some('z') => {
+                    let offset = self.utc_offset.unsigned_abs() / 60;
+                    let sign = if self.utc_offset < 0 { '-' } else { '+' };
+                    write!(out, "{}{:02}{:02}", sign, offset / 60, offset % 60).unwrap();
+                }
+                Some('Z') => out.push_str(&self.zone),
+                Some('%') => out.push('%'),
+                _ => {
+                    out.push('%');
+                    out.push_str(&start[..start.len() - chars.as_str().len()]);
+                }
+            }
+        }
+    }
+
+    /// Parse `s` as `strptime()` does in the C locale, except that the
+    /// whole of `s` must match, apart from trailing white space.
+    ///
+    /// Fields that `fmt` does not set are those of the Epoch in UTC. If the
+    /// date is known, `weekday` and `yearday` are computed from it, or with
+    /// `%j` and a year, the month and day are. `%Z` skips a word without
+    /// interpreting it, and `%s` breaks the time down in the local zone.
+    pub fn parse(s: &str, fmt: &str) -> io::Result<Tm> {
+        let mut tm = Tm {
+            year: 1970,
+            month: 1,
+            day: 1,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            weekday: 4,
+            yearday: 0,
+            is_dst: false,
+            utc_offset: 0,
+            zone: "GMT".to_string(),
+        };
+        let mut state = ParseState::default();
+        let rest = parse_into(&mut tm, &mut state, s, fmt).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "time does not match format")
+        })?;
+        if !rest.trim_start().is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "trailing characters",
+            ));
+        }
+        state.finish(&mut tm);
+        Ok(tm)
+    }
+}
+
+#[derive(Default)]
+struct ParseState {
+    /// Whether the hour came from `%I`, so that `%p` applies to it.
+    twelve_hour: bool,
+    pm: bool,
+    century: Option<i32>,
+    /// The year within the century, from `%y`.
+    year_in_century: Option<i32>,
+    have_date: bool,
+    have_yearday: bool,
+}
+
+impl ParseState {
+    fn finish(&self, tm: &mut Tm) {
+        if self.twelve_hour && self.pm {
+            tm.hour += 12;
+        }
+        match (self.century, self.year_in_century) {
+            (Some(century), Some(year)) => tm.year = century * 100 + year,
+            (Some(century), None) => tm.year = century * 100,
+            // POSIX: 69 to 99 are 1969 to 1999, 0 to 68 are 2000 to 2068
+            (None, Some(year)) => tm.year = year + if year < 69 { 2000 } else { 1900 },
+            (None, None) => (),
+        }
+        let year = tm.year as i64;
+        if self.have_date {
+            let days = days_from_civil(year, tm.month as i64, tm.day as i64);
+            tm.weekday = weekday(days) as i32;
+            tm.yearday = (days - days_from_civil(year, 1, 1)) as i32;
+        } else if self.have_yearday {
+            let days = days_from_civil(year, 1, 1) + tm.yearday as i64;
+            let (_, month, day) = civil_from_days(days);
+            tm.month = month as i32;
+            tm.day = day as i32;
+            tm.weekday = weekday(days) as i32;
+        }
+    }
+}
+
+/// Parse a number of at most `max_digits` digits in `min..=max`, after
+/// optional white space.
+fn number(s: &str, max_digits: usize, min: i32, max: i32) -> Option<(i32, &str)> {
+    let s = s.trim_start();
+    let len = s
+        .bytes()
+        .take(max_digits)
+        .take_while(u8::is_ascii_digit)
+        .count();
+    let n: i32 = s[..len].parse().ok()?;
+    (min..=max).contains(&n).then_some((n, &s[len..]))
+}
+
+/// Match a full or abbreviated name, ignoring case, returning its index.
+fn name_index<'a>(s: &'a str, names: &[&str]) -> Option<(i32, &'a str)> {
+    let s = s.trim_start();
+    for (i, name) in names.iter().enumerate() {
+        for candidate in [*name, &name[..3]] {
+            if let Some(prefix) = s.get(..candidate.len()) {
+                if prefix.eq_ignore_ascii_case(candidate) {
+                    return Some((i as i32, &s[candidate.len()..]));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn parse_into<'a>(
+    tm: &mut Tm,
+    state: &mut ParseState,
+    mut s: &'a str,
+    fmt: &str,
+) -> Option<&'a str> {
+    let mut chars = fmt.chars();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            s = s.trim_start();
+            continue;
+        }
+        if c != '%' {
+            s = s.strip_prefix(c)?;
+            continue;
+        }
+        let mut conv = chars.next()?;
+        while matches!(conv, 'E' | 'O') {
+            conv = chars.next()?;
+        }
+        let field = |s: &'a str, digits, min, max, set: &mut dyn FnMut(i32)| {
+            let (n, rest) = number(s, digits, min, max)?;
+            set(n);
+            Some(rest)
+        };
+        s = match conv {
+            'a' | 'A' => name_index(s, &WEEKDAYS).map(|(i, rest)| {
+                tm.weekday = i;
+                rest
+            })?,
+            'b' | 'B' | 'h' => {
+                state.have_date = true;
+                name_index(s, &MONTHS).map(|(i, rest)| {
+                    tm.month = i + 1;
+                    rest
+                })?
+            }
+            'c' => parse_into(tm, state, s, "%a %b %e %H:%M:%S %Y")?,
+            'C' => field(s, 2, 0, 99, &mut |n| state.century = Some(n))?,
+            'd' | 'e' => {
+                state.have_date = true;
+                field(s, 2, 1, 31, &mut |n| tm.day = n)?
+            }
+            'D' | 'x' => parse_into(tm, state, s, "%m/%d/%y")?,
+            'F' => parse_into(tm, state, s, "%Y-%m-%d")?,
+            'H' | 'k' => field(s, 2, 0, 23, &mut |n| tm.hour = n)?,
+            'I' | 'l' => {
+                state.twelve_hour = true;
+                field(s, 2, 1, 12, &mut |n| tm.hour = n % 12)?
+            }
+            'j' => {
+                state.have_yearday = true;
+                field(s, 3, 1, 366, &mut |n| tm.yearday = n - 1)?
+            }
+            'm' => {
+                state.have_date = true;
+                field(s, 2, 1, 12, &mut |n| tm.month = n)?
+            }
+            'M' => field(s, 2, 0, 59, &mut |n| tm.minute = n)?,
+            'n' | 't' => s.trim_start(),
+            'p' | 'P' => {
+                let t = s.trim_start();
+                let pm = match t.get(..2) {
+                    Some(p) if p.eq_ignore_ascii_case("AM") => false,
+                    Some(p) if p.eq_ignore_ascii_case("PM") => true,
+                    _ => return None,
+                };
+                state.pm = pm;
+                &t[2..]
+            }
+            'r' => parse_into(tm, state, s, "%I:%M:%S %p")?,
+            'R' => parse_into(tm, state, s, "%H:%M")?,
+            's' => {
+                let t = s.trim_start();
+                let len = t
+                    .char_indices()
+                    .take_while(|&(i, c)| c.is_ascii_digit() || (i == 0 && c == '-'))
+                    .count();
+                let secs: i64 = t[..len].parse().ok()?;
+                *tm = local_zone().to_local(secs)?;
+                *state = ParseState::default();
+                &t[len..]
+            }
+            'S' => field(s, 2, 0, 60, &mut |n| tm.second = n)?,
+            'T' | 'X' => parse_into(tm, state, s, "%H:%M:%S")?,
+            'u' => field(s, 1, 1, 7, &mut |n| tm.weekday = n % 7)?,
+            'w' => field(s, 1, 0, 6, &mut |n| tm.weekday = n)?,
+            'y' => field(s, 2, 0, 99, &mut |n| state.year_in_century = Some(n))?,
+            'Y' => {
+                let t = s.trim_start();
+                let (negative, t) = match t.strip_prefix('-') {
+                    Some(t) => (true, t),
+                    None => (false, t),
+                };
+                let (n, rest) = number(t, 4, 0, 9999)?;
+                tm.year = if negative { -n } else { n };
+                state.century = None;
+                state.year_in_century = None;
+                rest
+            }
+            'z' => {
+                let t = s.trim_start();
+                if let Some(rest) = t.strip_prefix('Z') {
+                    tm.utc_offset = 0;
+                    rest
+                } else {
+                    let sign = match t.chars().next()? {
+                        '+' => 1,
+                        '-' => -1,
+                        _ => return None,
+                    };
+                    let (hours, rest) = number(&t[1..], 2, 0, 99)?;
+                    let rest = rest.strip_prefix(':').unwrap_or(rest);
+                    let (minutes, rest) = match number(rest, 2, 0, 59) {
+                        Some(found) => found,
+                        None => (0, rest),
+                    };
+                    tm.utc_offset = sign * (hours * 3600 + minutes * 60);
+                    rest
+                }
+            }
+            'Z' => s
+                .trim_start()
+                .trim_start_matches(|c: char| !c.is_whitespace()),
+            '%' => s.trim_start().strip_prefix('%')?,
+            _ => return None,
+        };
+    }
+    Some(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::calendar::gmtime;
+
+    fn format(t: i64, fmt: &str) -> String {
+        gmtime(t).unwrap().format(fmt)
+    }
+
+    #[test]
+    fn format_fields() {
+        let t = 1720083903; // 2024-07-04 09:05:03 UTC
+        assert_eq!(format(t, "%Y-%m-%d %H:%M:%S"), "2024-07-04 09:05:03");
+        assert_eq!(format(t, "%c"), "Thu Jul  4 09:05:03 2024");
+        assert_eq!(format(t, "%D|%r|%F"), "07/04/24|09:05:03 AM|2024-07-04");
+        assert_eq!(format(t, "%-d|%_m|%k|%l|%p|%y|%C"), "4| 7| 9| 9|AM|24|20");
+        assert_eq!(
+            format(t, "%j %A %B %z %Z %%"),
+            "186 Thursday July +0000 GMT %"
+        );
+        assert_eq!(format(t, "%s"), "1720083903");
+        assert_eq!(format(t, "%q"), "%q");
+    }
+
+    #[test]
+    fn iso_weeks() {
+        assert_eq!(format(1609372800, "%G-W%V-%u"), "2020-W53-4"); // 2020-12-31
+        assert_eq!(format(1609632000, "%G-W%V-%u"), "2020-W53-7"); // 2021-01-03
+        assert_eq!(format(1609718400, "%G-W%V-%u %g"), "2021-W01-1 21"); // 2021-01-04
+        assert_eq!(format(1672531200, "%G-W%V-%u"), "2022-W52-7"); // 2023-01-01
+        assert_eq!(format(1735516800, "%G-W%V-%u"), "2025-W01-1"); // 2024-12-30
+    }
+
+    #[test]
+    fn sunday_and_monday_weeks() {
+        // 2024 starts on a Monday and 2023 on a Sunday
+        assert_eq!(format(1704067200, "%U %W"), "00 01");
+        assert_eq!(format(1672531200, "%U %W"), "01 00");
+        assert_eq!(format(1609632000, "%U %W"), "01 00"); // Sunday 2021-01-03
+        assert_eq!(format(1735516800, "%U %W"), "52 53"); // Monday 2024-12-30
+    }
+
+    #[test]
+    fn parse_date_and_time() {
+        let tm = Tm::parse("2024-07-04 09:05:03", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(tm, gmtime(1720083903).unwrap());
+        let tm = Tm::parse("Thu Jul  4 09:05:03 2024", "%c").unwrap();
+        assert_eq!(tm, gmtime(1720083903).unwrap());
+        let tm = Tm::parse("12:30:00 am", "%r").unwrap();
+        assert_eq!((tm.hour, tm.minute), (0, 30));
+        let tm = Tm::parse("12:30:00 PM", "%I:%M:%S %p").unwrap();
+        assert_eq!(tm.hour, 12);
+        let tm = Tm::parse("+05:30", "%z").unwrap();
+        assert_eq!(tm.utc_offset, 19800);
+        assert!(Tm::parse("2024-07-04 junk", "%Y-%m-%d").is_err());
+        assert!(Tm::parse("2024-13-04", "%Y-%m-%d").is_err());
+        assert!(Tm::parse("2024-07-04  ", "%Y-%m-%d").is_ok());
+    }
+
+    #[test]
+    fn parse_century() {
+        assert_eq!(Tm::parse("68", "%y").unwrap().year, 2068);
+        assert_eq!(Tm::parse("69", "%y").unwrap().year, 1969);
+        assert_eq!(Tm::parse("1999", "%C%y").unwrap().year, 1999);
+        assert_eq!(Tm::parse("21 05", "%C %y").unwrap().year, 2105);
+        assert_eq!(Tm::parse("20", "%C").unwrap().year, 2000);
+        // %Y overrides an earlier %C and %y
+        assert_eq!(Tm::parse("19 99 2024", "%C %y %Y").unwrap().year, 2024);
+    }
+
+    #[test]
+    fn parse_yearday() {
+        let tm = Tm::parse("2024 060", "%Y %j").unwrap();
+        assert_eq!((tm.month, tm.day, tm.yearday, tm.weekday), (2, 29, 59, 4));
+        let tm = Tm::parse("2023 060", "%Y %j").unwrap();
+        assert_eq!((tm.month, tm.day, tm.yearday), (3, 1, 59));
+        let tm = Tm::parse("24 366", "%y %j").unwrap();
+        assert_eq!((tm.year, tm.month, tm.day), (2024, 12, 31));
+        assert!(Tm::parse("2024 367", "%Y %j").is_err());
+        // A full date takes precedence, and gives the yearday
+        let tm = Tm::parse("2024-03-01 001", "%F %j").unwrap();
+        assert_eq!((tm.month, tm.day, tm.yearday), (3, 1, 60));
+    }
+}
@@ -0,0 +1,710 @@
+use super::{civil_from_days, days_from_civil, gmtime, is_leap, timegm, weekday, Tm, SECS_PER_DAY};
+use crate::env;
+use crate::fs::OpenOptions;
+use std::ffi::OsString;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+
+const ZONEINFO: &str = "/usr/share/zoneinfo";
+const LOCALTIME: &str = "/etc/localtime";
+
+/// The offset, DST flag and abbreviation in effect in a time zone at some
+/// time (`struct ttinfo`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalTimeType {
+    /// Seconds east of UTC.
+    pub utc_offset: i32,
+    pub is_dst: bool,
+    pub abbreviation: String,
+}
+
+impl LocalTimeType {
+    fn utc() -> LocalTimeType {
+        LocalTimeType {
+            utc_offset: 0,
+            is_dst: false,
+            abbreviation: "UTC".to_string(),
+        }
+    }
+}
+
+/// A time zone, from a TZif file (RFC 8536, versions 1 to 3) or a POSIX
+/// `TZ` string.
+///
+/// Unlike `localtime_r()`, which consults the process-wide state set by
+/// `tzset()`, conversions only use the zone itself, so several zones can
+/// be used at once, from any thread. Leap second records, which only the
+/// "right/" zones have, are ignored.
+#[derive(Clone, Debug)]
+pub struct TimeZone {
+    /// Transition times, ascending, with the index into `types` of the
+    /// local time type that starts at each.
+    transitions: Vec<(i64, usize)>,
+    types: Vec<LocalTimeType>,
+    /// The rule for times after the last transition, from the footer.
+    rule: Option<PosixTz>,
+}
+
+impl TimeZone {
+    pub fn utc() -> TimeZone {
+        TimeZone {
+            transitions: Vec::new(),
+            types: vec![LocalTimeType::utc()],
+            rule: None,
+        }
+    }
+
+    /// Decode the contents of a TZif file.
+    pub fn parse(bytes: &[u8]) -> io::Result<TimeZone> {
+        let mut data = Data(bytes);
+        let (version, counts) = data.header()?;
+        if version == 0 {
+            return data.body(&counts, 4);
+        }
+
+        // Version 2 and later files repeat the data with 64-bit times,
+        // followed by a POSIX TZ string between newlines
+        data.take(counts.body_len(4))?;
+        let (_, counts) = data.header()?;
+        let mut zone = data.body(&counts, 8)?;
+        let footer = match data.0 {
+            [b'\n', footer @ .., b'\n'] => footer,
+            _ => return Err(invalid()),
+        };
+        if !footer.is_empty() {
+            let footer = std::str::from_utf8(footer).map_err(|_| invalid())?;
+            zone.rule = Some(PosixTz::parse(footer).ok_or_else(invalid)?);
+        }
+        Ok(zone)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<TimeZone> {
+        let mut buf = Vec::new();
+        OpenOptions::new()
+            .read(true)
+            .open(path)?
+            .read_to_end(&mut buf)?;
+        TimeZone::parse(&buf)
+    }
+
+    /// Load a zone such as "Europe/Berlin" from the database in `$TZDIR`,
+    /// or /usr/share/zoneinfo.
+    pub fn named(name: &str) -> io::Result<TimeZone> {
+        let path = Path::new(name);
+        if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid time zone name",
+            ));
+        }
+        let dir = env::getenv("TZDIR").unwrap_or_else(|| OsString::from(ZONEINFO));
+        TimeZone::from_file(PathBuf::from(dir).join(path))
+    }
+
+    /// Create a zone from a POSIX `TZ` string such as
+    /// "CET-1CEST,M3.5.0,M10.5.0/3". Note that the offsets in these are
+    /// west of UTC.
+    pub fn from_posix(tz: &str) -> io::Result<TimeZone> {
+        let rule = PosixTz::parse(tz)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid TZ string"))?;
+        Ok(TimeZone {
+            transitions: Vec::new(),
+            types: vec![rule.std.clone()],
+            rule: Some(rule),
+        })
+    }
+
+    /// The zone selected by the `TZ` environment variable as glibc
+    /// interprets it: unset means /etc/localtime; otherwise, after
+    /// removing a leading ':', an absolute path to a TZif file, a name in
+    /// the database, or a POSIX `TZ` string. Anything that cannot be
+    /// loaded gives UTC.
+    pub fn local() -> TimeZone {
+        let tz = match env::getenv("TZ").map(OsString::into_string) {
+            Some(Ok(tz)) => tz,
+            None => {
+                return TimeZone::from_file(LOCALTIME).unwrap_or_else(|_| TimeZone::utc());
+            }
+            Some(Err(_)) => return TimeZone::utc(),
+        };
+        let tz = tz.strip_prefix(':').unwrap_or(&tz);
+        if tz.is_empty() {
+            return TimeZone::utc();
+        }
+        let zone = if tz.starts_with('/') {
+            TimeZone::from_file(tz)
+        } else {
+            TimeZone::named(tz).or_else(|_| TimeZone::from_posix(tz))
+        };
+        zone.unwrap_or_else(|_| TimeZone::utc())
+    }
+
+    /// Return the local time type in effect at `t` seconds since the
+    /// Epoch.
+    pub fn local_time_type(&self, t: i64) -> &LocalTimeType {
+        match (self.transitions.last(), &self.rule) {
+            (Some(&(last, _)), Some(rule)) if t >= last => return rule.find(t),
+            (None, Some(rule)) => return rule.find(t),
+            _ => (),
+        }
+        // Times before the first transition use the first type
+        match self.transitions.partition_point(|&(time, _)| time <= t) {
+            0 => &self.types[0],
+            i => &self.types[self.transitions[i - 1].1],
+        }
+    }
+
+    /// Break down `t` as local time in this zone. Returns `None` if the
+    /// year does not fit in an `i32`.
+    pub fn to_local(&self, t: i64) -> Option<Tm> {
+        let ty = self.local_time_type(t);
+        let mut tm = gmtime(t.checked_add(ty.utc_offset as i64)?)?;
+        tm.is_dst = ty.is_dst;
+        tm.utc_offset = ty.utc_offset;
+        tm.zone = ty.abbreviation.clone();
+        Some(tm)
+    }
+
+    /// Convert a broken-down local time in this zone to seconds since the
+    /// Epoch. Out-of-range fields are normalized, and `weekday`,
+    /// `yearday`, `utc_offset` and `zone` are ignored.
+    ///
+    /// A local time that occurs twice, when clocks go back, is resolved
+    /// with `is_dst`, and otherwise taken as the earlier. One skipped when
+    /// clocks go forward is interpreted with the offset from before the
+    /// change, so 02:30 in a gap of an hour gives 03:30.
+    pub fn from_local(&self, tm: &Tm) -> i64 {
+        let local = timegm(tm);
+        // No zone changes its offset more than once within a day
+        let before = self.local_time_type(local - SECS_PER_DAY).utc_offset as i64;
+        let after = self.local_time_type(local + SECS_PER_DAY).utc_offset as i64;
+
+        let mut candidates = [local - before, local - after];
+        candidates.sort();
+        let valid = candidates
+            .iter()
+            .map(|&t| (t, self.local_time_type(t)))
+            .filter(|(t, ty)| local - ty.utc_offset as i64 == *t);
+        let mut first = None;
+        for (t, ty) in valid {
+            if ty.is_dst == tm.is_dst {
+                return t;
+            }
+            first.get_or_insert(t);
+        }
+        first.unwrap_or(local - before)
+    }
+}
+
+fn invalid() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed TZif data")
+}
+
+struct Counts {
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl Counts {
+    fn body_len(&self, time_size: usize) -> usize {
+        self.timecnt * (time_size + 1)
+            + self.typecnt * 6
+            + self.charcnt
+            + self.leapcnt * (time_size + 4)
+            + self.isstdcnt
+            + self.isutcnt
+    }
+}
+
+/// The unread part of a TZif file, in which integers are big-endian.
+struct Data<'a>(&'a [u8]);
+
+impl<'a> Data<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(invalid());
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    /// Read the 44-byte header, returning the version (0 for version 1)
+    /// and the counts.
+    fn header(&mut self) -> io::Result<(u8, Counts)> {
+        if self.take(4)? != b"TZif" {
+            return Err(invalid());
+        }
+        let version = match self.take(1)?[0] {
+            0 => 0,
+            v @ b'2'..=b'9' => v - b'0',
+            _ => return Err(invalid()),
+        };
+        self.take(15)?;
+        let mut count = || self.u32().map(|n| n as usize);
+        let counts = Counts {
+            isutcnt: count()?,
+            isstdcnt: count()?,
+            leapcnt: count()?,
+            timecnt: count()?,
+            typecnt: count()?,
+            charcnt: count()?,
+        };
+        if counts.typecnt == 0 {
+            return Err(invalid());
+        }
+        Ok((version, counts))
+    }
+
+    fn body(&mut self, counts: &Counts, time_size: usize) -> io::Result<TimeZone> {
+        let times = self.take(counts.timecnt * time_size)?;
+        let indices = self.take(counts.timecnt)?;
+        let ttinfos = self.take(counts.typecnt * 6)?;
+        let chars = self.take(counts.charcnt)?;
+        self.take(counts.leapcnt * (time_size + 4) + counts.isstdcnt + counts.isutcnt)?;
+
+        let mut transitions = Vec::with_capacity(counts.timecnt);
+        for (time, &index) in times.chunks_exact(time_size).zip(indices) {
+            let time = match time_size {
+                4 => i32::from_be_bytes(time.try_into().unwrap()) as i64,
+                _ => i64::from_be_bytes(time.try_into().unwrap()),
+            };
+            if index as usize >= counts.typecnt {
+                return Err(invalid());
+            }
+            transitions.push((time, index as usize));
+        }
+
+        let mut types = Vec::with_capacity(counts.typecnt);
+        for ttinfo in ttinfos.chunks_exact(6) {
+            let abbreviation = chars.get(ttinfo[5] as usize..).ok_or_else(invalid)?;
+            let len = abbreviation
+                .iter()
+                .position(|&c| c == 0)
+                .ok_or_else(invalid)?;
+            types.push(LocalTimeType {
+                utc_offset: i32::from_be_bytes(ttinfo[..4].try_into().unwrap()),
+                is_dst: ttinfo[4] != 0,
+                abbreviation: String::from_utf8_lossy(&abbreviation[..len]).into_owned(),
+            });
+        }
+
+        Ok(TimeZone {
+            transitions,
+            types,
+            rule: None,
+        })
+    }
+}
+
+/// The day of the year on which a DST rule takes effect.
+#[derive(Clone, Copy, Debug)]
+enum RuleDay {
+    /// `Jn`: day 1 to 365, not counting 29 February.
+    Julian1(i64),
+    /// `n`: day 0 to 365, counting 29 February.
+    Julian0(i64),
+    /// `Mm.w.d`: weekday `d` (0 is Sunday) of week `w` (5 being the last)
+    /// of month `m`.
+    MonthWeekDay(i64, i64, i64),
+}
+
+impl RuleDay {
+    fn days_since_epoch(self, year: i64) -> i64 {
+        let jan1 = days_from_civil(year, 1, 1);
+        match self {
+            RuleDay::Julian1(n) if is_leap(year) && n >= 60 => jan1.saturating_add(n),
+            RuleDay::Julian1(n) => jan1.saturating_add(n - 1),
+            RuleDay::Julian0(n) => jan1.saturating_add(n),
+            RuleDay::MonthWeekDay(month, week, day) => {
+                let first = days_from_civil(year, month, 1);
+                let offset = (day - weekday(first)).rem_euclid(7) + (week - 1) * 7;
+                let mut days = first.saturating_add(offset);
+                let next_month = days_from_civil(year, month + 1, 1);
+                while days >= next_month {
+                    days -= 7;
+                }
+                days
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Dst {
+    ty: LocalTimeType,
+    /// When DST starts and ends, each as a day and a time of day (which
+    /// may be negative or beyond 24 hours) in the local time then current.
+    start: (RuleDay, i64),
+    end: (RuleDay, i64),
+}
+
+/// A POSIX `TZ` string: "std offset [dst [offset] [,start[/time],end[/time]]]".
+#[derive(Clone, Debug)]
+struct PosixTz {
+    std: LocalTimeType,
+    dst: Option<Dst>,
+}
+
+impl PosixTz {
+    fn parse(s: &str) -> Option<PosixTz> {
+        let mut p = TzParser(s.as_bytes());
+        let std = LocalTimeType {
+            abbreviation: p.name()?,
+            utc_offset: -p.offset(24)?,
+            is_dst: false,
+        };
+        if p.0.is_empty() {
+            return Some(PosixTz { std, dst: None });
+        }
+
+        let abbreviation = p.name()?;
+        let utc_offset = match p.0.first() {
+            Some(b'0'..=b'9' | b'+' | b'-') => -p.offset(24)?,
+            _ => std.utc_offset + 3600,
+        };
+        let ty = LocalTimeType {
+            abbreviation,
+            utc_offset,
+            is_dst: true,
+        };
+        // Without rules, glibc uses the US ones
+        let (start, end) = if p.eat(b',') {
+            let start = p.rule()?;
+            if !p.eat(b',') {
+                return None;
+            }
+            (start, p.rule()?)
+        } else {
+            (
+                (RuleDay::MonthWeekDay(3, 2, 0), 7200),
+                (RuleDay::MonthWeekDay(11, 1, 0), 7200),
+            )
+        };
+        if !p.0.is_empty() {
+            return None;
+        }
+        Some(PosixTz {
+            std,
+            dst: Some(Dst { ty, start, end }),
+        })
+    }
+
+    fn find(&self, t: i64) -> &LocalTimeType {
+        let dst = match &self.dst {
+            Some(dst) => dst,
+            None => return &self.std,
+        };
+        // Saturate so that times near the ends of the range still get an
+        // type, leaving to_local() to report that they overflow.
+        let local = t.saturating_add(self.std.utc_offset as i64);
+        let (year, _, _) = civil_from_days(local.div_euclid(SECS_PER_DAY));
+        let instant = |(day, time): (RuleDay, i64), offset: i32| {
+            day.days_since_epoch(year)
+                .saturating_mul(SECS_PER_DAY)
+                .saturating_add(time)
+                .saturating_sub(offset as i64)
+        };
+        let start = instant(dst.start, self.std.utc_offset);
+        let end = instant(dst.end, dst.ty.utc_offset);
+        // In the southern hemisphere, DST spans the new year
+        let in_dst = if start <= end {
+            start <= t && t < end
+        } else {
+            !(end <= t && t < start)
+        };
+        if in_dst {
+            &dst.ty
+        } else {
+            &self.std
+        }
+    }
+}
+
+struct TzParser<'a>(&'a [u8]);
+
+impl TzParser<'_> {
+    fn eat(&mut self, c: u8) -> bool {
+        match self.0.split_first() {
+            Some((&first, rest)) if first == c => {
+                self.0 = rest;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &[u8] {
+        let len = self.0.iter().position(|&c| !f(c)).unwrap_or(self.0.len());
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        head
+    }
+
+    /// An abbreviation: at least three letters, or anything between
+    /// angle brackets such as "<+03>".
+    fn name(&mut self) -> Option<String> {
+        let name = if self.eat(b'<') {
+            let name = self
+                .take_while(|c| c.is_ascii_alphanumeric() || c == b'+' || c == b'-')
+                .to_vec();
+            if !self.eat(b'>') {
+                return None;
+            }
+            name
+        } else {
+            self.take_while(|c| c.is_ascii_alphabetic()).to_vec()
+        };
+        if name.len() < 3 {
+            return None;
+        }
+        String::from_utf8(name).ok()
+    }
+
+    fn number(&mut self, max: i64) -> Option<i64> {
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        let n: i64 = std::str::from_utf8(digits).ok()?.parse().ok()?;
+        if n > max {
+            return None;
+        }
+        Some(n)
+    }
+
+    /// A signed time "[+-]hh[:mm[:ss]]", in seconds, with at most
+    /// `max_hours` hours.
+    fn offset(&mut self, max_hours: i64) -> Option<i32> {
+        let sign = if self.eat(b'-') {
+            -1
+        } else {
+            self.eat(b'+');
+            1
+        };
+        let mut secs = self.number(max_hours)? * 3600;
+        if self.eat(b':') {
+            secs += self.number(59)? * 60;
+            if self.eat(b':') {
+                secs += self.number(59)?;
+            }
+        }
+        Some((sign * secs) as i32)
+    }
+
+    fn rule(&mut self) -> Option<(RuleDay, i64)> {
+        let day = if self.eat(b'J') {
+            RuleDay::Julian1(self.number(365).filter(|&n| n >= 1)?)
+        } else if self.eat(b'M') {
+            let month = self.number(12).filter(|&m| m >= 1)?;
+            let week = self
+                .eat(b'.')
+                .then(|| self.number(5).filter(|&w| w >= 1))??;
+            let day = self.eat(b'.').then(|| self.number(6))??;
+            RuleDay::MonthWeekDay(month, week, day)
+        } else {
+            RuleDay::Julian0(self.number(365)?)
+        };
+        // Version 3 allows times from -167 to 167 hours
+        let time = if self.eat(b'/') {
+            self.offset(167)?
+        } else {
+            7200
+        };
+        Some((day, time as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CET: &str = "CET-1CEST,M3.5.0,M10.5.0/3";
+    /// 2024-03-31 01:00 and 2024-10-27 01:00 UTC, when CEST starts and ends.
+    const CEST_START: i64 = 1711846800;
+    const CEST_END: i64 = 1729990800;
+
+    fn local(year: i32, month: i32, day: i32, hour: i32, minute: i32, is_dst: bool) -> Tm {
+        Tm {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second: 0,
+            weekday: 0,
+            yearday: 0,
+            is_dst,
+            utc_offset: 0,
+            zone: String::new(),
+        }
+    }
+
+    fn header(version: u8, timecnt: u32, typecnt: u32, charcnt: u32) -> Vec<u8> {
+        let mut out = b"TZif".to_vec();
+        out.push(version);
+        out.extend([0; 15]);
+        for count in [0, 0, 0, timecnt, typecnt, charcnt] {
+            out.extend(u32::to_be_bytes(count));
+        }
+        out
+    }
+
+    /// A version 2 file with types LMT (+00:30), XST (+01:00) and XDT
+    /// (+02:00, DST), transitions to XST at 0, XDT at 1000000 and XST at
+    /// 2000000, and the given footer. The version 1 data holds only UTC.
+    fn tzif(footer: &str) -> Vec<u8> {
+        let mut out = header(b'2', 0, 1, 4);
+        out.extend([0, 0, 0, 0, 0, 0]);
+        out.extend(b"UTC\0");
+
+        out.extend(header(b'2', 3, 3, 12));
+        for time in [0i64, 1000000, 2000000] {
+            out.extend(time.to_be_bytes());
+        }
+        out.extend([1, 2, 1]);
+        for (offset, is_dst, index) in [(1800i32, 0, 0), (3600, 0, 4), (7200, 1, 8)] {
+            out.extend(offset.to_be_bytes());
+            out.extend([is_dst, index]);
+        }
+        out.extend(b"LMT\0XST\0XDT\0");
+        out.push(b'\n');
+        out.extend(footer.as_bytes());
+        out.push(b'\n');
+        out
+    }
+
+    fn abbreviation(zone: &TimeZone, t: i64) -> &str {
+        &zone.local_time_type(t).abbreviation
+    }
+
+    #[test]
+    fn posix_transitions() {
+        let zone = TimeZone::from_posix(CET).unwrap();
+        assert_eq!(abbreviation(&zone, CEST_START - 1), "CET");
+        assert_eq!(abbreviation(&zone, CEST_START), "CEST");
+        assert_eq!(abbreviation(&zone, CEST_END - 1), "CEST");
+        assert_eq!(abbreviation(&zone, CEST_END), "CET");
+
+        let tm = zone.to_local(CEST_START).unwrap();
+        assert_eq!((tm.hour, tm.is_dst, tm.utc_offset), (3, true, 7200));
+        assert_eq!(tm.zone, "CEST");
+        let tm = zone.to_local(CEST_END).unwrap();
+        assert_eq!((tm.hour, tm.is_dst, tm.utc_offset), (2, false, 3600));
+    }
+
+    #[test]
+    fn posix_southern_hemisphere() {
+        let zone = TimeZone::from_posix("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(abbreviation(&zone, 1705276800), "AEDT"); // 2024-01-15
+        assert_eq!(abbreviation(&zone, 1720083903), "AEST"); // 2024-07-04
+    }
+
+    #[test]
+    fn posix_strings() {
+        let zone = TimeZone::from_posix("<+0530>-5:30").unwrap();
+        let ty = zone.local_time_type(0);
+        assert_eq!((ty.utc_offset, ty.abbreviation.as_str()), (19800, "+0530"));
+        // Without rules, the US ones apply
+        let zone = TimeZone::from_posix("EST5EDT").unwrap();
+        assert_eq!(abbreviation(&zone, 1720083903), "EDT");
+        assert_eq!(abbreviation(&zone, 1705276800), "EST");
+        assert!(TimeZone::from_posix("CET-1CEST,M3.5.0").is_err());
+        assert!(TimeZone::from_posix("CET-1 junk").is_err());
+    }
+
+    #[test]
+    fn spring_forward_gap() {
+        let zone = TimeZone::from_posix(CET).unwrap();
+        let t = zone.from_local(&local(2024, 3, 31, 2, 30, false));
+        let tm = zone.to_local(t).unwrap();
+        assert_eq!((tm.hour, tm.minute, tm.is_dst), (3, 30, true));
+        assert_eq!(zone.from_local(&local(2024, 3, 31, 3, 0, true)), CEST_START);
+    }
+
+    #[test]
+    fn fall_back_overlap() {
+        let zone = TimeZone::from_posix(CET).unwrap();
+        assert_eq!(
+            zone.from_local(&local(2024, 10, 27, 2, 30, true)),
+            CEST_END - 1800
+        );
+        assert_eq!(
+            zone.from_local(&local(2024, 10, 27, 2, 30, false)),
+            CEST_END + 1800
+        );
+    }
+
+    #[test]
+    fn extreme_times() {
+        for zone in [CET, "EST5EDT", "UTC0"] {
+            let zone = TimeZone::from_posix(zone).unwrap();
+            assert_eq!(zone.to_local(i64::MAX), None);
+            assert_eq!(zone.to_local(i64::MIN), None);
+        }
+        let zone = TimeZone::parse(&tzif("XST-1XDT,M3.5.0,M10.5.0/3")).unwrap();
+        assert_eq!(zone.to_local(i64::MAX), None);
+    }
+
+    #[test]
+    fn tzif_v2() {
+        let zone = TimeZone::parse(&tzif("XST-1")).unwrap();
+        assert_eq!(abbreviation(&zone, -1), "LMT");
+        assert_eq!(abbreviation(&zone, 0), "XST");
+        assert_eq!(abbreviation(&zone, 1000000), "XDT");
+        assert_eq!(abbreviation(&zone, 1999999), "XDT");
+        assert_eq!(abbreviation(&zone, 2000000), "XST");
+        assert_eq!(abbreviation(&zone, 1720083903), "XST");
+
+        let tm = zone.to_local(1500000).unwrap();
+        assert_eq!((tm.day, tm.hour, tm.minute), (18, 10, 40));
+        assert_eq!((tm.utc_offset, tm.is_dst), (7200, true));
+        assert_eq!(zone.from_local(&tm), 1500000);
+    }
+
+    #[test]
+    fn tzif_footer_rule() {
+        let zone = TimeZone::parse(&tzif("XST-1XDT,M3.5.0,M10.5.0/3")).unwrap();
+        assert_eq!(abbreviation(&zone, CEST_START - 1), "XST");
+        assert_eq!(abbreviation(&zone, CEST_START), "XDT");
+        // With an empty footer, the last transition's type lasts forever
+        let zone = TimeZone::parse(&tzif("")).unwrap();
+        assert_eq!(abbreviation(&zone, CEST_START), "XST");
+    }
+
+    #[test]
+    fn tzif_malformed() {
+        let good = tzif("XST-1");
+        assert!(TimeZone::parse(&good[..good.len() - 1]).is_err());
+        assert!(TimeZone::parse(&good[..100]).is_err());
+        let mut bad_magic = good.clone();
+        bad_magic[0] = b'X';
+        assert!(TimeZone::parse(&bad_magic).is_err());
+        let mut bad_index = good.clone();
+        // The first transition's type index, after both headers, the
+        // version 1 data and three 64-bit times
+        bad_index[44 + 10 + 44 + 24] = 3;
+        assert!(TimeZone::parse(&bad_index).is_err());
+        assert!(TimeZone::parse(&tzif("XST")).is_err());
+    }
+
+    #[test]
+    fn tzif_v1() {
+        let mut data = header(0, 1, 2, 8);
+        data.extend(0i32.to_be_bytes());
+        data.push(1);
+        for (offset, index) in [(0i32, 0), (-18000, 4)] {
+            data.extend(offset.to_be_bytes());
+            data.extend([0, index]);
+        }
+        data.extend(b"LMT\0EST\0");
+        let zone = TimeZone::parse(&data).unwrap();
+        assert_eq!(abbreviation(&zone, -1), "LMT");
+        assert_eq!(zone.to_local(0).unwrap().hour, 19);
+    }
+}
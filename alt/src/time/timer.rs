@@ -0,0 +1,366 @@
+use super::{Clock, Timespec};
+use libc::c_int;
+use std::collections::BTreeMap;
+use std::io;
+use std::ops::BitOr;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The setting of a timer, as in `struct itimerspec`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TimerSpec {
+    /// The time until the next expiration, or with `TimerFlags::ABSTIME`
+    /// the clock time of it. Zero disarms the timer.
+    pub value: Timespec,
+    /// The period of later expirations, or zero for a one-shot timer.
+    pub interval: Timespec,
+}
+
+impl TimerSpec {
+    pub fn oneshot(after: Duration) -> TimerSpec {
+        TimerSpec {
+            value: saturating(after),
+            interval: Timespec::ZERO,
+        }
+    }
+
+    pub fn periodic(initial: Duration, interval: Duration) -> TimerSpec {
+        TimerSpec {
+            value: saturating(initial),
+            interval: saturating(interval),
+        }
+    }
+
+    pub fn is_armed(&self) -> bool {
+        self.value != Timespec::ZERO
+    }
+
+    pub(super) fn from_raw(spec: libc::itimerspec) -> TimerSpec {
+        TimerSpec {
+            value: Timespec::from_raw(spec.it_value),
+            interval: Timespec::from_raw(spec.it_interval),
+        }
+    }
+
+    pub(super) fn as_raw(&self) -> libc::itimerspec {
+        libc::itimerspec {
+            it_interval: self.interval.as_raw(),
+            it_value: self.value.as_raw(),
+        }
+    }
+}
+
+fn saturating(duration: Duration) -> Timespec {
+    Timespec::from_duration(duration).unwrap_or(Timespec::MAX)
+}
+
+/// Flags for `Timer::set` and `TimerFd::set`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TimerFlags(c_int);
+
+impl TimerFlags {
+    /// `TimerSpec::value` is a time on the timer's clock rather than an
+    /// interval from now.
+    pub const ABSTIME: TimerFlags = TimerFlags(libc::TIMER_ABSTIME);
+    /// For a `TimerFd` on `Clock::REALTIME` with `ABSTIME`, make reads fail
+    /// with `ECANCELED` if the clock is set discontinuously.
+    pub const CANCEL_ON_SET: TimerFlags = TimerFlags(libc::TFD_TIMER_CANCEL_ON_SET);
+
+    pub const fn empty() -> TimerFlags {
+        TimerFlags(0)
+    }
+
+    pub const fn bits(self) -> c_int {
+        self.0
+    }
+
+    pub const fn contains(self, other: TimerFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for TimerFlags {
+    type Output = TimerFlags;
+
+    fn bitor(self, rhs: TimerFlags) -> TimerFlags {
+        TimerFlags(self.0 | rhs.0)
+    }
+}
+
+/// How a `Timer` reports its expirations.
+pub enum Notify {
+    /// Not at all; the timer can only be polled with `Timer::get`.
+    None,
+    /// Queue signal `signo`, whose siginfo carries `value` in `si_value`
+    /// and the overrun count in `si_overrun`. With a realtime signal, only
+    /// one signal is pending per timer however often it expires.
+    Signal { signo: i32, value: usize },
+    /// Call the function in a new thread on each expiration. A panic in it
+    /// aborts the process.
+    Thread(Box<dyn Fn() + Send + Sync>),
+}
+
+type Callback = Arc<dyn Fn() + Send + Sync>;
+
+/// `Notify::Thread` callbacks, keyed by the value passed to the
+/// notification thread. An expiration that is delivered after its timer
+/// was deleted then finds nothing to call, rather than a freed closure.
+static CALLBACKS: Mutex<BTreeMap<usize, Callback>> = Mutex::new(BTreeMap::new());
+static NEXT_CALLBACK: AtomicUsize = AtomicUsize::new(0);
+
+fn callbacks() -> std::sync::MutexGuard<'static, BTreeMap<usize, Callback>> {
+    CALLBACKS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+extern "C" fn run_callback(value: libc::sigval) {
+    let key = unsafe { value.sival_ptr } as usize;
+    let callback = callbacks().get(&key).cloned();
+    if let Some(callback) = callback {
+        callback();
+    }
+}
+
+/// A POSIX interval timer (`timer_create()`), deleted on drop.
+pub struct Timer {
+    id: libc::timer_t,
+    callback: Option<usize>,
+}
+
+// The timer ID is only a handle for the kernel (or glibc's notification
+// thread), never dereferenced here.
+unsafe impl Send for Timer {}
+unsafe impl Sync for Timer {}
+
+impl Timer {
+    pub fn new(clock: Clock, notify: Notify) -> io::Result<Timer> {
+        let mut sev: libc::sigevent = unsafe { std::mem::zeroed() };
+        let mut callback = None;
+        match notify {
+            Notify::None => sev.sigev_notify = libc::SIGEV_NONE,
+            Notify::Signal { signo, value } => {
+                sev.sigev_notify = libc::SIGEV_SIGNAL;
+                sev.sigev_signo = signo;
+                sev.sigev_value.sival_ptr = value as *mut libc::c_void;
+            }
+            Notify::Thread(f) => {
+                let key = NEXT_CALLBACK.fetch_add(1, Ordering::Relaxed);
+                callbacks().insert(key, Arc::from(f));
+                callback = Some(key);
+                sev.sigev_notify = libc::SIGEV_THREAD;
+                sev.sigev_notify_function = Some(run_callback);
+                sev.sigev_value.sival_ptr = key as *mut libc::c_void;
+            }
+        }
+
+        let mut id = ptr::null_mut();
+        let result = unsafe { libc::timer_create(clock.as_raw(), &mut sev, &mut id) };
+        if result == -1 {
+            let err = io::Error::last_os_error();
+            if let Some(key) = callback {
+                callbacks().remove(&key);
+            }
+            Err(err)
+        } else {
+            Ok(Timer { id, callback })
+        }
+    }
+
+    /// Arm (or with a zero `value`, disarm) the timer, returning its
+    /// previous setting.
+    pub fn set(&self, spec: &TimerSpec, flags: TimerFlags) -> io::Result<TimerSpec> {
+        let mut old = TimerSpec::default().as_raw();
+        let result = unsafe { libc::timer_settime(self.id, flags.0, &spec.as_raw(), &mut old) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(TimerSpec::from_raw(old))
+        }
+    }
+
+    /// Return the time until the next expiration, which is relative even
+    /// if the timer was set with `TimerFlags::ABSTIME`, and the interval.
+    pub fn get(&self) -> io::Result<TimerSpec> {
+        let mut spec = TimerSpec::default().as_raw();
+        let result = unsafe { libc::timer_gettime(self.id, &mut spec) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(TimerSpec::from_raw(spec))
+        }
+    }
+
+    /// The number of extra expirations that occurred before the last
+    /// notification was accepted.
+    pub fn overrun(&self) -> io::Result<u32> {
+        let result = unsafe { libc::timer_getoverrun(self.id) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(result as u32)
+        }
+    }
+
+    pub fn as_raw(&self) -> libc::timer_t {
+        self.id
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        unsafe { libc::timer_delete(self.id) };
+        if let Some(key) = self.callback {
+            callbacks().remove(&key);
+        }
+    }
+}
+
+/// The classic per-process timers of `setitimer()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntervalTimer {
+    /// Counts real time and sends `SIGALRM`.
+    Real,
+    /// Counts user CPU time and sends `SIGVTALRM`.
+    Virtual,
+    /// Counts user and system CPU time and sends `SIGPROF`.
+    Prof,
+}
+
+impl IntervalTimer {
+    fn as_raw(self) -> c_int {
+        match self {
+            IntervalTimer::Real => libc::ITIMER_REAL,
+            IntervalTimer::Virtual => libc::ITIMER_VIRTUAL,
+            IntervalTimer::Prof => libc::ITIMER_PROF,
+        }
+    }
+}
+
+fn timeval(ts: Timespec) -> libc::timeval {
+    libc::timeval {
+        tv_sec: ts.sec(),
+        tv_usec: (ts.subsec_nanos() / 1000) as libc::suseconds_t,
+    }
+}
+
+fn from_timeval(tv: libc::timeval) -> Timespec {
+    Timespec::new(tv.tv_sec, tv.tv_usec as u32 * 1000)
+}
+
+/// Set one of the `setitimer()` timers, which have microsecond resolution,
+/// returning its previous setting.
+pub fn setitimer(which: IntervalTimer, spec: &TimerSpec) -> io::Result<TimerSpec> {
+    let new = libc::itimerval {
+        it_interval: timeval(spec.interval),
+        it_value: timeval(spec.value),
+    };
+    let mut old = new;
+    let result = unsafe { libc::setitimer(which.as_raw(), &new, &mut old) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(TimerSpec {
+            value: from_timeval(old.it_value),
+            interval: from_timeval(old.it_interval),
+        })
+    }
+}
+
+pub fn getitimer(which: IntervalTimer) -> io::Result<TimerSpec> {
+    let zero = timeval(Timespec::ZERO);
+    let mut curr = libc::itimerval {
+        it_interval: zero,
+        it_value: zero,
+    };
+    let result = unsafe { libc::getitimer(which.as_raw(), &mut curr) };
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(TimerSpec {
+            value: from_timeval(curr.it_value),
+            interval: from_timeval(curr.it_interval),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::{self, Fork};
+    use std::sync::mpsc;
+
+    fn secs(sec: u64) -> Duration {
+        Duration::from_secs(sec)
+    }
+
+    #[test]
+    fn set_returns_previous() {
+        let timer = Timer::new(Clock::MONOTONIC, Notify::None).unwrap();
+        assert!(!timer.get().unwrap().is_armed());
+
+        let first = TimerSpec::periodic(secs(100), secs(5));
+        assert_eq!(
+            timer.set(&first, TimerFlags::empty()).unwrap(),
+            TimerSpec::default()
+        );
+        let old = timer
+            .set(&TimerSpec::default(), TimerFlags::empty())
+            .unwrap();
+        assert!(
+            old.value > Timespec::new(99, 0) && old.value <= first.value,
+            "{:?}",
+            old
+        );
+        assert_eq!(old.interval, first.interval);
+    }
+
+    #[test]
+    fn thread_callback() {
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+        let notify = Notify::Thread(Box::new(move || {
+            let _ = tx.lock().unwrap().send(());
+        }));
+        let timer = Timer::new(Clock::MONOTONIC, notify).unwrap();
+        let key = timer.callback.unwrap();
+        assert!(callbacks().contains_key(&key));
+
+        timer
+            .set(
+                &TimerSpec::oneshot(Duration::from_millis(1)),
+                TimerFlags::empty(),
+            )
+            .unwrap();
+        rx.recv_timeout(secs(10)).unwrap();
+        drop(timer);
+        assert!(!callbacks().contains_key(&key));
+    }
+
+    #[test]
+    fn setitimer_round_trip() {
+        // The setitimer() timers belong to the process, so use a child
+        // rather than disturb other tests
+        match unsafe { process::fork() }.unwrap() {
+            Fork::Child => {
+                let spec = TimerSpec::periodic(secs(100), Duration::from_micros(2500));
+                let ok = getitimer(IntervalTimer::Real).is_ok_and(|old| !old.is_armed())
+                    && setitimer(IntervalTimer::Real, &spec).is_ok_and(|old| !old.is_armed())
+                    && getitimer(IntervalTimer::Real).is_ok_and(|curr| {
+                        curr.value > Timespec::new(99, 0)
+                            && curr.value <= spec.value
+                            && curr.interval == spec.interval
+                    })
+                    && setitimer(IntervalTimer::Real, &TimerSpec::default())
+                        .is_ok_and(|old| old.interval == spec.interval)
+                    && getitimer(IntervalTimer::Real).is_ok_and(|curr| !curr.is_armed());
+                unsafe { libc::_exit(if ok { 0 } else { 1 }) }
+            }
+            Fork::Parent(pid) => {
+                let mut status = 0;
+                assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+                assert_eq!(status, 0);
+            }
+        }
+    }
+}
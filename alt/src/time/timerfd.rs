@@ -0,0 +1,116 @@
+use super::{Clock, TimerFlags, TimerSpec};
+use crate::os::fd::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use crate::sys::fd::FileDesc;
+use std::io;
+
+/// A timer whose expirations are read from a file descriptor, so that it
+/// can be waited for with `poll()` alongside other I/O.
+pub struct TimerFd(FileDesc);
+
+impl TimerFd {
+    /// Create a disarmed timer on `clock`, which must be `REALTIME`,
+    /// `MONOTONIC` or `BOOTTIME`.
+    pub fn new(clock: Clock) -> io::Result<TimerFd> {
+        let fd = unsafe { libc::timerfd_create(clock.as_raw(), libc::TFD_CLOEXEC) };
+        if fd == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(TimerFd(unsafe { FileDesc::from_raw_fd(fd) }))
+        }
+    }
+
+    /// Arm (or with a zero `value`, disarm) the timer, returning its
+    /// previous setting. This also resets the count of expirations.
+    pub fn set(&self, spec: &TimerSpec, flags: TimerFlags) -> io::Result<TimerSpec> {
+        let mut old = TimerSpec::default().as_raw();
+        let result = unsafe {
+            libc::timerfd_settime(self.as_raw_fd(), flags.bits(), &spec.as_raw(), &mut old)
+        };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(TimerSpec::from_raw(old))
+        }
+    }
+
+    pub fn get(&self) -> io::Result<TimerSpec> {
+        let mut spec = TimerSpec::default().as_raw();
+        let result = unsafe { libc::timerfd_gettime(self.as_raw_fd(), &mut spec) };
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(TimerSpec::from_raw(spec))
+        }
+    }
+
+    /// Wait until the timer has expired, and return the number of
+    /// expirations since it was set or last read.
+    pub fn read(&self) -> io::Result<u64> {
+        let mut buf = [0; 8];
+        let n = self.0.read(&mut buf)?;
+        if n != buf.len() {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
+        Ok(u64::from_ne_bytes(buf))
+    }
+}
+
+impl AsRawFd for TimerFd {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl FromRawFd for TimerFd {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        TimerFd(FromRawFd::from_raw_fd(fd))
+    }
+}
+
+impl IntoRawFd for TimerFd {
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Timespec;
+    use std::time::Duration;
+
+    #[test]
+    fn oneshot() {
+        let timer = TimerFd::new(Clock::MONOTONIC).unwrap();
+        let spec = TimerSpec::oneshot(Duration::from_millis(1));
+        timer.set(&spec, TimerFlags::empty()).unwrap();
+        assert!(timer.read().unwrap() >= 1);
+        assert_eq!(timer.get().unwrap(), TimerSpec::default());
+    }
+
+    #[test]
+    fn set_returns_previous() {
+        let timer = TimerFd::new(Clock::MONOTONIC).unwrap();
+        let first = TimerSpec::periodic(Duration::from_secs(100), Duration::from_secs(5));
+        assert_eq!(
+            timer.set(&first, TimerFlags::empty()).unwrap(),
+            TimerSpec::default()
+        );
+
+        let second = TimerSpec::oneshot(Duration::from_secs(200));
+        let old = timer.set(&second, TimerFlags::empty()).unwrap();
+        assert!(
+            old.value > Timespec::new(99, 0) && old.value <= first.value,
+            "{:?}",
+            old
+        );
+        assert_eq!(old.interval, first.interval);
+
+        let old = timer
+            .set(&TimerSpec::default(), TimerFlags::empty())
+            .unwrap();
+        assert!(old.value > Timespec::new(199, 0), "{:?}", old);
+        assert_eq!(old.interval, Timespec::ZERO);
+    }
+}
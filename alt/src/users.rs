@@ -0,0 +1,251 @@
+mod files;
+
+use libc::{c_char, c_int, size_t};
+use std::ffi::{CStr, CString};
+use std::io;
+use std::mem;
+use std::path::PathBuf;
+use std::ptr;
+use std::sync::{Mutex, MutexGuard};
+
+pub use files::*;
+
+/// An entry of the password database (`struct passwd`).
+///
+/// Strings that are not valid UTF-8 are converted lossily.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Passwd {
+    pub name: String,
+    /// Usually "x", the real password hash being kept in the shadow
+    /// database.
+    pub passwd: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub gecos: String,
+    pub dir: PathBuf,
+    pub shell: PathBuf,
+}
+
+impl Passwd {
+    unsafe fn from_raw(pw: &libc::passwd) -> Passwd {
+        Passwd {
+            name: string(pw.pw_name),
+            passwd: string(pw.pw_passwd),
+            uid: pw.pw_uid,
+            gid: pw.pw_gid,
+            gecos: string(pw.pw_gecos),
+            dir: PathBuf::from(string(pw.pw_dir)),
+            shell: PathBuf::from(string(pw.pw_shell)),
+        }
+    }
+}
+
+/// An entry of the group database (`struct group`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Group {
+    pub name: String,
+    pub passwd: String,
+    pub gid: u32,
+    /// The users listed as supplementary members. Users whose primary
+    /// group this is are usually not listed.
+    pub members: Vec<String>,
+}
+
+impl Group {
+    unsafe fn from_raw(gr: &libc::group) -> Group {
+        let mut members = Vec::new();
+        let mut mem = gr.gr_mem;
+        while !mem.is_null() && !(*mem).is_null() {
+            members.push(string(*mem));
+            mem = mem.add(1);
+        }
+        Group {
+            name: string(gr.gr_name),
+            passwd: string(gr.gr_passwd),
+            gid: gr.gr_gid,
+            members,
+        }
+    }
+}
+
+unsafe fn string(s: *const c_char) -> String {
+    if s.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(s).to_string_lossy().into_owned()
+    }
+}
+
+fn to_c_string(s: &str) -> io::Result<CString> {
+    CString::new(s).map_err(|_| io::Error::from_raw_os_error(libc::EINVAL))
+}
+
+const INITIAL_BUF_SIZE: usize = 1024;
+
+/// Call one of the reentrant `get*_r()` functions, growing the buffer for
+/// the strings the entry points into until it is large enough, and convert
+/// the entry with `owned` while that buffer is still alive.
+///
+/// Returns `Ok(None)` if there is no matching entry; glibc reports that
+/// with a null result and a return of 0, but some NSS modules return one
+/// of the errors below instead.
+fn get_r<T, R, F>(mut f: F, owned: unsafe fn(&T) -> R) -> io::Result<Option<R>>
+where
+    F: FnMut(&mut T, &mut [c_char], &mut *mut T) -> c_int,
+{
+    let mut buf = vec![0 as c_char; INITIAL_BUF_SIZE];
+    loop {
+        let mut entry: T = unsafe { mem::zeroed() };
+        let mut result = ptr::null_mut();
+        match f(&mut entry, &mut buf, &mut result) {
+            0 if result.is_null() => return Ok(None),
+            0 => return Ok(Some(unsafe { owned(&entry) })),
+            libc::ERANGE => {
+                let len = buf.len() * 2;
+                buf.resize(len, 0);
+            }
+            libc::ENOENT | libc::ESRCH | libc::EBADF | libc::EPERM => return Ok(None),
+            err => return Err(io::Error::from_raw_os_error(err)),
+        }
+    }
+}
+
+pub fn get_user_by_name(name: &str) -> io::Result<Option<Passwd>> {
+    let name = to_c_string(name)?;
+    get_r(
+        |pw, buf, result| unsafe {
+            libc::getpwnam_r(
+                name.as_ptr(),
+                pw,
+                buf.as_mut_ptr(),
+                buf.len() as size_t,
+                result,
+            )
+        },
+        Passwd::from_raw,
+    )
+}
+
+pub fn get_user_by_uid(uid: u32) -> io::Result<Option<Passwd>> {
+    get_r(
+        |pw, buf, result| unsafe {
+            libc::getpwuid_r(uid, pw, buf.as_mut_ptr(), buf.len() as size_t, result)
+        },
+        Passwd::from_raw,
+    )
+}
+
+pub fn get_group_by_name(name: &str) -> io::Result<Option<Group>> {
+    let name = to_c_string(name)?;
+    get_r(
+        |gr, buf, result| unsafe {
+            libc::getgrnam_r(
+                name.as_ptr(),
+                gr,
+                buf.as_mut_ptr(),
+                buf.len() as size_t,
+                result,
+            )
+        },
+        Group::from_raw,
+    )
+}
+
+pub fn get_group_by_gid(gid: u32) -> io::Result<Option<Group>> {
+    get_r(
+        |gr, buf, result| unsafe {
+            libc::getgrgid_r(gid, gr, buf.as_mut_ptr(), buf.len() as size_t, result)
+        },
+        Group::from_raw,
+    )
+}
+
+/// The `getpwent()` and `getgrent()` cursors are process-wide, so only
+/// one iteration over each database may be in progress at a time.
+static PWENT_LOCK: Mutex<()> = Mutex::new(());
+static GRENT_LOCK: Mutex<()> = Mutex::new(());
+
+/// An iteration over the password database, from `users()`.
+pub struct Users {
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Iterator for Users {
+    type Item = io::Result<Passwd>;
+
+    fn next(&mut self) -> Option<io::Result<Passwd>> {
+        get_r(
+            |pw, buf, result| unsafe {
+                libc::getpwent_r(pw, buf.as_mut_ptr(), buf.len() as size_t, result)
+            },
+            Passwd::from_raw,
+        )
+        .transpose()
+    }
+}
+
+impl Drop for Users {
+    fn drop(&mut self) {
+        unsafe { libc::endpwent() }
+    }
+}
+
+/// Iterate over every entry of the password database, as with
+/// `getpwent()`. Other iterations over it block until this one is
+/// dropped.
+pub fn users() -> Users {
+    let guard = PWENT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    unsafe { libc::setpwent() };
+    Users { _guard: guard }
+}
+
+/// An iteration over the group database, from `groups()`.
+pub struct Groups {
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Iterator for Groups {
+    type Item = io::Result<Group>;
+
+    fn next(&mut self) -> Option<io::Result<Group>> {
+        get_r(
+            |gr, buf, result| unsafe {
+                libc::getgrent_r(gr, buf.as_mut_ptr(), buf.len() as size_t, result)
+            },
+            Group::from_raw,
+        )
+        .transpose()
+    }
+}
+
+impl Drop for Groups {
+    fn drop(&mut self) {
+        unsafe { libc::endgrent() }
+    }
+}
+
+pub fn groups() -> Groups {
+    let guard = GRENT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    unsafe { libc::setgrent() };
+    Groups { _guard: guard }
+}
+
+/// Return the groups `user` is a member of according to the group
+/// database, together with `group` (normally the user's primary group from
+/// the password database), as `initgroups()` would set them.
+pub fn group_list(user: &str, group: u32) -> io::Result<Vec<u32>> {
+    let user = to_c_string(user)?;
+    let mut groups = vec![0; 32];
+    loop {
+        let mut ngroups = groups.len() as c_int;
+        let result =
+            unsafe { libc::getgrouplist(user.as_ptr(), group, groups.as_mut_ptr(), &mut ngroups) };
+        // On failure, ngroups is set to the number of groups there are
+        if result != -1 {
+            groups.truncate(ngroups as usize);
+            return Ok(groups);
+        }
+        let len = (ngroups as usize).max(groups.len() * 2);
+        groups.resize(len, 0);
+    }
+}
@@ -0,0 +1,248 @@
+use super::{Group, Passwd};
+use crate::fs::OpenOptions;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+impl Passwd {
+    /// Parse a line of /etc/passwd:
+    /// `name:passwd:uid:gid:gecos:dir:shell`.
+    pub fn parse_line(line: &str) -> Option<Passwd> {
+        let fields: Vec<&str> = line.split(':').collect();
+        if fields.len() != 7 {
+            return None;
+        }
+        Some(Passwd {
+            name: fields[0].to_string(),
+            passwd: fields[1].to_string(),
+            uid: fields[2].parse().ok()?,
+            gid: fields[3].parse().ok()?,
+            gecos: fields[4].to_string(),
+            dir: PathBuf::from(fields[5]),
+            shell: PathBuf::from(fields[6]),
+        })
+    }
+}
+
+impl Group {
+    /// Parse a line of /etc/group: `name:passwd:gid:member,member,...`.
+    pub fn parse_line(line: &str) -> Option<Group> {
+        let fields: Vec<&str> = line.split(':').collect();
+        if fields.len() != 4 {
+            return None;
+        }
+        let members = fields[3]
+            .split(',')
+            .filter(|member| !member.is_empty())
+            .map(String::from)
+            .collect();
+        Some(Group {
+            name: fields[0].to_string(),
+            passwd: fields[1].to_string(),
+            gid: fields[2].parse().ok()?,
+            members,
+        })
+    }
+}
+
+/// Parse every entry of a file in /etc/passwd or /etc/group format.
+/// Blank lines, comments, NIS "+"/"-" entries and malformed lines are
+/// skipped, as glibc does.
+fn parse_all<T>(text: &str, parse: fn(&str) -> Option<T>) -> Vec<T> {
+    text.lines()
+        .filter(|line| !matches!(line.chars().next(), None | Some('#' | '+' | '-')))
+        .filter_map(parse)
+        .collect()
+}
+
+/// Direct access to the /etc/passwd and /etc/group files beneath a chosen
+/// root directory, bypassing NSS.
+///
+/// Unlike `get_user_by_name` and friends, this does not see users from
+/// other sources such as LDAP or systemd, but it can read the databases of
+/// a container or chroot image, or test fixtures.
+#[derive(Clone, Debug)]
+pub struct FileDb {
+    root: PathBuf,
+}
+
+impl FileDb {
+    pub fn new() -> FileDb {
+        FileDb::with_root("/")
+    }
+
+    pub fn with_root<P: AsRef<Path>>(root: P) -> FileDb {
+        FileDb {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn read(&self, name: &str) -> io::Result<String> {
+        let mut buf = Vec::new();
+        OpenOptions::new()
+            .read(true)
+            .open(self.root.join("etc").join(name))?
+            .read_to_end(&mut buf)?;
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    pub fn users(&self) -> io::Result<Vec<Passwd>> {
+        Ok(parse_all(&self.read("passwd")?, Passwd::parse_line))
+    }
+
+    pub fn groups(&self) -> io::Result<Vec<Group>> {
+        Ok(parse_all(&self.read("group")?, Group::parse_line))
+    }
+
+    pub fn user_by_name(&self, name: &str) -> io::Result<Option<Passwd>> {
+        Ok(self.users()?.into_iter().find(|pw| pw.name == name))
+    }
+
+    pub fn user_by_uid(&self, uid: u32) -> io::Result<Option<Passwd>> {
+        Ok(self.users()?.into_iter().find(|pw| pw.uid == uid))
+    }
+
+    pub fn group_by_name(&self, name: &str) -> io::Result<Option<Group>> {
+        Ok(self.groups()?.into_iter().find(|gr| gr.name == name))
+    }
+
+    pub fn group_by_gid(&self, gid: u32) -> io::Result<Option<Group>> {
+        Ok(self.groups()?.into_iter().find(|gr| gr.gid == gid))
+    }
+
+    /// Like `alt::users::group_list`: `group` followed by the groups
+    /// listing `user` as a member.
+    pub fn group_list(&self, user: &str, group: u32) -> io::Result<Vec<u32>> {
+        let mut gids = vec![group];
+        for gr in self.groups()? {
+            if gr.members.iter().any(|member| member == user) && !gids.contains(&gr.gid) {
+                gids.push(gr.gid);
+            }
+        }
+        Ok(gids)
+    }
+}
+
+impl Default for FileDb {
+    fn default() -> Self {
+        FileDb::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::{Dir, TempDir};
+    use std::io::Write;
+
+    const PASSWD: &str = "\
+root:x:0:0:root:/root:/bin/bash
+# a comment
+
++nisuser::::::
+alice:x:1000:1000:Alice,,,:/home/alice:/bin/sh
+broken:x:1001:1001:/home/broken:/bin/sh
+baduid:x:uid:1002::/home/baduid:/bin/sh
+bob:x:1003:100::/home/bob:
+";
+
+    const GROUP: &str = "\
+root:x:0:
+users:x:100:alice,bob
+staff:x:50:bob,,alice
+wheel:x:10:alice
+empty:x:200:
+-nisgroup:::
+badgid:x:gid:alice
+toofew:x:300
+users:x:100:alice
+";
+
+    fn fixture() -> (TempDir, FileDb) {
+        let root = TempDir::new().unwrap();
+        Dir::open(root.path())
+            .unwrap()
+            .mkdir_at("etc", 0o755)
+            .unwrap();
+        for (name, text) in [("passwd", PASSWD), ("group", GROUP)] {
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .open(root.path().join("etc").join(name))
+                .unwrap()
+                .write_all(text.as_bytes())
+                .unwrap();
+        }
+        let db = FileDb::with_root(root.path());
+        (root, db)
+    }
+
+    #[test]
+    fn users() {
+        let (_root, db) = fixture();
+        let names: Vec<String> = db.users().unwrap().into_iter().map(|pw| pw.name).collect();
+        assert_eq!(names, ["root", "alice", "bob"]);
+
+        let alice = db.user_by_name("alice").unwrap().unwrap();
+        assert_eq!((alice.uid, alice.gid), (1000, 1000));
+        assert_eq!(alice.gecos, "Alice,,,");
+        assert_eq!(alice.dir, Path::new("/home/alice"));
+        let bob = db.user_by_uid(1003).unwrap().unwrap();
+        assert_eq!(bob.shell, Path::new(""));
+        assert_eq!(db.user_by_name("broken").unwrap(), None);
+        assert_eq!(db.user_by_uid(1002).unwrap(), None);
+    }
+
+    #[test]
+    fn groups() {
+        let (_root, db) = fixture();
+        let names: Vec<String> = db.groups().unwrap().into_iter().map(|gr| gr.name).collect();
+        assert_eq!(names, ["root", "users", "staff", "wheel", "empty", "users"]);
+
+        assert!(db.group_by_gid(0).unwrap().unwrap().members.is_empty());
+        assert!(db
+            .group_by_name("empty")
+            .unwrap()
+            .unwrap()
+            .members
+            .is_empty());
+        let staff = db.group_by_gid(50).unwrap().unwrap();
+        assert_eq!(staff.members, ["bob", "alice"]);
+        assert_eq!(
+            db.group_by_name("users").unwrap().unwrap().members,
+            ["alice", "bob"]
+        );
+        assert_eq!(db.group_by_name("toofew").unwrap(), None);
+        assert_eq!(db.group_by_name("nisgroup").unwrap(), None);
+    }
+
+    #[test]
+    fn group_list() {
+        let (_root, db) = fixture();
+        // users (100) is listed twice and is also bob's primary group
+        assert_eq!(db.group_list("alice", 1000).unwrap(), [1000, 100, 50, 10]);
+        assert_eq!(db.group_list("bob", 100).unwrap(), [100, 50]);
+        assert_eq!(db.group_list("nobody", 65534).unwrap(), [65534]);
+    }
+
+    #[test]
+    fn missing_files() {
+        let root = TempDir::new().unwrap();
+        let db = FileDb::with_root(root.path());
+        assert_eq!(db.users().unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(
+            db.group_list("alice", 1000).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn parse_lines() {
+        assert!(Passwd::parse_line("a:x:1:2:g:/d:/s:extra").is_none());
+        assert!(Passwd::parse_line("a:x:-1:2:g:/d:/s").is_none());
+        let gr = Group::parse_line("g:x:5:").unwrap();
+        assert!(gr.members.is_empty());
+        let gr = Group::parse_line("g::5:,a,").unwrap();
+        assert_eq!(gr.members, ["a"]);
+        assert!(Group::parse_line("g:x:5:a:b").is_none());
+    }
+}
@@ -0,0 +1,27 @@
+use alt::caps::Capabilities;
+use alt::fs::OpenOptions;
+use alt::io;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    let mut out = io::stdout();
+
+    let caps = Capabilities::current().context("cap_get_proc")?;
+    writeln!(out, "Capabilities: {}", caps)?;
+
+    /* If a file was named, try opening it; with CAP_DAC_READ_SEARCH
+     * (e.g. "setcap cap_dac_read_search=pe" on this program), this
+     * succeeds regardless of the file's permissions */
+
+    if let Some(path) = argv.get(1) {
+        match OpenOptions::new().read(true).open(path) {
+            Ok(_) => writeln!(out, "Successfully opened {}", path)?,
+            Err(err) => writeln!(out, "Open failed: {}", err)?,
+        }
+    }
+
+    Ok(())
+}
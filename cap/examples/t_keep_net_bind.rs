@@ -0,0 +1,51 @@
+use alt::caps::{self, Capabilities, Capability};
+use alt::io;
+use alt::net::TcpListener;
+use alt::process::credentials::Credentials;
+use alt::users;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use tlpi::{fatal, usage_err};
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+
+    if argv.len() < 2 || argv[1] == "--help" {
+        usage_err!("{} user [port]\n", argv[0]);
+    }
+    let port: u16 = match argv.get(2) {
+        Some(port) => port.parse().context("port")?,
+        None => 80,
+    };
+
+    let pw = match users::get_user_by_name(&argv[1]).context("getpwnam")? {
+        Some(pw) => pw,
+        None => {
+            fatal!("Unknown user: {}", argv[1]);
+            return Ok(());
+        }
+    };
+
+    /* Run as the given user, but keep the one capability needed to bind
+     * a port below 1024, instead of all of root's privileges */
+
+    caps::keep_only(Capability::NetBindService.into(), pw.uid, pw.gid).context("keep_only")?;
+
+    let mut out = io::stdout();
+    writeln!(out, "{}", Credentials::current().context("credentials")?)?;
+    writeln!(
+        out,
+        "Capabilities: {}",
+        Capabilities::current().context("cap_get_proc")?
+    )?;
+
+    let listener = TcpListener::bind(("0.0.0.0", port)).context("bind")?;
+    writeln!(
+        out,
+        "Bound to {}",
+        listener.local_addr().context("getsockname")?
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use alt::caps;
+use alt::io;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use tlpi::usage_err;
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+
+    if argv.len() != 2 || argv[1] == "--help" {
+        usage_err!("{} <file>\n", argv[0]);
+    }
+
+    /* Fetch and decode the "security.capability" extended attribute */
+
+    let mut out = io::stdout();
+    let fcaps = match caps::file_caps(&argv[1]).context("getxattr")? {
+        Some(fcaps) => fcaps,
+        None => {
+            writeln!(out, "{} has no file capabilities", argv[1])?;
+            return Ok(());
+        }
+    };
+
+    writeln!(out, "Version:     {}", fcaps.revision)?;
+    writeln!(
+        out,
+        "Effective:   {}",
+        if fcaps.effective { "set" } else { "not set" }
+    )?;
+    writeln!(
+        out,
+        "Permitted:   {:#018x} ({})",
+        fcaps.permitted.bits(),
+        fcaps.permitted
+    )?;
+    writeln!(
+        out,
+        "Inheritable: {:#018x} ({})",
+        fcaps.inheritable.bits(),
+        fcaps.inheritable
+    )?;
+    if let Some(root_id) = fcaps.root_id {
+        writeln!(out, "Root ID:     {}", root_id)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use alt::fs::{self, OpenOptions};
+use alt::io;
+use anyhow::{Context, Result};
+use std::env;
+use std::ffi::CString;
+use std::io::Write;
+use std::path::Path;
+use tlpi::{fatal, get_int, usage_err, GN_GT_0};
+
+const BUF_SIZE: usize = 1024;
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() < 2 || argv[1] == "--help" {
+        usage_err!("{} temp-file [num-1kB-blocks] \n", argv[0]);
+    }
+
+    let num_blocks = match argv.get(2) {
+        Some(arg) => get_int(arg, GN_GT_0, "num-1kB-blocks")?,
+        None => 100000,
+    };
+
+    /* create_new() so that we ensure we create a new file */
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&argv[1])
+        .context("open")?;
+
+    fs::remove_file(&argv[1]).context("unlink")?;
+
+    let buf = [0; BUF_SIZE];
+    for _ in 0..num_blocks {
+        match file.write(&buf) {
+            Ok(BUF_SIZE) => {}
+            _ => fatal!("Partial/failed write"),
+        }
+    }
+
+    let dir = match Path::new(&argv[1]).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let shell_cmd = CString::new(format!("df -k {}", dir.display()))?;
+    system(&shell_cmd)?;
+
+    file.close().context("close")?;
+    writeln!(io::stdout(), "********** Closed file descriptor")?;
+
+    /* Depending on factors such as scheduler decisions and the size of the
+     * file, the blocks of the closed file may not yet have been freed by
+     * the time 'df' runs again; a short sleep here would make sure. */
+
+    system(&shell_cmd)?;
+
+    Ok(())
+}
+
+fn system(cmd: &CString) -> Result<()> {
+    io::stdout().flush()?; /* Keep our output ordered with the shell's */
+    unsafe {
+        libc::system(cmd.as_ptr());
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use alt::fs;
+use alt::io;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use tlpi::{fatal, usage_err};
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() != 2 || argv[1] == "--help" {
+        usage_err!("{} pathname\n", argv[0]);
+    }
+
+    /* Use symlink_metadata() to check whether the supplied pathname is a
+     * symbolic link. Alternatively, we could have checked to whether
+     * read_link() failed with EINVAL. */
+
+    let metadata = fs::symlink_metadata(&argv[1]).context("lstat")?;
+    if !metadata.is_symlink() {
+        fatal!("{} is not a symbolic link", argv[1]);
+    }
+
+    let mut out = io::stdout();
+    let target = fs::read_link(&argv[1]).context("readlink")?;
+    writeln!(out, "readlink: {} --> {}", argv[1], target.display())?;
+
+    let resolved = fs::canonicalize(&argv[1]).context("realpath")?;
+    writeln!(out, "realpath: {} --> {}", argv[1], resolved.display())?;
+
+    Ok(())
+}
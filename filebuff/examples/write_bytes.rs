@@ -0,0 +1,45 @@
+use alt::fs::OpenOptions;
+use anyhow::{Context, Result};
+use std::cmp;
+use std::env;
+use std::io::Write;
+use tlpi::{fatal, get_long, usage_err, GN_GT_0};
+
+/* Write 'num-bytes' bytes to 'file', using a buffer size of 'buf-size' for
+ * each write(). A simple program for file I/O benchmarking: run it under
+ * time(1) with varying buffer sizes to see the cost of each system call. */
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() != 4 || argv[1] == "--help" {
+        usage_err!("{} file num-bytes buf-size\n", argv[0]);
+    }
+
+    let num_bytes: usize = get_long(&argv[2], GN_GT_0, "num-bytes")?
+        .try_into()
+        .unwrap();
+    let buf_size: usize = get_long(&argv[3], GN_GT_0, "buf-size")?.try_into().unwrap();
+
+    let buf = vec![0; buf_size];
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .mode(0o600)
+        .open(&argv[1])
+        .context("open")?;
+
+    let mut tot_written = 0;
+    while tot_written < num_bytes {
+        let this_write = cmp::min(buf_size, num_bytes - tot_written);
+        match file.write(&buf[..this_write]) {
+            Ok(n) if n == this_write => {}
+            _ => fatal!("partial/failed write"),
+        }
+        tot_written += this_write;
+    }
+
+    file.close().context("close")?;
+
+    Ok(())
+}
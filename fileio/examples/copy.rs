@@ -1,46 +1,99 @@
-use alt::fs::OpenOptions;
-use anyhow::{Context, Result};
-use std::env;
-use std::io::{Read, Write};
-use tlpi::{fatal, usage_err};
+use alt::env;
+use alt::fs::xattr::{self, Namespace, SetFlags};
+use alt::fs::{self, FileTimes, OpenOptions};
+use alt::io;
+use anyhow::{bail, Context, Result};
+use std::ffi::OsString;
+use std::path::Path;
+use tlpi::usage_err;
 
 fn main() -> Result<()> {
-    let argv: Vec<String> = env::args().collect();
+    /* File names are arbitrary byte strings, so take them as such */
+
+    let argv: Vec<OsString> = env::args().context("reading arguments")?.collect();
     if argv.len() != 3 || argv[1] == "--help" {
-        usage_err!("{} old-file new-file\n", argv[0]);
+        usage_err!("{} old-file new-file\n", argv[0].to_string_lossy());
     }
 
     /* Open input and output files */
 
-    let mut input_file = OpenOptions::new()
+    let input_file = OpenOptions::new()
         .read(true)
         .open(&argv[1])
-        .with_context(|| format!("opening file {}", argv[1]))?;
+        .with_context(|| format!("opening file {}", argv[1].to_string_lossy()))?;
+
+    /* Refuse to start a copy that cannot fit in the space left on the
+     * output filesystem, rather than failing with ENOSPC part way. This
+     * must come before the output is opened, which truncates it. Only a
+     * regular file's allocated blocks say how much space it needs: holes
+     * take none, and /proc and device files report unrelated sizes */
+
+    let sb = input_file.metadata().context("fstat")?;
+    if sb.is_file() {
+        let needed = sb.blocks() * 512;
+        let output_dir = match Path::new(&argv[2]).parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let available = fs::statvfs(output_dir)
+            .context("statvfs")?
+            .available_space();
+        if needed > available {
+            bail!(
+                "{}: need {} bytes but only {} available",
+                argv[2].to_string_lossy(),
+                needed,
+                available
+            );
+        }
+    }
 
-    let mut output_file = OpenOptions::new()
+    let output_file = OpenOptions::new()
         .write(true)
         .create(true)
         .truncate(true)
         .mode(0o600)
         .open(&argv[2])
-        .with_context(|| format!("opening file {}", argv[2]))?;
+        .with_context(|| format!("opening file {}", argv[2].to_string_lossy()))?;
 
+    /* Transfer data until we encounter end of input or an error, letting
+     * the kernel move the data where it can. Otherwise the data passes
+     * through a buffer sized from st_blksize (or the page size) */
 
-    /* Transfer data until we encounter end of input or an error */
+    io::copy(&input_file, &output_file).context("copy")?;
 
-    let mut buffer = [0; 1024];
+    /* Carry over user.* extended attributes; a filesystem without xattr
+     * support simply has none to copy, or cannot take them, in which
+     * case the copy goes ahead without them */
 
-    loop {
-        let num_read = input_file.read(&mut buffer).context("read")?;
-        if num_read == 0 {
-            break;
-        }
-        let num_written = output_file.write(&buffer[..num_read]).context("write")?;
-        if num_written != num_read {
-            fatal!("could not write whole buffer");
+    match xattr::flist(&input_file) {
+        Ok(names) => {
+            for name in names {
+                if !matches!(xattr::split_name(&name), Some((Namespace::User, _))) {
+                    continue;
+                }
+                if let Some(value) = xattr::fget(&input_file, &name).context("fgetxattr")? {
+                    match xattr::fset(&output_file, &name, &value, SetFlags::empty()) {
+                        Ok(()) => {}
+                        Err(e) if e.raw_os_error() == Some(libc::ENOTSUP) => break,
+                        Err(e) => return Err(e).context("fsetxattr"),
+                    }
+                }
+            }
         }
+        Err(e) if e.raw_os_error() == Some(libc::ENOTSUP) => {}
+        Err(e) => return Err(e).context("flistxattr"),
     }
 
+    /* Give the copy the timestamps of the original, as "cp -p" does. This
+     * must follow the last write, which would otherwise update st_mtime */
+
+    let sb = input_file.metadata().context("fstat")?;
+    let times = FileTimes::new()
+        .set_accessed(sb.accessed())
+        .set_modified(sb.modified());
+    output_file.set_times(times).context("futimens")?;
+
     input_file.close().context("close input")?;
     output_file.close().context("close output")?;
 
@@ -1,17 +1,23 @@
 use anyhow::{Context, Result};
+use alt::env;
 use alt::fs::OpenOptions;
-use std::env;
+use alt::io;
+use std::ffi::OsString;
 use std::io::{Read, Seek, SeekFrom, Write};
+use std::os::unix::ffi::OsStrExt;
 use tlpi::{
     GN_ANY_BASE, GN_NONNEG,
     cmd_line_err, get_long, usage_err,
 };
 
 fn main() -> Result<()> {
-    let argv: Vec<String> = env::args().collect();
+    let argv: Vec<OsString> = env::args().context("reading arguments")?.collect();
     if argv.len() < 3 || argv[1] == "--help" {
-        usage_err!("{} file {}...\n", argv[0],
-                   "{r<length>|R<length>|w<string>|s<offset>}");
+        usage_err!(
+            "{} file {}...\n",
+            argv[0].to_string_lossy(),
+            "{r<length>|R<length>|w<string>|s<offset>}"
+        );
     }
 
     let mut file = OpenOptions::new()
@@ -22,8 +28,15 @@ fn main() -> Result<()> {
         .open(&argv[1])
         .context("open")?;
 
-    for arg in argv.into_iter().skip(2) {
-        match arg.bytes().next() {
+    let mut stdout = io::stdout();
+
+    for arg in argv.iter().skip(2) {
+        /* The string written by "w" is passed through byte for byte; the
+         * lossy form is only for messages and parsing numbers */
+
+        let bytes = arg.as_bytes();
+        let arg = arg.to_string_lossy();
+        match bytes.first() {
             Some(b'r') | Some(b'R') => {
                 let flags = GN_NONNEG | GN_ANY_BASE;
                 let len: usize = get_long(&arg[1..], flags, &arg)?
@@ -31,41 +44,39 @@ fn main() -> Result<()> {
                 let mut buf = vec![0; len];
                 let num_read = file.read(&mut buf).context("read")?;
                 if num_read == 0 {
-                    println!("{}: end-of-file", arg);
+                    writeln!(stdout, "{}: end-of-file", arg)?;
                 } else {
-                    print!("{}: ", arg);
-                    for j in 0..num_read {
-                        match arg.bytes().next().unwrap() {
+                    write!(stdout, "{}: ", arg)?;
+                    for &byte in &buf[..num_read] {
+                        match bytes[0] {
                             b'r' => {
-                                let is_printable = unsafe {
-                                    let byte = (buf[j] as i8) as i32;
-                                    libc::isprint(byte) > 0
-                                };
+                                let is_printable =
+                                    unsafe { libc::isprint((byte as i8) as i32) > 0 };
                                 match is_printable {
-                                    true => print!("{}", buf[j] as char),
-                                    false => print!("{}", '?'),
+                                    true => write!(stdout, "{}", byte as char)?,
+                                    false => write!(stdout, "?")?,
                                 }
                             }
                             b'R' => {
-                                print!("{:02x} ", buf[j]);
+                                write!(stdout, "{:02x} ", byte)?;
                             }
                             _ => unreachable!(),
                         }
                     }
-                    print!("\n");
+                    writeln!(stdout)?;
                 }
             }
             Some(b'w') => {
-                let buf = &arg[1..].as_bytes();
+                let buf = &bytes[1..];
                 let num_written = file.write(buf).context("write")?;
-                println!("{}: wrote {} bytes", arg, num_written);
+                writeln!(stdout, "{}: wrote {} bytes", arg, num_written)?;
             }
             Some(b's') => {
                 let flags = GN_NONNEG | GN_ANY_BASE;
                 let offset: u64 = get_long(&arg[1..], flags, &arg)?
                     .try_into().unwrap();
                 file.seek(SeekFrom::Start(offset)).context("lseek")?;
-                println!("{}: seek succeeded", arg);
+                writeln!(stdout, "{}: seek succeeded", arg)?;
             }
             _ => {
                 cmd_line_err!("Argument must start with [rRws]: {}\n", arg);
@@ -0,0 +1,55 @@
+use alt::fs::{self, Dir, OpenOptions, Permissions};
+use alt::io;
+use anyhow::{Context, Result};
+use std::io::Write;
+use tlpi::err_msg;
+
+const MYFILE: &str = "myfile";
+const MYDIR: &str = "mydir";
+const FILE_PERMS: u32 = libc::S_IRUSR | libc::S_IWUSR | libc::S_IRGRP | libc::S_IWGRP;
+const DIR_PERMS: u32 = libc::S_IRWXU | libc::S_IRWXG | libc::S_IRWXO;
+const UMASK_SETTING: u32 = libc::S_IWGRP | libc::S_IXGRP | libc::S_IWOTH | libc::S_IXOTH;
+
+fn main() -> Result<()> {
+    fs::umask(UMASK_SETTING);
+
+    let _file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .mode(FILE_PERMS)
+        .open(MYFILE)
+        .with_context(|| format!("open-{}", MYFILE))?;
+    Dir::open(".")
+        .and_then(|cwd| cwd.mkdir_at(MYDIR, DIR_PERMS))
+        .with_context(|| format!("mkdir-{}", MYDIR))?;
+
+    let u = Permissions::from_mode(fs::umask(0)); /* Retrieves (and clears) umask value */
+
+    let mut out = io::stdout();
+    let sb = fs::metadata(MYFILE).with_context(|| format!("stat-{}", MYFILE))?;
+    writeln!(
+        out,
+        "Requested file perms: {}",
+        Permissions::from_mode(FILE_PERMS)
+    )?;
+    writeln!(out, "Process umask:        {}", u)?;
+    writeln!(out, "Actual file perms:    {}\n", sb.permissions())?;
+
+    let sb = fs::metadata(MYDIR).with_context(|| format!("stat-{}", MYDIR))?;
+    writeln!(
+        out,
+        "Requested dir. perms: {}",
+        Permissions::from_mode(DIR_PERMS)
+    )?;
+    writeln!(out, "Process umask:        {}", u)?;
+    writeln!(out, "Actual dir. perms:    {}", sb.permissions())?;
+
+    if fs::remove_file(MYFILE).is_err() {
+        err_msg!("unlink-{}", MYFILE);
+    }
+    if fs::remove_dir(MYDIR).is_err() {
+        err_msg!("rmdir-{}", MYDIR);
+    }
+    Ok(())
+}
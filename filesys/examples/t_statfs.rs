@@ -0,0 +1,37 @@
+use alt::fs;
+use alt::io;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use tlpi::usage_err;
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() != 2 || argv[1] == "--help" {
+        usage_err!("{} path\n", argv[0]);
+    }
+
+    let sfs = fs::statfs(&argv[1]).context("statfs")?;
+    let fsid = sfs.fsid();
+
+    let mut out = io::stdout();
+    writeln!(out, "File system type:              {:#x}", sfs.fs_type())?;
+    writeln!(out, "Optimal I/O block size:        {}", sfs.block_size())?;
+    writeln!(out, "Total data blocks:             {}", sfs.blocks())?;
+    writeln!(out, "Free data blocks:              {}", sfs.blocks_free())?;
+    writeln!(
+        out,
+        "Free blocks for nonsuperuser:  {}",
+        sfs.blocks_available()
+    )?;
+    writeln!(out, "Total i-nodes:                 {}", sfs.files())?;
+    writeln!(
+        out,
+        "File system ID:                {:#x}, {:#x}",
+        fsid[0], fsid[1]
+    )?;
+    writeln!(out, "Free i-nodes:                  {}", sfs.files_free())?;
+    writeln!(out, "Maximum file name length:      {}", sfs.name_max())?;
+
+    Ok(())
+}
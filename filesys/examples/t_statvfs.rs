@@ -0,0 +1,46 @@
+use alt::fs;
+use alt::io;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use tlpi::usage_err;
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() != 2 || argv[1] == "--help" {
+        usage_err!("{} path\n", argv[0]);
+    }
+
+    let sb = fs::statvfs(&argv[1]).context("statvfs")?;
+
+    let mut out = io::stdout();
+    writeln!(out, "Block size                       {}", sb.block_size())?;
+    writeln!(
+        out,
+        "Fundamental block size           {}",
+        sb.fragment_size()
+    )?;
+    writeln!(out, "Total blocks (in above units)    {}", sb.blocks())?;
+    writeln!(out, "Free blocks for priv. proc.      {}", sb.blocks_free())?;
+    writeln!(
+        out,
+        "Free blocks for unpriv. proc.    {}",
+        sb.blocks_available()
+    )?;
+    writeln!(out, "Total number of i-nodes          {}", sb.files())?;
+    writeln!(out, "Free i-nodes for priv. proc.     {}", sb.files_free())?;
+    writeln!(
+        out,
+        "Free i-nodes for nonpriv. proc.  {}",
+        sb.files_available()
+    )?;
+    writeln!(out, "File system ID                   {:#x}", sb.fsid())?;
+    writeln!(
+        out,
+        "Flags                            {:#x}",
+        sb.flags().bits()
+    )?;
+    writeln!(out, "Maximum filename length          {}", sb.name_max())?;
+
+    Ok(())
+}
@@ -1,15 +1,40 @@
-#![allow(non_camel_case_types)]
+#![allow(non_camel_case_types, non_upper_case_globals)]
 pub type c_char = i8;
 pub type c_int = i32;
+pub type c_uint = u32;
 pub type c_long = i64;
+pub type c_ulong = u64;
 pub type c_void = std::ffi::c_void;
 
 pub type mode_t = u32;
+pub type pid_t = i32;
+pub type uid_t = u32;
+pub type gid_t = u32;
+pub type dev_t = u64;
+pub type ino64_t = u64;
+pub type nlink_t = u64;
+pub type blksize_t = i64;
+pub type blkcnt64_t = i64;
+pub type fsblkcnt64_t = u64;
+pub type fsfilcnt64_t = u64;
+pub type time_t = i64;
+pub type clockid_t = c_int;
+pub type pthread_t = c_ulong;
+pub type timer_t = *mut c_void;
+pub type suseconds_t = i64;
+pub type rlim_t = u64;
+pub type clock_t = c_long;
 pub type off_t = i64;
 pub type off64_t = i64;
+pub type loff_t = i64;
 pub type size_t = usize;
 pub type ssize_t = isize;
 
+pub type sa_family_t = u16;
+pub type socklen_t = u32;
+pub type in_addr_t = u32;
+pub type in_port_t = u16;
+
 pub const EXIT_SUCCESS: c_int = 0;
 pub const EXIT_FAILURE: c_int = 1;
 
@@ -18,25 +43,1105 @@ pub const O_WRONLY: c_int = 1;
 pub const O_RDWR: c_int = 2;
 
 pub const O_CREAT: c_int = 64;
+pub const O_EXCL: c_int = 128;
 pub const O_TRUNC: c_int = 512;
+pub const O_DIRECTORY: c_int = 0o200000;
+pub const O_NOFOLLOW: c_int = 0o400000;
+pub const O_CLOEXEC: c_int = 0o2000000;
+pub const O_PATH: c_int = 0o10000000;
+pub const O_TMPFILE: c_int = 0o20000000 | O_DIRECTORY;
+
+pub const AT_FDCWD: c_int = -100;
+pub const AT_SYMLINK_NOFOLLOW: c_int = 0x100;
+pub const AT_REMOVEDIR: c_int = 0x200;
+pub const AT_EACCESS: c_int = 0x200;
+pub const AT_SYMLINK_FOLLOW: c_int = 0x400;
+pub const AT_EMPTY_PATH: c_int = 0x1000;
+
+pub const F_OK: c_int = 0;
+pub const X_OK: c_int = 1;
+pub const W_OK: c_int = 2;
+pub const R_OK: c_int = 4;
 
 pub const SEEK_SET: c_int = 0;
 pub const SEEK_CUR: c_int = 1;
 pub const SEEK_END: c_int = 2;
+pub const SEEK_DATA: c_int = 3;
+pub const SEEK_HOLE: c_int = 4;
 
 // errno.h
+pub const EPERM: c_int = 1;
+pub const ENOENT: c_int = 2;
+pub const ESRCH: c_int = 3;
+pub const EINTR: c_int = 4;
+pub const ENXIO: c_int = 6;
+pub const EBADF: c_int = 9;
+pub const EACCES: c_int = 13;
+pub const EEXIST: c_int = 17;
+pub const EXDEV: c_int = 18;
+pub const ENOTDIR: c_int = 20;
+pub const EISDIR: c_int = 21;
 pub const EINVAL: c_int = 22;
+pub const ERANGE: c_int = 34;
+pub const ENOSYS: c_int = 38;
+pub const ENOTEMPTY: c_int = 39;
+pub const ELOOP: c_int = 40;
+pub const ENODATA: c_int = 61;
+pub const EOPNOTSUPP: c_int = 95;
+pub const ENOTSUP: c_int = EOPNOTSUPP;
+
+// fcntl.h
+pub const SPLICE_F_MOVE: c_uint = 1;
+pub const SPLICE_F_NONBLOCK: c_uint = 2;
+pub const SPLICE_F_MORE: c_uint = 4;
+pub const SPLICE_F_GIFT: c_uint = 8;
+
+pub const FALLOC_FL_KEEP_SIZE: c_int = 0x01;
+pub const FALLOC_FL_PUNCH_HOLE: c_int = 0x02;
+pub const FALLOC_FL_COLLAPSE_RANGE: c_int = 0x08;
+pub const FALLOC_FL_ZERO_RANGE: c_int = 0x10;
+pub const FALLOC_FL_INSERT_RANGE: c_int = 0x20;
+
+pub const SYNC_FILE_RANGE_WAIT_BEFORE: c_uint = 1;
+pub const SYNC_FILE_RANGE_WRITE: c_uint = 2;
+pub const SYNC_FILE_RANGE_WAIT_AFTER: c_uint = 4;
+
+pub const POSIX_FADV_NORMAL: c_int = 0;
+pub const POSIX_FADV_RANDOM: c_int = 1;
+pub const POSIX_FADV_SEQUENTIAL: c_int = 2;
+pub const POSIX_FADV_WILLNEED: c_int = 3;
+pub const POSIX_FADV_DONTNEED: c_int = 4;
+pub const POSIX_FADV_NOREUSE: c_int = 5;
+
+// linux/openat2.h
+pub const RESOLVE_NO_XDEV: u64 = 0x01;
+pub const RESOLVE_NO_MAGICLINKS: u64 = 0x02;
+pub const RESOLVE_NO_SYMLINKS: u64 = 0x04;
+pub const RESOLVE_BENEATH: u64 = 0x08;
+pub const RESOLVE_IN_ROOT: u64 = 0x10;
+pub const RESOLVE_CACHED: u64 = 0x20;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct open_how {
+    pub flags: u64,
+    pub mode: u64,
+    pub resolve: u64,
+}
+
+// sys/syscall.h
+pub const SYS_openat2: c_long = 437;
+
+// stdio.h
+pub const RENAME_NOREPLACE: c_uint = 1;
+pub const RENAME_EXCHANGE: c_uint = 2;
+pub const RENAME_WHITEOUT: c_uint = 4;
+
+// sys/stat.h
+pub const S_IFMT: mode_t = 0o170000;
+pub const S_IFSOCK: mode_t = 0o140000;
+pub const S_IFLNK: mode_t = 0o120000;
+pub const S_IFREG: mode_t = 0o100000;
+pub const S_IFBLK: mode_t = 0o060000;
+pub const S_IFDIR: mode_t = 0o040000;
+pub const S_IFCHR: mode_t = 0o020000;
+pub const S_IFIFO: mode_t = 0o010000;
+pub const S_ISUID: mode_t = 0o4000;
+pub const S_ISGID: mode_t = 0o2000;
+pub const S_ISVTX: mode_t = 0o1000;
+pub const S_IRWXU: mode_t = 0o700;
+pub const S_IRUSR: mode_t = 0o400;
+pub const S_IWUSR: mode_t = 0o200;
+pub const S_IXUSR: mode_t = 0o100;
+pub const S_IRWXG: mode_t = 0o070;
+pub const S_IRGRP: mode_t = 0o040;
+pub const S_IWGRP: mode_t = 0o020;
+pub const S_IXGRP: mode_t = 0o010;
+pub const S_IRWXO: mode_t = 0o007;
+pub const S_IROTH: mode_t = 0o004;
+pub const S_IWOTH: mode_t = 0o002;
+pub const S_IXOTH: mode_t = 0o001;
+
+pub const UTIME_NOW: c_long = (1 << 30) - 1;
+pub const UTIME_OMIT: c_long = (1 << 30) - 2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct stat64 {
+    pub st_dev: dev_t,
+    pub st_ino: ino64_t,
+    pub st_nlink: nlink_t,
+    pub st_mode: mode_t,
+    pub st_uid: uid_t,
+    pub st_gid: gid_t,
+    __pad0: c_int,
+    pub st_rdev: dev_t,
+    pub st_size: off64_t,
+    pub st_blksize: blksize_t,
+    pub st_blocks: blkcnt64_t,
+    pub st_atime: time_t,
+    pub st_atime_nsec: i64,
+    pub st_mtime: time_t,
+    pub st_mtime_nsec: i64,
+    pub st_ctime: time_t,
+    pub st_ctime_nsec: i64,
+    __unused: [i64; 3],
+}
+
+// sys/statvfs.h
+pub const ST_RDONLY: c_ulong = 1;
+pub const ST_NOSUID: c_ulong = 2;
+pub const ST_NODEV: c_ulong = 4;
+pub const ST_NOEXEC: c_ulong = 8;
+pub const ST_SYNCHRONOUS: c_ulong = 16;
+pub const ST_MANDLOCK: c_ulong = 64;
+pub const ST_NOATIME: c_ulong = 1024;
+pub const ST_NODIRATIME: c_ulong = 2048;
+pub const ST_RELATIME: c_ulong = 4096;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct statvfs64 {
+    pub f_bsize: c_ulong,
+    pub f_frsize: c_ulong,
+    pub f_blocks: fsblkcnt64_t,
+    pub f_bfree: fsblkcnt64_t,
+    pub f_bavail: fsblkcnt64_t,
+    pub f_files: fsfilcnt64_t,
+    pub f_ffree: fsfilcnt64_t,
+    pub f_favail: fsfilcnt64_t,
+    pub f_fsid: c_ulong,
+    pub f_flag: c_ulong,
+    pub f_namemax: c_ulong,
+    __f_spare: [c_int; 6],
+}
+
+// sys/statfs.h
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct fsid_t {
+    pub __val: [c_int; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct statfs64 {
+    pub f_type: c_long,
+    pub f_bsize: c_long,
+    pub f_blocks: fsblkcnt64_t,
+    pub f_bfree: fsblkcnt64_t,
+    pub f_bavail: fsblkcnt64_t,
+    pub f_files: fsfilcnt64_t,
+    pub f_ffree: fsfilcnt64_t,
+    pub f_fsid: fsid_t,
+    pub f_namelen: c_long,
+    pub f_frsize: c_long,
+    pub f_flags: c_long,
+    f_spare: [c_long; 4],
+}
+
+// linux/magic.h
+pub const BTRFS_SUPER_MAGIC: c_long = 0x9123683e;
+pub const CGROUP2_SUPER_MAGIC: c_long = 0x63677270;
+pub const EXT4_SUPER_MAGIC: c_long = 0xef53;
+pub const NFS_SUPER_MAGIC: c_long = 0x6969;
+pub const OVERLAYFS_SUPER_MAGIC: c_long = 0x794c7630;
+pub const PROC_SUPER_MAGIC: c_long = 0x9fa0;
+pub const SYSFS_MAGIC: c_long = 0x62656572;
+pub const TMPFS_MAGIC: c_long = 0x01021994;
+pub const XFS_SUPER_MAGIC: c_long = 0x58465342;
+
+// sys/xattr.h
+pub const XATTR_CREATE: c_int = 1;
+pub const XATTR_REPLACE: c_int = 2;
+
+// pwd.h
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct passwd {
+    pub pw_name: *mut c_char,
+    pub pw_passwd: *mut c_char,
+    pub pw_uid: uid_t,
+    pub pw_gid: gid_t,
+    pub pw_gecos: *mut c_char,
+    pub pw_dir: *mut c_char,
+    pub pw_shell: *mut c_char,
+}
+
+// grp.h
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct group {
+    pub gr_name: *mut c_char,
+    pub gr_passwd: *mut c_char,
+    pub gr_gid: gid_t,
+    pub gr_mem: *mut *mut c_char,
+}
+
+// unistd.h
+pub const _SC_ARG_MAX: c_int = 0;
+pub const _SC_CHILD_MAX: c_int = 1;
+pub const _SC_CLK_TCK: c_int = 2;
+pub const _SC_NGROUPS_MAX: c_int = 3;
+pub const _SC_OPEN_MAX: c_int = 4;
+pub const _SC_STREAM_MAX: c_int = 5;
+pub const _SC_PAGESIZE: c_int = 30;
+pub const _SC_RTSIG_MAX: c_int = 31;
+pub const _SC_SIGQUEUE_MAX: c_int = 34;
+pub const _SC_LINE_MAX: c_int = 43;
+pub const _SC_LOGIN_NAME_MAX: c_int = 71;
+pub const _SC_TTY_NAME_MAX: c_int = 72;
+pub const _SC_NPROCESSORS_CONF: c_int = 83;
+pub const _SC_NPROCESSORS_ONLN: c_int = 84;
+pub const _SC_PHYS_PAGES: c_int = 85;
+pub const _SC_AVPHYS_PAGES: c_int = 86;
+pub const _SC_HOST_NAME_MAX: c_int = 180;
+
+pub const _PC_LINK_MAX: c_int = 0;
+pub const _PC_MAX_CANON: c_int = 1;
+pub const _PC_MAX_INPUT: c_int = 2;
+pub const _PC_NAME_MAX: c_int = 3;
+pub const _PC_PATH_MAX: c_int = 4;
+pub const _PC_PIPE_BUF: c_int = 5;
+pub const _PC_CHOWN_RESTRICTED: c_int = 6;
+pub const _PC_NO_TRUNC: c_int = 7;
+pub const _PC_VDISABLE: c_int = 8;
+
+// sys/resource.h
+pub const RLIMIT_CPU: c_int = 0;
+pub const RLIMIT_FSIZE: c_int = 1;
+pub const RLIMIT_DATA: c_int = 2;
+pub const RLIMIT_STACK: c_int = 3;
+pub const RLIMIT_CORE: c_int = 4;
+pub const RLIMIT_RSS: c_int = 5;
+pub const RLIMIT_NPROC: c_int = 6;
+pub const RLIMIT_NOFILE: c_int = 7;
+pub const RLIMIT_MEMLOCK: c_int = 8;
+pub const RLIMIT_AS: c_int = 9;
+pub const RLIMIT_LOCKS: c_int = 10;
+pub const RLIMIT_SIGPENDING: c_int = 11;
+pub const RLIMIT_MSGQUEUE: c_int = 12;
+pub const RLIMIT_NICE: c_int = 13;
+pub const RLIMIT_RTPRIO: c_int = 14;
+pub const RLIMIT_RTTIME: c_int = 15;
+pub const RLIMIT_NLIMITS: c_int = 16;
+
+pub const RLIM_INFINITY: rlim_t = !0;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct rlimit {
+    pub rlim_cur: rlim_t,
+    pub rlim_max: rlim_t,
+}
+
+pub const RUSAGE_SELF: c_int = 0;
+pub const RUSAGE_CHILDREN: c_int = -1;
+pub const RUSAGE_THREAD: c_int = 1;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct rusage {
+    pub ru_utime: timeval,
+    pub ru_stime: timeval,
+    pub ru_maxrss: c_long,
+    pub ru_ixrss: c_long,
+    pub ru_idrss: c_long,
+    pub ru_isrss: c_long,
+    pub ru_minflt: c_long,
+    pub ru_majflt: c_long,
+    pub ru_nswap: c_long,
+    pub ru_inblock: c_long,
+    pub ru_oublock: c_long,
+    pub ru_msgsnd: c_long,
+    pub ru_msgrcv: c_long,
+    pub ru_nsignals: c_long,
+    pub ru_nvcsw: c_long,
+    pub ru_nivcsw: c_long,
+}
+
+// sys/times.h
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct tms {
+    pub tms_utime: clock_t,
+    pub tms_stime: clock_t,
+    pub tms_cutime: clock_t,
+    pub tms_cstime: clock_t,
+}
+
+// sys/wait.h
+pub const WNOHANG: c_int = 1;
+pub const WUNTRACED: c_int = 2;
+pub const WCONTINUED: c_int = 8;
+
+// sys/utsname.h
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct utsname {
+    pub sysname: [c_char; 65],
+    pub nodename: [c_char; 65],
+    pub release: [c_char; 65],
+    pub version: [c_char; 65],
+    pub machine: [c_char; 65],
+    pub domainname: [c_char; 65],
+}
+
+// sys/sysinfo.h
+pub const SI_LOAD_SHIFT: c_int = 16;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct sysinfo {
+    pub uptime: c_long,
+    pub loads: [c_ulong; 3],
+    pub totalram: c_ulong,
+    pub freeram: c_ulong,
+    pub sharedram: c_ulong,
+    pub bufferram: c_ulong,
+    pub totalswap: c_ulong,
+    pub freeswap: c_ulong,
+    pub procs: u16,
+    pub pad: u16,
+    pub totalhigh: c_ulong,
+    pub freehigh: c_ulong,
+    pub mem_unit: c_uint,
+}
+
+pub const HOST_NAME_MAX: c_int = 64;
+
+// sys/auxv.h
+pub const AT_PAGESZ: c_ulong = 6;
+pub const AT_UID: c_ulong = 11;
+pub const AT_EUID: c_ulong = 12;
+pub const AT_GID: c_ulong = 13;
+pub const AT_EGID: c_ulong = 14;
+pub const AT_PLATFORM: c_ulong = 15;
+pub const AT_HWCAP: c_ulong = 16;
+pub const AT_CLKTCK: c_ulong = 17;
+pub const AT_SECURE: c_ulong = 23;
+pub const AT_RANDOM: c_ulong = 25;
+pub const AT_EXECFN: c_ulong = 31;
+
+// linux/capability.h
+pub const _LINUX_CAPABILITY_VERSION_3: u32 = 0x20080522;
+pub const _LINUX_CAPABILITY_U32S_3: usize = 2;
+
+pub const CAP_CHOWN: c_int = 0;
+pub const CAP_DAC_OVERRIDE: c_int = 1;
+pub const CAP_DAC_READ_SEARCH: c_int = 2;
+pub const CAP_FOWNER: c_int = 3;
+pub const CAP_FSETID: c_int = 4;
+pub const CAP_KILL: c_int = 5;
+pub const CAP_SETGID: c_int = 6;
+pub const CAP_SETUID: c_int = 7;
+pub const CAP_SETPCAP: c_int = 8;
+pub const CAP_LINUX_IMMUTABLE: c_int = 9;
+pub const CAP_NET_BIND_SERVICE: c_int = 10;
+pub const CAP_NET_BROADCAST: c_int = 11;
+pub const CAP_NET_ADMIN: c_int = 12;
+pub const CAP_NET_RAW: c_int = 13;
+pub const CAP_IPC_LOCK: c_int = 14;
+pub const CAP_IPC_OWNER: c_int = 15;
+pub const CAP_SYS_MODULE: c_int = 16;
+pub const CAP_SYS_RAWIO: c_int = 17;
+pub const CAP_SYS_CHROOT: c_int = 18;
+pub const CAP_SYS_PTRACE: c_int = 19;
+pub const CAP_SYS_PACCT: c_int = 20;
+pub const CAP_SYS_ADMIN: c_int = 21;
+pub const CAP_SYS_BOOT: c_int = 22;
+pub const CAP_SYS_NICE: c_int = 23;
+pub const CAP_SYS_RESOURCE: c_int = 24;
+pub const CAP_SYS_TIME: c_int = 25;
+pub const CAP_SYS_TTY_CONFIG: c_int = 26;
+pub const CAP_MKNOD: c_int = 27;
+pub const CAP_LEASE: c_int = 28;
+pub const CAP_AUDIT_WRITE: c_int = 29;
+pub const CAP_AUDIT_CONTROL: c_int = 30;
+pub const CAP_SETFCAP: c_int = 31;
+pub const CAP_MAC_OVERRIDE: c_int = 32;
+pub const CAP_MAC_ADMIN: c_int = 33;
+pub const CAP_SYSLOG: c_int = 34;
+pub const CAP_WAKE_ALARM: c_int = 35;
+pub const CAP_BLOCK_SUSPEND: c_int = 36;
+pub const CAP_AUDIT_READ: c_int = 37;
+pub const CAP_PERFMON: c_int = 38;
+pub const CAP_BPF: c_int = 39;
+pub const CAP_CHECKPOINT_RESTORE: c_int = 40;
+pub const CAP_LAST_CAP: c_int = CAP_CHECKPOINT_RESTORE;
+
+pub const VFS_CAP_REVISION_MASK: u32 = 0xff000000;
+pub const VFS_CAP_REVISION_1: u32 = 0x01000000;
+pub const VFS_CAP_REVISION_2: u32 = 0x02000000;
+pub const VFS_CAP_REVISION_3: u32 = 0x03000000;
+pub const VFS_CAP_FLAGS_EFFECTIVE: u32 = 0x000001;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct __user_cap_header_struct {
+    pub version: u32,
+    pub pid: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct __user_cap_data_struct {
+    pub effective: u32,
+    pub permitted: u32,
+    pub inheritable: u32,
+}
+
+// linux/securebits.h
+pub const SECBIT_NOROOT: c_uint = 1 << 0;
+pub const SECBIT_NOROOT_LOCKED: c_uint = 1 << 1;
+pub const SECBIT_NO_SETUID_FIXUP: c_uint = 1 << 2;
+pub const SECBIT_NO_SETUID_FIXUP_LOCKED: c_uint = 1 << 3;
+pub const SECBIT_KEEP_CAPS: c_uint = 1 << 4;
+pub const SECBIT_KEEP_CAPS_LOCKED: c_uint = 1 << 5;
+pub const SECBIT_NO_CAP_AMBIENT_RAISE: c_uint = 1 << 6;
+pub const SECBIT_NO_CAP_AMBIENT_RAISE_LOCKED: c_uint = 1 << 7;
+
+// sys/prctl.h
+pub const PR_GET_KEEPCAPS: c_int = 7;
+pub const PR_SET_KEEPCAPS: c_int = 8;
+pub const PR_CAPBSET_READ: c_int = 23;
+pub const PR_CAPBSET_DROP: c_int = 24;
+pub const PR_GET_SECUREBITS: c_int = 27;
+pub const PR_SET_SECUREBITS: c_int = 28;
+pub const PR_CAP_AMBIENT: c_int = 47;
+pub const PR_CAP_AMBIENT_IS_SET: c_ulong = 1;
+pub const PR_CAP_AMBIENT_RAISE: c_ulong = 2;
+pub const PR_CAP_AMBIENT_LOWER: c_ulong = 3;
+pub const PR_CAP_AMBIENT_CLEAR_ALL: c_ulong = 4;
+
+// time.h
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct timespec {
+    pub tv_sec: time_t,
+    pub tv_nsec: c_long,
+}
+
+pub const CLOCK_REALTIME: clockid_t = 0;
+pub const CLOCK_MONOTONIC: clockid_t = 1;
+pub const CLOCK_PROCESS_CPUTIME_ID: clockid_t = 2;
+pub const CLOCK_THREAD_CPUTIME_ID: clockid_t = 3;
+pub const CLOCK_MONOTONIC_RAW: clockid_t = 4;
+pub const CLOCK_REALTIME_COARSE: clockid_t = 5;
+pub const CLOCK_MONOTONIC_COARSE: clockid_t = 6;
+pub const CLOCK_BOOTTIME: clockid_t = 7;
+pub const CLOCK_TAI: clockid_t = 11;
+
+pub const TIMER_ABSTIME: c_int = 1;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct itimerspec {
+    pub it_interval: timespec,
+    pub it_value: timespec,
+}
+
+// sys/time.h
+pub const ITIMER_REAL: c_int = 0;
+pub const ITIMER_VIRTUAL: c_int = 1;
+pub const ITIMER_PROF: c_int = 2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct timeval {
+    pub tv_sec: time_t,
+    pub tv_usec: suseconds_t,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct itimerval {
+    pub it_interval: timeval,
+    pub it_value: timeval,
+}
+
+// sys/timerfd.h
+pub const TFD_NONBLOCK: c_int = 0o4000;
+pub const TFD_CLOEXEC: c_int = O_CLOEXEC;
+pub const TFD_TIMER_ABSTIME: c_int = 1;
+pub const TFD_TIMER_CANCEL_ON_SET: c_int = 2;
+
+// signal.h
+pub const SIGINT: c_int = 2;
+pub const SIGALRM: c_int = 14;
+pub const SIGVTALRM: c_int = 26;
+pub const SIGPROF: c_int = 27;
+
+pub const SIG_BLOCK: c_int = 0;
+pub const SIG_UNBLOCK: c_int = 1;
+pub const SIG_SETMASK: c_int = 2;
+
+pub const SI_TIMER: c_int = -2;
+
+pub const SIGEV_SIGNAL: c_int = 0;
+pub const SIGEV_NONE: c_int = 1;
+pub const SIGEV_THREAD: c_int = 2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct sigset_t {
+    pub __val: [c_ulong; 16],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union sigval {
+    pub sival_int: c_int,
+    pub sival_ptr: *mut c_void,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct sigevent {
+    pub sigev_value: sigval,
+    pub sigev_signo: c_int,
+    pub sigev_notify: c_int,
+    pub sigev_notify_function: Option<extern "C" fn(sigval)>,
+    pub sigev_notify_attributes: *mut c_void,
+    pub __pad: [c_int; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct siginfo_t {
+    pub si_signo: c_int,
+    pub si_errno: c_int,
+    pub si_code: c_int,
+    __pad0: c_int,
+    __fields: [u64; 14],
+}
+
+impl siginfo_t {
+    /// The fields of the union that are valid for `SI_TIMER`.
+    ///
+    /// # Safety
+    ///
+    /// `si_code` must be `SI_TIMER`.
+    pub unsafe fn si_overrun(&self) -> c_int {
+        *(self.__fields.as_ptr() as *const c_int).add(1)
+    }
+
+    /// # Safety
+    ///
+    /// The signal must have been queued with a value, e.g. `si_code` is
+    /// `SI_TIMER` or `SI_QUEUE`.
+    pub unsafe fn si_value(&self) -> sigval {
+        *(self.__fields.as_ptr().add(1) as *const sigval)
+    }
+}
+
+// dirent.h
+pub const DT_UNKNOWN: u8 = 0;
+pub const DT_FIFO: u8 = 1;
+pub const DT_CHR: u8 = 2;
+pub const DT_DIR: u8 = 4;
+pub const DT_BLK: u8 = 6;
+pub const DT_REG: u8 = 8;
+pub const DT_LNK: u8 = 10;
+pub const DT_SOCK: u8 = 12;
+
+pub enum DIR {}
+
+#[repr(C)]
+pub struct dirent64 {
+    pub d_ino: u64,
+    pub d_off: i64,
+    pub d_reclen: u16,
+    pub d_type: u8,
+    pub d_name: [c_char; 256],
+}
+
+// sys/socket.h
+pub const AF_UNSPEC: c_int = 0;
+pub const AF_INET: c_int = 2;
+pub const AF_INET6: c_int = 10;
+
+pub const SOCK_STREAM: c_int = 1;
+pub const SOCK_DGRAM: c_int = 2;
+pub const SOCK_CLOEXEC: c_int = O_CLOEXEC;
+
+pub const SOL_SOCKET: c_int = 1;
+pub const SO_REUSEADDR: c_int = 2;
+pub const SO_ERROR: c_int = 4;
+pub const SO_BROADCAST: c_int = 6;
+pub const SO_SNDBUF: c_int = 7;
+pub const SO_RCVBUF: c_int = 8;
+pub const SO_KEEPALIVE: c_int = 9;
+
+pub const SOMAXCONN: c_int = 4096;
+
+pub const MSG_PEEK: c_int = 0x2;
+pub const MSG_NOSIGNAL: c_int = 0x4000;
+
+pub const SHUT_RD: c_int = 0;
+pub const SHUT_WR: c_int = 1;
+pub const SHUT_RDWR: c_int = 2;
+
+// netinet/in.h
+pub const IPPROTO_IP: c_int = 0;
+pub const IPPROTO_TCP: c_int = 6;
+pub const IPPROTO_IPV6: c_int = 41;
+
+pub const IP_TTL: c_int = 2;
+pub const IPV6_V6ONLY: c_int = 26;
+
+// netinet/tcp.h
+pub const TCP_NODELAY: c_int = 1;
+
+// netdb.h
+pub const AI_PASSIVE: c_int = 0x0001;
+pub const AI_CANONNAME: c_int = 0x0002;
+pub const AI_NUMERICHOST: c_int = 0x0004;
+pub const AI_ADDRCONFIG: c_int = 0x0020;
+pub const AI_NUMERICSERV: c_int = 0x0400;
+
+pub const NI_MAXHOST: usize = 1025;
+pub const NI_MAXSERV: usize = 32;
+
+pub const NI_NUMERICHOST: c_int = 1;
+pub const NI_NUMERICSERV: c_int = 2;
+pub const NI_NOFQDN: c_int = 4;
+pub const NI_NAMEREQD: c_int = 8;
+pub const NI_DGRAM: c_int = 16;
+
+pub const EAI_BADFLAGS: c_int = -1;
+pub const EAI_NONAME: c_int = -2;
+pub const EAI_AGAIN: c_int = -3;
+pub const EAI_FAIL: c_int = -4;
+pub const EAI_NODATA: c_int = -5;
+pub const EAI_FAMILY: c_int = -6;
+pub const EAI_SOCKTYPE: c_int = -7;
+pub const EAI_SERVICE: c_int = -8;
+pub const EAI_ADDRFAMILY: c_int = -9;
+pub const EAI_MEMORY: c_int = -10;
+pub const EAI_SYSTEM: c_int = -11;
+pub const EAI_OVERFLOW: c_int = -12;
+
+#[repr(C)]
+pub struct addrinfo {
+    pub ai_flags: c_int,
+    pub ai_family: c_int,
+    pub ai_socktype: c_int,
+    pub ai_protocol: c_int,
+    pub ai_addrlen: socklen_t,
+    pub ai_addr: *mut sockaddr,
+    pub ai_canonname: *mut c_char,
+    pub ai_next: *mut addrinfo,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct sockaddr {
+    pub sa_family: sa_family_t,
+    pub sa_data: [c_char; 14],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct sockaddr_storage {
+    pub ss_family: sa_family_t,
+    __ss_padding: [u8; 118],
+    __ss_align: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct in_addr {
+    pub s_addr: in_addr_t,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct sockaddr_in {
+    pub sin_family: sa_family_t,
+    pub sin_port: in_port_t,
+    pub sin_addr: in_addr,
+    pub sin_zero: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct in6_addr {
+    pub s6_addr: [u8; 16],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct sockaddr_in6 {
+    pub sin6_family: sa_family_t,
+    pub sin6_port: in_port_t,
+    pub sin6_flowinfo: u32,
+    pub sin6_addr: in6_addr,
+    pub sin6_scope_id: u32,
+}
 
 extern "C" {
     pub fn __errno_location() -> *mut c_int;
+    pub fn exit(status: c_int) -> !;
     pub fn _exit(status: c_int) -> !;
+    pub fn syscall(num: c_long, ...) -> c_long;
+    pub fn atexit(cb: extern "C" fn()) -> c_int;
+
+    pub fn fork() -> pid_t;
+    pub fn waitpid(pid: pid_t, status: *mut c_int, options: c_int) -> pid_t;
+    pub fn getpid() -> pid_t;
+    pub fn getppid() -> pid_t;
+
+    pub static mut environ: *mut *mut c_char;
+    pub fn getenv(name: *const c_char) -> *mut c_char;
+    pub fn setenv(name: *const c_char, value: *const c_char, overwrite: c_int) -> c_int;
+    pub fn unsetenv(name: *const c_char) -> c_int;
+    pub fn clearenv() -> c_int;
+    pub fn execvp(file: *const c_char, argv: *const *const c_char) -> c_int;
+    pub fn wait4(pid: pid_t, status: *mut c_int, options: c_int, rusage: *mut rusage) -> pid_t;
+    pub fn getrusage(who: c_int, usage: *mut rusage) -> c_int;
+    pub fn times(buf: *mut tms) -> clock_t;
+    pub fn uname(buf: *mut utsname) -> c_int;
+    pub fn sysinfo(info: *mut sysinfo) -> c_int;
+    pub fn gethostname(name: *mut c_char, len: size_t) -> c_int;
+    pub fn sethostname(name: *const c_char, len: size_t) -> c_int;
+
+    pub fn sysconf(name: c_int) -> c_long;
+    pub fn pathconf(path: *const c_char, name: c_int) -> c_long;
+    pub fn fpathconf(fd: c_int, name: c_int) -> c_long;
+    pub fn getauxval(type_: c_ulong) -> c_ulong;
+    pub fn getrlimit(resource: c_int, rlim: *mut rlimit) -> c_int;
+    pub fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int;
+    pub fn prlimit(
+        pid: pid_t,
+        resource: c_int,
+        new_limit: *const rlimit,
+        old_limit: *mut rlimit,
+    ) -> c_int;
+
+    pub fn getuid() -> uid_t;
+    pub fn geteuid() -> uid_t;
+    pub fn getgid() -> gid_t;
+    pub fn getegid() -> gid_t;
+    pub fn getresuid(ruid: *mut uid_t, euid: *mut uid_t, suid: *mut uid_t) -> c_int;
+    pub fn getresgid(rgid: *mut gid_t, egid: *mut gid_t, sgid: *mut gid_t) -> c_int;
+    pub fn setresuid(ruid: uid_t, euid: uid_t, suid: uid_t) -> c_int;
+    pub fn setresgid(rgid: gid_t, egid: gid_t, sgid: gid_t) -> c_int;
+    pub fn setfsuid(fsuid: uid_t) -> c_int;
+    pub fn setfsgid(fsgid: gid_t) -> c_int;
+    pub fn getgroups(size: c_int, list: *mut gid_t) -> c_int;
+    pub fn capget(hdrp: *mut __user_cap_header_struct, datap: *mut __user_cap_data_struct)
+        -> c_int;
+    pub fn capset(
+        hdrp: *mut __user_cap_header_struct,
+        datap: *const __user_cap_data_struct,
+    ) -> c_int;
+    pub fn prctl(option: c_int, ...) -> c_int;
+
+    pub fn clock_gettime(clockid: clockid_t, tp: *mut timespec) -> c_int;
+    pub fn clock_getres(clockid: clockid_t, res: *mut timespec) -> c_int;
+    pub fn clock_settime(clockid: clockid_t, tp: *const timespec) -> c_int;
+    pub fn clock_nanosleep(
+        clockid: clockid_t,
+        flags: c_int,
+        request: *const timespec,
+        remain: *mut timespec,
+    ) -> c_int;
+    pub fn clock_getcpuclockid(pid: pid_t, clockid: *mut clockid_t) -> c_int;
+    pub fn pthread_self() -> pthread_t;
+    pub fn pthread_getcpuclockid(thread: pthread_t, clockid: *mut clockid_t) -> c_int;
+
+    pub fn timer_create(clockid: clockid_t, sevp: *mut sigevent, timerid: *mut timer_t) -> c_int;
+    pub fn timer_settime(
+        timerid: timer_t,
+        flags: c_int,
+        new_value: *const itimerspec,
+        old_value: *mut itimerspec,
+    ) -> c_int;
+    pub fn timer_gettime(timerid: timer_t, curr_value: *mut itimerspec) -> c_int;
+    pub fn timer_getoverrun(timerid: timer_t) -> c_int;
+    pub fn timer_delete(timerid: timer_t) -> c_int;
+    pub fn setitimer(which: c_int, new_value: *const itimerval, old_value: *mut itimerval)
+        -> c_int;
+    pub fn getitimer(which: c_int, curr_value: *mut itimerval) -> c_int;
+    pub fn timerfd_create(clockid: clockid_t, flags: c_int) -> c_int;
+    pub fn timerfd_settime(
+        fd: c_int,
+        flags: c_int,
+        new_value: *const itimerspec,
+        old_value: *mut itimerspec,
+    ) -> c_int;
+    pub fn timerfd_gettime(fd: c_int, curr_value: *mut itimerspec) -> c_int;
+
+    pub fn __libc_current_sigrtmin() -> c_int;
+    pub fn __libc_current_sigrtmax() -> c_int;
+    pub fn sigemptyset(set: *mut sigset_t) -> c_int;
+    pub fn sigaddset(set: *mut sigset_t, signum: c_int) -> c_int;
+    pub fn sigprocmask(how: c_int, set: *const sigset_t, oldset: *mut sigset_t) -> c_int;
+    pub fn sigwaitinfo(set: *const sigset_t, info: *mut siginfo_t) -> c_int;
+    pub fn setgroups(size: size_t, list: *const gid_t) -> c_int;
 
     pub fn open(path: *const c_char, oflag: c_int, ...) -> c_int;
+    pub fn openat(dirfd: c_int, path: *const c_char, oflag: c_int, ...) -> c_int;
     pub fn read(fd: c_int, buf: *mut c_void, count: size_t) -> ssize_t;
     pub fn write(fd: c_int, buf: *const c_void, count: size_t) -> ssize_t;
     pub fn close(fd: c_int) -> c_int;
+    pub fn pipe2(fds: *mut c_int, flags: c_int) -> c_int;
+    pub fn dup2(oldfd: c_int, newfd: c_int) -> c_int;
+    pub fn isatty(fd: c_int) -> c_int;
     pub fn lseek(fd: c_int, offset: off_t, whence: c_int) -> off_t;
     pub fn lseek64(fd: c_int, offset: off64_t, whence: c_int) -> off64_t;
+    pub fn ftruncate64(fd: c_int, length: off64_t) -> c_int;
+    pub fn fallocate64(fd: c_int, mode: c_int, offset: off64_t, len: off64_t) -> c_int;
+    pub fn mkostemp(template: *mut c_char, flags: c_int) -> c_int;
+    pub fn mkdtemp(template: *mut c_char) -> *mut c_char;
+    pub fn unlink(path: *const c_char) -> c_int;
+    pub fn unlinkat(dirfd: c_int, path: *const c_char, flags: c_int) -> c_int;
+    pub fn rmdir(path: *const c_char) -> c_int;
+    pub fn rename(old: *const c_char, new: *const c_char) -> c_int;
+    pub fn renameat2(
+        olddirfd: c_int,
+        oldpath: *const c_char,
+        newdirfd: c_int,
+        newpath: *const c_char,
+        flags: c_uint,
+    ) -> c_int;
+    pub fn symlink(target: *const c_char, linkpath: *const c_char) -> c_int;
+    pub fn readlink(path: *const c_char, buf: *mut c_char, bufsiz: size_t) -> ssize_t;
+    pub fn realpath(path: *const c_char, resolved: *mut c_char) -> *mut c_char;
+
+    pub fn mkdir(path: *const c_char, mode: mode_t) -> c_int;
+    pub fn mkdirat(dirfd: c_int, path: *const c_char, mode: mode_t) -> c_int;
+    pub fn readlinkat(
+        dirfd: c_int,
+        path: *const c_char,
+        buf: *mut c_char,
+        bufsiz: size_t,
+    ) -> ssize_t;
+
+    pub fn stat64(path: *const c_char, buf: *mut stat64) -> c_int;
+    pub fn lstat64(path: *const c_char, buf: *mut stat64) -> c_int;
+    pub fn fstat64(fd: c_int, buf: *mut stat64) -> c_int;
+    pub fn fstatat64(dirfd: c_int, path: *const c_char, buf: *mut stat64, flags: c_int) -> c_int;
+    pub fn linkat(
+        olddirfd: c_int,
+        oldpath: *const c_char,
+        newdirfd: c_int,
+        newpath: *const c_char,
+        flags: c_int,
+    ) -> c_int;
+
+    pub fn chmod(path: *const c_char, mode: mode_t) -> c_int;
+    pub fn fchmod(fd: c_int, mode: mode_t) -> c_int;
+    pub fn fchmodat(dirfd: c_int, path: *const c_char, mode: mode_t, flags: c_int) -> c_int;
+    pub fn chown(path: *const c_char, owner: uid_t, group: gid_t) -> c_int;
+    pub fn fchown(fd: c_int, owner: uid_t, group: gid_t) -> c_int;
+    pub fn lchown(path: *const c_char, owner: uid_t, group: gid_t) -> c_int;
+    pub fn fchownat(
+        dirfd: c_int,
+        path: *const c_char,
+        owner: uid_t,
+        group: gid_t,
+        flags: c_int,
+    ) -> c_int;
+    pub fn utimensat(
+        dirfd: c_int,
+        path: *const c_char,
+        times: *const timespec,
+        flags: c_int,
+    ) -> c_int;
+    pub fn futimens(fd: c_int, times: *const timespec) -> c_int;
+    pub fn umask(mask: mode_t) -> mode_t;
+    pub fn access(path: *const c_char, mode: c_int) -> c_int;
+    pub fn faccessat(dirfd: c_int, path: *const c_char, mode: c_int, flags: c_int) -> c_int;
+
+    pub fn getxattr(
+        path: *const c_char,
+        name: *const c_char,
+        value: *mut c_void,
+        size: size_t,
+    ) -> ssize_t;
+    pub fn lgetxattr(
+        path: *const c_char,
+        name: *const c_char,
+        value: *mut c_void,
+        size: size_t,
+    ) -> ssize_t;
+    pub fn fgetxattr(fd: c_int, name: *const c_char, value: *mut c_void, size: size_t) -> ssize_t;
+    pub fn setxattr(
+        path: *const c_char,
+        name: *const c_char,
+        value: *const c_void,
+        size: size_t,
+        flags: c_int,
+    ) -> c_int;
+    pub fn lsetxattr(
+        path: *const c_char,
+        name: *const c_char,
+        value: *const c_void,
+        size: size_t,
+        flags: c_int,
+    ) -> c_int;
+    pub fn fsetxattr(
+        fd: c_int,
+        name: *const c_char,
+        value: *const c_void,
+        size: size_t,
+        flags: c_int,
+    ) -> c_int;
+    pub fn listxattr(path: *const c_char, list: *mut c_char, size: size_t) -> ssize_t;
+    pub fn llistxattr(path: *const c_char, list: *mut c_char, size: size_t) -> ssize_t;
+    pub fn flistxattr(fd: c_int, list: *mut c_char, size: size_t) -> ssize_t;
+    pub fn removexattr(path: *const c_char, name: *const c_char) -> c_int;
+    pub fn lremovexattr(path: *const c_char, name: *const c_char) -> c_int;
+    pub fn fremovexattr(fd: c_int, name: *const c_char) -> c_int;
+
+    pub fn statvfs64(path: *const c_char, buf: *mut statvfs64) -> c_int;
+    pub fn fstatvfs64(fd: c_int, buf: *mut statvfs64) -> c_int;
+    pub fn statfs64(path: *const c_char, buf: *mut statfs64) -> c_int;
+    pub fn fstatfs64(fd: c_int, buf: *mut statfs64) -> c_int;
+
+    pub fn fdopendir(fd: c_int) -> *mut DIR;
+    pub fn readdir64(dirp: *mut DIR) -> *mut dirent64;
+    pub fn closedir(dirp: *mut DIR) -> c_int;
+
+    pub fn fsync(fd: c_int) -> c_int;
+    pub fn fdatasync(fd: c_int) -> c_int;
+    pub fn sync_file_range(fd: c_int, offset: off64_t, nbytes: off64_t, flags: c_uint) -> c_int;
+    pub fn syncfs(fd: c_int) -> c_int;
+    pub fn posix_fadvise64(fd: c_int, offset: off64_t, len: off64_t, advice: c_int) -> c_int;
+
+    pub fn copy_file_range(
+        fd_in: c_int,
+        off_in: *mut off64_t,
+        fd_out: c_int,
+        off_out: *mut off64_t,
+        len: size_t,
+        flags: c_uint,
+    ) -> ssize_t;
+    pub fn sendfile64(out_fd: c_int, in_fd: c_int, offset: *mut off64_t, count: size_t) -> ssize_t;
+    pub fn splice(
+        fd_in: c_int,
+        off_in: *mut loff_t,
+        fd_out: c_int,
+        off_out: *mut loff_t,
+        len: size_t,
+        flags: c_uint,
+    ) -> ssize_t;
+    pub fn tee(fd_in: c_int, fd_out: c_int, len: size_t, flags: c_uint) -> ssize_t;
+
+    pub fn socket(domain: c_int, ty: c_int, protocol: c_int) -> c_int;
+    pub fn bind(fd: c_int, addr: *const sockaddr, len: socklen_t) -> c_int;
+    pub fn listen(fd: c_int, backlog: c_int) -> c_int;
+    pub fn accept4(fd: c_int, addr: *mut sockaddr, len: *mut socklen_t, flags: c_int) -> c_int;
+    pub fn connect(fd: c_int, addr: *const sockaddr, len: socklen_t) -> c_int;
+    pub fn getsockname(fd: c_int, addr: *mut sockaddr, len: *mut socklen_t) -> c_int;
+    pub fn getpeername(fd: c_int, addr: *mut sockaddr, len: *mut socklen_t) -> c_int;
+    pub fn setsockopt(
+        fd: c_int,
+        level: c_int,
+        name: c_int,
+        value: *const c_void,
+        len: socklen_t,
+    ) -> c_int;
+    pub fn getsockopt(
+        fd: c_int,
+        level: c_int,
+        name: c_int,
+        value: *mut c_void,
+        len: *mut socklen_t,
+    ) -> c_int;
+    pub fn shutdown(fd: c_int, how: c_int) -> c_int;
+    pub fn send(fd: c_int, buf: *const c_void, len: size_t, flags: c_int) -> ssize_t;
+    pub fn recv(fd: c_int, buf: *mut c_void, len: size_t, flags: c_int) -> ssize_t;
+    pub fn sendto(
+        fd: c_int,
+        buf: *const c_void,
+        len: size_t,
+        flags: c_int,
+        addr: *const sockaddr,
+        addrlen: socklen_t,
+    ) -> ssize_t;
+    pub fn recvfrom(
+        fd: c_int,
+        buf: *mut c_void,
+        len: size_t,
+        flags: c_int,
+        addr: *mut sockaddr,
+        addrlen: *mut socklen_t,
+    ) -> ssize_t;
+
+    pub fn getpwnam_r(
+        name: *const c_char,
+        pwd: *mut passwd,
+        buf: *mut c_char,
+        buflen: size_t,
+        result: *mut *mut passwd,
+    ) -> c_int;
+    pub fn getpwuid_r(
+        uid: uid_t,
+        pwd: *mut passwd,
+        buf: *mut c_char,
+        buflen: size_t,
+        result: *mut *mut passwd,
+    ) -> c_int;
+    pub fn setpwent();
+    pub fn getpwent_r(
+        pwd: *mut passwd,
+        buf: *mut c_char,
+        buflen: size_t,
+        result: *mut *mut passwd,
+    ) -> c_int;
+    pub fn endpwent();
+    pub fn getgrnam_r(
+        name: *const c_char,
+        grp: *mut group,
+        buf: *mut c_char,
+        buflen: size_t,
+        result: *mut *mut group,
+    ) -> c_int;
+    pub fn getgrgid_r(
+        gid: gid_t,
+        grp: *mut group,
+        buf: *mut c_char,
+        buflen: size_t,
+        result: *mut *mut group,
+    ) -> c_int;
+    pub fn setgrent();
+    pub fn getgrent_r(
+        grp: *mut group,
+        buf: *mut c_char,
+        buflen: size_t,
+        result: *mut *mut group,
+    ) -> c_int;
+    pub fn endgrent();
+    pub fn getgrouplist(
+        user: *const c_char,
+        group: gid_t,
+        groups: *mut gid_t,
+        ngroups: *mut c_int,
+    ) -> c_int;
+
+    pub fn getaddrinfo(
+        node: *const c_char,
+        service: *const c_char,
+        hints: *const addrinfo,
+        res: *mut *mut addrinfo,
+    ) -> c_int;
+    pub fn freeaddrinfo(res: *mut addrinfo);
+    pub fn getnameinfo(
+        addr: *const sockaddr,
+        addrlen: socklen_t,
+        host: *mut c_char,
+        hostlen: socklen_t,
+        serv: *mut c_char,
+        servlen: socklen_t,
+        flags: c_int,
+    ) -> c_int;
+    pub fn gai_strerror(errcode: c_int) -> *const c_char;
+
+    pub fn free(p: *mut c_void);
+    pub fn system(command: *const c_char) -> c_int;
 
     pub fn strerror(n: c_int) -> *mut c_char;
     pub fn strtol(s: *const c_char, endp: *mut *mut c_char, base: c_int) -> c_long;
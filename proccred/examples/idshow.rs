@@ -0,0 +1,15 @@
+use alt::io;
+use alt::process::credentials::Credentials;
+use anyhow::{Context, Result};
+use std::io::Write;
+
+fn main() -> Result<()> {
+    /* getresuid(), getresgid(), the current file-system IDs (queried
+     * without changing them) and getgroups() */
+
+    let creds = Credentials::current().context("getresuid/getresgid/getgroups")?;
+
+    writeln!(io::stdout(), "{}", creds)?;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use alt::fs::File;
+use alt::io;
+use alt::os::fd::FromRawFd;
+use alt::process;
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::mem::ManuallyDrop;
+
+/* Demonstrate that user-space buffers are duplicated by fork(). Run with
+ * stdout redirected to a file and "Hello world" appears twice, since both
+ * parent and child flush their copy of the buffer when they exit. Flushing
+ * before the fork(), or discarding the buffer in the child, avoids this. */
+
+fn main() -> Result<()> {
+    /* Like stdio, alt::io::stdout() buffers by line on a terminal and
+     * fully otherwise */
+
+    writeln!(io::stdout(), "Hello world")?;
+
+    /* Write to fd 1 directly, bypassing the buffer. The File only borrows
+     * the descriptor, so it must never be dropped and close it */
+
+    let raw_stdout = ManuallyDrop::new(unsafe { File::from_raw_fd(1) });
+    (&*raw_stdout).write_all(b"Ciao\n").context("write")?;
+
+    unsafe { process::fork() }.context("fork")?;
+
+    /* Both child and parent continue execution here */
+
+    Ok(())
+}
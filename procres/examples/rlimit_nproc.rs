@@ -0,0 +1,71 @@
+use alt::io;
+use alt::limits::{self, Resource, Rlimit};
+use alt::process::{self, Fork};
+use alt::time::Clock;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use std::time::Duration;
+use tlpi::{get_long, usage_err, GN_NONNEG};
+
+/* Set the soft and hard limits on the number of processes of our real
+ * user ID, then create children until fork() fails. The limit counts
+ * every process of the user, not just our children, so fewer than
+ * "soft-limit" children are usually created */
+
+fn parse_limit(arg: &str, name: &str) -> Result<Option<u64>> {
+    if arg == "i" {
+        Ok(None)
+    } else {
+        Ok(Some(get_long(arg, GN_NONNEG, name)? as u64))
+    }
+}
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() < 2 || argv.len() > 3 || argv[1] == "--help" {
+        usage_err!(
+            "{} soft-limit [hard-limit]\n       (use 'i' for infinity)\n",
+            argv[0]
+        );
+    }
+
+    let mut out = io::stdout();
+    let rl = limits::getrlimit(Resource::Nproc).context("getrlimit")?;
+    writeln!(out, "Initial maximum process limits: {}", rl)?;
+
+    /* Set new process limits (hard == soft if not specified) */
+
+    let soft = parse_limit(&argv[1], "soft-limit")?;
+    let hard = match argv.get(2) {
+        Some(arg) => parse_limit(arg, "hard-limit")?,
+        None => soft,
+    };
+    limits::setrlimit(Resource::Nproc, &Rlimit::new(soft, hard)).context("setrlimit")?;
+
+    let rl = limits::getrlimit(Resource::Nproc).context("getrlimit")?;
+    writeln!(out, "New maximum process limits:     {}", rl)?;
+    out.flush()?;
+
+    /* Create as many children as possible */
+
+    for j in 1.. {
+        match unsafe { process::fork() } {
+            Err(e) => {
+                writeln!(out, "fork: {}", e)?;
+                writeln!(out, "Limit reached")?;
+                break;
+            }
+            Ok(Fork::Child) => {
+                Clock::MONOTONIC.sleep(Duration::from_secs(3)).ok();
+                unsafe { libc::_exit(libc::EXIT_SUCCESS) };
+            }
+            Ok(Fork::Parent(pid)) => {
+                writeln!(out, "Child {} (PID={}) started", j, pid)?;
+                out.flush()?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use alt::io;
+use alt::process::{self, Fork, WaitFlags};
+use anyhow::{Context, Result};
+use std::env;
+use std::ffi::CString;
+use std::io::Write;
+use std::os::unix::ffi::OsStringExt;
+use std::ptr;
+use tlpi::{err_exit2, print_rusage, usage_err};
+
+/* Execute a command and then print details of the resources that it used,
+ * as reported by wait4(), along with the real time it took */
+
+fn main() -> Result<()> {
+    let argv: Vec<CString> = env::args_os()
+        .map(|arg| CString::new(arg.into_vec()))
+        .collect::<Result<_, _>>()
+        .context("argument contains a null byte")?;
+    if argv.len() < 2 || argv[1].as_bytes() == b"--help" {
+        usage_err!("{} command arg...\n", argv[0].to_string_lossy());
+    }
+
+    let start = process::times().context("times")?;
+
+    match unsafe { process::fork() }.context("fork")? {
+        Fork::Child => {
+            let mut args: Vec<_> = argv[1..].iter().map(|arg| arg.as_ptr()).collect();
+            args.push(ptr::null());
+            unsafe { libc::execvp(args[0], args.as_ptr()) };
+            err_exit2!("execvp");
+            unreachable!();
+        }
+        Fork::Parent(pid) => {
+            let mut out = io::stdout();
+            writeln!(out, "Command PID: {}", pid)?;
+
+            let child = process::wait4(pid, WaitFlags::empty())
+                .context("wait4")?
+                .context("wait4 returned no child")?;
+            let end = process::times().context("times")?;
+
+            writeln!(out, "Command {}", child.status)?;
+            writeln!(out)?;
+            writeln!(
+                out,
+                "\tElapsed real time:       {:.3}",
+                (end.elapsed - start.elapsed).as_secs_f64()
+            )?;
+            print_rusage("\t", &child.rusage)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use alt::net::{SockType, UdpSocket};
+use anyhow::{Context, Result};
+use sockets::id_echo::{BUF_SIZE, SERVICE};
+use sockets::inet_connect;
+use std::env;
+use tlpi::{fatal, usage_err};
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() < 2 || argv[1] == "--help" {
+        usage_err!("{}: host msg...\n", argv[0]);
+    }
+
+    /* Construct server address from first command-line argument */
+
+    let sock: UdpSocket = inet_connect(&argv[1], SERVICE, SockType::Dgram)
+        .context("Could not connect to server socket")?;
+
+    /* Send remaining command-line arguments to server as separate datagrams */
+
+    let mut buf = [0; BUF_SIZE];
+    for msg in &argv[2..] {
+        match sock.send(msg.as_bytes()) {
+            Ok(n) if n == msg.len() => {}
+            _ => fatal!("partial/failed write"),
+        }
+
+        let num_read = sock.recv(&mut buf).context("read")?;
+        println!(
+            "[{} bytes] {}",
+            num_read,
+            String::from_utf8_lossy(&buf[..num_read])
+        );
+    }
+
+    Ok(())
+}
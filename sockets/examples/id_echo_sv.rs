@@ -0,0 +1,31 @@
+use alt::net::{SockType, UdpSocket};
+use anyhow::{Context, Result};
+use sockets::id_echo::{BUF_SIZE, SERVICE};
+use sockets::{inet_address_str, inet_bind};
+use std::env;
+use tlpi::{err_msg, usage_err};
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() > 1 && argv[1] == "--help" {
+        usage_err!("{}\n", argv[0]);
+    }
+
+    /* Unlike the original, stay in the foreground and report errors on
+     * stderr rather than via syslog */
+
+    let sock: UdpSocket =
+        inet_bind(SERVICE, SockType::Dgram).context("Could not create server socket")?;
+
+    /* Receive datagrams and return copies to senders */
+
+    let mut buf = [0; BUF_SIZE];
+    loop {
+        let (num_read, claddr) = sock.recv_from(&mut buf).context("recvfrom")?;
+
+        match sock.send_to(&buf[..num_read], claddr) {
+            Ok(n) if n == num_read => {}
+            _ => err_msg!("Error echoing response to {}", inet_address_str(&claddr)),
+        }
+    }
+}
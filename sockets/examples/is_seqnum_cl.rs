@@ -0,0 +1,55 @@
+use alt::net::{LookupOptions, SockType, SocketAddr, TcpStream};
+use anyhow::{Context, Result};
+use sockets::is_seqnum::{INT_LEN, PORT_NUM};
+use sockets::read_line;
+use std::env;
+use std::io::Write;
+use tlpi::{fatal, usage_err};
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() < 2 || argv[1] == "--help" {
+        usage_err!("{} server-host [sequence-len]\n", argv[0]);
+    }
+
+    /* Call getaddrinfo() to obtain a list of addresses that
+     * we can try connecting to */
+
+    let addrs: Vec<SocketAddr> = LookupOptions::new()
+        .socktype(SockType::Stream)
+        .numeric_serv(true)
+        .lookup(Some(&argv[1]), Some(PORT_NUM))
+        .context("getaddrinfo")?
+        .collect();
+
+    /* Walk through returned list until we find an address structure
+     * that can be used to successfully connect a socket */
+
+    let mut stream =
+        TcpStream::connect(&addrs[..]).context("Could not connect socket to any address")?;
+
+    /* Send requested sequence length, with terminating newline */
+
+    let req_len_str = argv.get(2).map_or("1", |s| s.as_str());
+    if stream.write_all(req_len_str.as_bytes()).is_err() {
+        fatal!("Partial/failed write (reqLenStr)");
+    }
+    if stream.write_all(b"\n").is_err() {
+        fatal!("Partial/failed write (newline)");
+    }
+
+    /* Read and display sequence number returned by server */
+
+    let mut seq_num_str = [0; INT_LEN];
+    let num_read = read_line(&stream, &mut seq_num_str).context("readLine")?;
+    if num_read == 0 {
+        fatal!("Unexpected EOF from server");
+    }
+
+    print!(
+        "Sequence number: {}",
+        String::from_utf8_lossy(&seq_num_str[..num_read])
+    );
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use alt::net::{LookupOptions, SockType, SocketAddr, TcpListener};
+use anyhow::{Context, Result};
+use sockets::is_seqnum::{INT_LEN, PORT_NUM};
+use sockets::{inet_address_str, read_line};
+use std::env;
+use std::io::Write;
+use std::str;
+use tlpi::{err_msg, get_int, usage_err};
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() > 1 && argv[1] == "--help" {
+        usage_err!("{} [init-seq-num]\n", argv[0]);
+    }
+
+    let mut seq_num: i32 = match argv.get(1) {
+        Some(arg) => get_int(arg, 0, "init-seq-num")?,
+        None => 0,
+    };
+
+    /* Call getaddrinfo() to obtain a list of addresses that
+     * we can try binding to */
+
+    let addrs: Vec<SocketAddr> = LookupOptions::new()
+        .socktype(SockType::Stream)
+        .passive(true) /* Wildcard IP address */
+        .numeric_serv(true) /* Service is numeric */
+        .lookup(None, Some(PORT_NUM))
+        .context("getaddrinfo")?
+        .collect();
+
+    /* Walk through returned list until we find an address structure
+     * that can be used to successfully create and bind a socket */
+
+    let listener = TcpListener::bind(&addrs[..]).context("Could not bind socket to any address")?;
+
+    /* Handle clients iteratively */
+
+    loop {
+        let (mut stream, claddr) = match listener.accept() {
+            Ok(conn) => conn,
+            Err(_) => {
+                err_msg!("accept");
+                continue;
+            }
+        };
+
+        println!("Connection from {}", inet_address_str(&claddr));
+
+        /* Read client request, send sequence number back */
+
+        let mut req_len_str = [0; INT_LEN];
+        let num_read = match read_line(&stream, &mut req_len_str) {
+            Ok(n) if n > 0 => n,
+            _ => continue, /* Failed read; skip request */
+        };
+
+        let req_len: i32 = str::from_utf8(&req_len_str[..num_read])
+            .ok()
+            .and_then(|s| s.trim_end().parse().ok())
+            .unwrap_or(0);
+        if req_len <= 0 {
+            /* Watch for misbehaving clients */
+            continue; /* Bad request; skip it */
+        }
+
+        let seq_num_str = format!("{}\n", seq_num);
+        if stream.write_all(seq_num_str.as_bytes()).is_err() {
+            err_msg!("Error on write");
+        }
+
+        seq_num = seq_num.wrapping_add(req_len); /* Update sequence number */
+    }
+}
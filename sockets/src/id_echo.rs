@@ -0,0 +1,4 @@
+pub const SERVICE: &str = "echo"; /* Name of UDP service */
+
+pub const BUF_SIZE: usize = 500; /* Maximum size of datagrams that can
+                                 be read by client and server */
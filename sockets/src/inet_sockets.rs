@@ -0,0 +1,105 @@
+use alt::net::{LookupOptions, NameInfoOptions, SockType, SocketAddr};
+use alt::os::fd::{FromRawFd, IntoRawFd};
+use alt::sys::net::{setsockopt, Socket};
+use libc::c_int;
+use std::io;
+
+fn raw_type(ty: SockType) -> c_int {
+    match ty {
+        SockType::Stream => libc::SOCK_STREAM,
+        SockType::Dgram => libc::SOCK_DGRAM,
+    }
+}
+
+/* Create a socket of the given 'ty' connected to the address resolved from
+ * 'host' and 'service'. Each returned address is tried in turn until one
+ * succeeds, and the error from the last attempt is returned otherwise. */
+
+pub fn inet_connect<T: FromRawFd>(host: &str, service: &str, ty: SockType) -> io::Result<T> {
+    let addrs = LookupOptions::new()
+        .socktype(ty)
+        .lookup(Some(host), Some(service))?;
+
+    let mut last_err = None;
+    for addr in addrs {
+        let sock = match Socket::new(&addr, raw_type(ty)) {
+            Ok(sock) => sock,
+            Err(e) => {
+                last_err = Some(e);
+                continue; /* On error, try next address */
+            }
+        };
+
+        match sock.connect(&addr) {
+            Ok(()) => return Ok(unsafe { T::from_raw_fd(sock.into_raw_fd()) }),
+            Err(e) => last_err = Some(e),
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| io::Error::from_raw_os_error(libc::EINVAL)))
+}
+
+/* Create an Internet domain socket and bind it to the wildcard address
+ * for 'service'. If 'backlog' is given, mark the socket as listening with
+ * that backlog (and set SO_REUSEADDR first). */
+
+fn inet_passive_socket(service: &str, ty: SockType, backlog: Option<c_int>) -> io::Result<Socket> {
+    let addrs = LookupOptions::new()
+        .socktype(ty)
+        .passive(true) /* Use wildcard address */
+        .lookup(None, Some(service))?;
+
+    let mut last_err = None;
+    for addr in addrs {
+        let sock = match Socket::new(&addr, raw_type(ty)) {
+            Ok(sock) => sock,
+            Err(e) => {
+                last_err = Some(e);
+                continue;
+            }
+        };
+
+        if backlog.is_some() {
+            setsockopt(&sock, libc::SOL_SOCKET, libc::SO_REUSEADDR, 1 as c_int)?;
+        }
+
+        if let Err(e) = sock.bind(&addr) {
+            last_err = Some(e);
+            continue; /* Bind failed: close this socket
+                      and try next address */
+        }
+
+        if let Some(backlog) = backlog {
+            sock.listen(backlog)?;
+        }
+
+        return Ok(sock);
+    }
+
+    Err(last_err.unwrap_or_else(|| io::Error::from_raw_os_error(libc::EINVAL)))
+}
+
+/* Create stream socket, bound to wildcard address on port 'service', and
+ * listening with the given 'backlog' */
+
+pub fn inet_listen<T: FromRawFd>(service: &str, backlog: c_int) -> io::Result<T> {
+    let sock = inet_passive_socket(service, SockType::Stream, Some(backlog))?;
+    Ok(unsafe { T::from_raw_fd(sock.into_raw_fd()) })
+}
+
+/* Create socket bound to wildcard IP address + port given in 'service' */
+
+pub fn inet_bind<T: FromRawFd>(service: &str, ty: SockType) -> io::Result<T> {
+    let sock = inet_passive_socket(service, ty, None)?;
+    Ok(unsafe { T::from_raw_fd(sock.into_raw_fd()) })
+}
+
+/* Given a socket address, return a string containing the host name and
+ * service name (or port number) in the form "(host, service)" */
+
+pub fn inet_address_str(addr: &SocketAddr) -> String {
+    match NameInfoOptions::new().numeric_serv(true).lookup(addr) {
+        Ok((host, service)) => format!("({}, {})", host, service),
+        Err(_) => String::from("(?UNKNOWN?)"),
+    }
+}
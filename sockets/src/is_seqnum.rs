@@ -0,0 +1,4 @@
+pub const PORT_NUM: &str = "50000"; /* Port number for server */
+
+pub const INT_LEN: usize = 30; /* Size of string able to hold largest
+                               integer (including terminating '\n') */
@@ -0,0 +1,7 @@
+pub mod id_echo;
+mod inet_sockets;
+pub mod is_seqnum;
+mod read_line;
+
+pub use inet_sockets::*;
+pub use read_line::*;
@@ -0,0 +1,40 @@
+use std::io::{self, Read};
+
+/* Read characters from 'reader' until a newline is encountered. If a newline
+ * character is not encountered in the first buf.len() bytes, then the excess
+ * characters are discarded. The returned bytes include the newline
+ * character if it was read. Return the number of bytes placed in 'buf';
+ * 0 means end-of-file was reached before any byte was read. */
+
+pub fn read_line<R: Read>(mut reader: R, buf: &mut [u8]) -> io::Result<usize> {
+    if buf.is_empty() {
+        return Err(io::Error::from_raw_os_error(libc::EINVAL));
+    }
+
+    let mut tot_read = 0;
+    loop {
+        let mut ch = [0; 1];
+        let num_read = match reader.read(&mut ch) {
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        if num_read == 0 {
+            /* EOF */
+            break;
+        }
+
+        if tot_read < buf.len() {
+            /* Discard > buf.len() bytes */
+            buf[tot_read] = ch[0];
+            tot_read += 1;
+        }
+
+        if ch[0] == b'\n' {
+            break;
+        }
+    }
+
+    Ok(tot_read)
+}
@@ -0,0 +1,124 @@
+use alt::io;
+use alt::limits;
+use alt::process;
+use alt::sys_info::{self, MemInfo, ProcStat, ProcStatus};
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use tlpi::{get_int, usage_err, GN_GT_0};
+
+/* Show what ps(1) and friends would report about a process (by default
+ * ourselves), all taken from /proc, followed by system-wide memory use */
+
+const MB: u64 = 1024 * 1024;
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() > 2 || argv.get(1).is_some_and(|arg| arg == "--help") {
+        usage_err!("{} [pid]\n", argv[0]);
+    }
+    let pid = match argv.get(1) {
+        Some(arg) => get_int(arg, GN_GT_0, "pid")? as u32,
+        None => process::id(),
+    };
+
+    let status = ProcStatus::of(pid).context("reading status")?;
+    let stat = ProcStat::of(pid).context("reading stat")?;
+
+    let mut out = io::stdout();
+    writeln!(
+        out,
+        "PID {} ({}), state {}",
+        stat.pid, stat.comm, stat.state
+    )?;
+    writeln!(
+        out,
+        "  PPID {}; PGID {}; SID {}",
+        stat.ppid, stat.pgrp, stat.session
+    )?;
+    writeln!(
+        out,
+        "  UIDs: real={} eff={} saved={} fs={}",
+        status.uid.real, status.uid.effective, status.uid.saved, status.fsuid
+    )?;
+    writeln!(
+        out,
+        "  Threads: {}; last ran on CPU {}",
+        stat.num_threads, stat.processor
+    )?;
+    writeln!(
+        out,
+        "  CPU time: user={:.2}s system={:.2}s",
+        stat.user_time.as_secs_f64(),
+        stat.system_time.as_secs_f64()
+    )?;
+    writeln!(
+        out,
+        "  Memory: virtual={} kB resident={} kB",
+        stat.vsize / 1024,
+        stat.rss * limits::page_size() as u64 / 1024
+    )?;
+
+    match sys_info::fds(pid) {
+        Ok(fds) => {
+            writeln!(out, "Open files:")?;
+            for entry in fds {
+                writeln!(out, "  {:4} -> {}", entry.fd, entry.target.display())?;
+            }
+        }
+        Err(e) => writeln!(out, "Open files: {}", e)?,
+    }
+
+    match sys_info::maps(pid) {
+        Ok(maps) => {
+            writeln!(out, "Mappings:")?;
+            for map in maps {
+                let perms = [
+                    if map.readable { 'r' } else { '-' },
+                    if map.writable { 'w' } else { '-' },
+                    if map.executable { 'x' } else { '-' },
+                    if map.shared { 's' } else { 'p' },
+                ];
+                writeln!(
+                    out,
+                    "  {:012x} {:8} kB {} {}",
+                    map.start,
+                    map.len() / 1024,
+                    perms.iter().collect::<String>(),
+                    map.pathname
+                        .as_deref()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                )?;
+            }
+        }
+        Err(e) => writeln!(out, "Mappings: {}", e)?,
+    }
+
+    let mem = MemInfo::current().context("reading meminfo")?;
+    let info = sys_info::sysinfo().context("sysinfo")?;
+    writeln!(
+        out,
+        "System: up {}s, {} threads, load {:.2} {:.2} {:.2}",
+        info.uptime.as_secs(),
+        info.procs,
+        info.loads[0],
+        info.loads[1],
+        info.loads[2]
+    )?;
+    writeln!(
+        out,
+        "  Memory: {} MB total, {} MB free, {} MB available",
+        mem.total / MB,
+        mem.free / MB,
+        mem.available / MB
+    )?;
+    writeln!(
+        out,
+        "  Swap: {} MB total, {} MB free",
+        mem.swap_total / MB,
+        mem.swap_free / MB
+    )?;
+
+    Ok(())
+}
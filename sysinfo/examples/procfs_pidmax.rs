@@ -0,0 +1,45 @@
+use alt::fs::OpenOptions;
+use alt::io;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+/* Display the limit on PIDs from /proc/sys/kernel/pid_max, and if a new
+ * value is given on the command line, set it (which requires privilege) */
+
+const PID_MAX: &str = "/proc/sys/kernel/pid_max";
+const MAX_LINE: usize = 100;
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(argv.len() > 1)
+        .open(PID_MAX)
+        .context("open")?;
+
+    let mut line = [0; MAX_LINE];
+    let n = file.read(&mut line).context("read")?;
+
+    let mut out = io::stdout();
+    if argv.len() > 1 {
+        write!(out, "Old value: ")?;
+    }
+    out.write_all(&line[..n])?;
+
+    if argv.len() > 1 {
+        file.seek(SeekFrom::Start(0)).context("lseek")?;
+        file.write_all(argv[1].as_bytes()).context("write")?;
+
+        let mut value = String::new();
+        OpenOptions::new()
+            .read(true)
+            .open(PID_MAX)
+            .and_then(|mut file| file.read_to_string(&mut value))
+            .context("read")?;
+        write!(out, "{} now contains {}", PID_MAX, value)?;
+    }
+
+    Ok(())
+}
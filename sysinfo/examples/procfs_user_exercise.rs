@@ -0,0 +1,54 @@
+use alt::fs;
+use alt::io;
+use alt::sys_info::ProcStatus;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use tlpi::{cmd_line_err, usage_err, user_id_from_name};
+
+/* List the PID and command name of each process whose real user ID is
+ * that of the user named on the command line, by scanning the Uid line
+ * of /proc/PID/status */
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() != 2 || argv[1] == "--help" {
+        usage_err!("{} username\n", argv[0]);
+    }
+
+    let Some(uid) = user_id_from_name(&argv[1]) else {
+        cmd_line_err!("Bad username: {}\n", argv[1]);
+        return Ok(());
+    };
+
+    let mut out = io::stdout();
+    for entry in fs::read_dir("/proc").context("opendir")? {
+        let entry = entry.context("readdir")?;
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse().ok())
+        else {
+            continue; /* Not a process directory */
+        };
+
+        /* The process may have terminated since we read the directory */
+
+        let status = match ProcStatus::of(pid) {
+            Ok(status) => status,
+            Err(e)
+                if e.raw_os_error() == Some(libc::ENOENT)
+                    || e.raw_os_error() == Some(libc::ESRCH) =>
+            {
+                continue
+            }
+            Err(e) => return Err(e).with_context(|| format!("/proc/{}/status", pid)),
+        };
+
+        if status.uid.real == uid {
+            writeln!(out, "{:5} {}", pid, status.name)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use alt::io;
+use alt::sys_info;
+use anyhow::{Context, Result};
+use std::io::Write;
+
+fn main() -> Result<()> {
+    let uts = sys_info::uname().context("uname")?;
+
+    let mut out = io::stdout();
+    writeln!(out, "Node name:   {}", uts.nodename)?;
+    writeln!(out, "System name: {}", uts.sysname)?;
+    writeln!(out, "Release:     {}", uts.release)?;
+    writeln!(out, "Version:     {}", uts.version)?;
+    writeln!(out, "Machine:     {}", uts.machine)?;
+    writeln!(out, "Domain name: {}", uts.domainname)?;
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use alt::io;
+use alt::limits::{self, PathVar};
+use anyhow::{Context, Result};
+use std::io::Write;
+
+/* Print an fpathconf() value for standard input, which may be redirected
+ * from a file, directory or pipe to see how the limits differ */
+
+fn fpathconf_print(out: &mut impl Write, var: PathVar) -> Result<()> {
+    let msg = format!("{}:", var);
+    match limits::fpathconf(&io::stdin(), var).with_context(|| format!("fpathconf {}", var))? {
+        Some(lim) => writeln!(out, "{:<14}{}", msg, lim)?,
+        None => writeln!(out, "{:<14}(indeterminate)", msg)?,
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let mut out = io::stdout();
+    fpathconf_print(&mut out, PathVar::NameMax)?;
+    fpathconf_print(&mut out, PathVar::PathMax)?;
+    fpathconf_print(&mut out, PathVar::PipeBuf)?;
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use alt::io;
+use alt::limits::{self, SysVar};
+use anyhow::{Context, Result};
+use std::io::Write;
+
+/* Print a sysconf() value, or note that the limit is indeterminate */
+
+fn sysconf_print(out: &mut impl Write, var: SysVar) -> Result<()> {
+    let msg = format!("{}:", var);
+    match limits::sysconf(var).with_context(|| format!("sysconf {}", var))? {
+        Some(lim) => writeln!(out, "{:<22}{}", msg, lim)?,
+        None => writeln!(out, "{:<22}(indeterminate)", msg)?,
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let mut out = io::stdout();
+    for var in [
+        SysVar::ArgMax,
+        SysVar::LoginNameMax,
+        SysVar::OpenMax,
+        SysVar::NgroupsMax,
+        SysVar::PageSize,
+        SysVar::RtsigMax,
+        SysVar::ClkTck,
+        SysVar::NprocessorsOnln,
+    ] {
+        sysconf_print(&mut out, var)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use alt::io;
+use alt::time::calendar::{self, Tm};
+use alt::time::Clock;
+use anyhow::{Context, Result};
+use std::io::Write;
+
+const SECONDS_IN_TROPICAL_YEAR: f64 = 365.24219 * 24.0 * 60.0 * 60.0;
+
+fn print_tm(out: &mut impl Write, tm: &Tm) -> Result<()> {
+    /* Print the fields as they are in a struct tm: years since 1900 and
+     * months from 0 */
+
+    write!(
+        out,
+        "  year={} mon={} mday={} hour={} min={} sec={} ",
+        tm.year - 1900,
+        tm.month - 1,
+        tm.day,
+        tm.hour,
+        tm.minute,
+        tm.second
+    )?;
+    writeln!(
+        out,
+        "wday={} yday={} isdst={}",
+        tm.weekday, tm.yearday, tm.is_dst as i32
+    )?;
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let mut out = io::stdout();
+
+    /* Retrieve, convert, and display various forms of calendar time */
+
+    let now = Clock::REALTIME.now().context("clock_gettime")?;
+    let t = now.sec();
+    write!(out, "Seconds since the Epoch (1 Jan 1970): {}", t)?;
+    writeln!(
+        out,
+        " (about {:6.3} years)",
+        t as f64 / SECONDS_IN_TROPICAL_YEAR
+    )?;
+
+    writeln!(
+        out,
+        "  clock_gettime() returned {} secs, {} microsecs",
+        now.sec(),
+        now.subsec_nanos() / 1000
+    )?;
+
+    let gm = calendar::gmtime(t).context("gmtime")?;
+    writeln!(out, "Broken down by gmtime():")?;
+    print_tm(&mut out, &gm)?;
+
+    let loc = calendar::localtime(t).context("localtime")?;
+    writeln!(out, "Broken down by localtime():")?;
+    print_tm(&mut out, &loc)?;
+    writeln!(out)?;
+
+    writeln!(
+        out,
+        "asctime() formats the gmtime() value as: {}",
+        gm.format("%c")
+    )?;
+    writeln!(
+        out,
+        "ctime() formats the time() value as:     {}",
+        loc.format("%c")
+    )?;
+
+    writeln!(
+        out,
+        "mktime() of gmtime() value:    {} secs",
+        calendar::mktime(&gm)
+    )?;
+    writeln!(
+        out,
+        "mktime() of localtime() value: {} secs",
+        calendar::mktime(&loc)
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use alt::io;
+use alt::time::calendar;
+use alt::time::Clock;
+use anyhow::{Context, Result};
+use std::io::Write;
+
+fn main() -> Result<()> {
+    /* Only the C locale is supported, so names are always in English */
+
+    let mut out = io::stdout();
+
+    let t = Clock::REALTIME.now().context("clock_gettime")?.sec();
+    let loc = calendar::localtime(t).context("localtime")?;
+
+    writeln!(out, "ctime() of time() value is:  {}", loc.format("%c"))?;
+    writeln!(out, "asctime() of local time is:  {}", loc.format("%c"))?;
+    writeln!(
+        out,
+        "strftime() of local time is: {}",
+        loc.format("%A, %d %B %Y, %H:%M:%S %Z")
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use alt::io;
+use alt::time::calendar::{self, Tm};
+use anyhow::Result;
+use std::env;
+use std::io::Write;
+use tlpi::{fatal, usage_err};
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+
+    if argv.len() < 3 || argv[1] == "--help" {
+        usage_err!("{} input-date-time in-format [out-format]\n", argv[0]);
+    }
+
+    let mut tm = match Tm::parse(&argv[1], &argv[2]) {
+        Ok(tm) => tm,
+        Err(_) => {
+            fatal!("strptime");
+            return Ok(());
+        }
+    };
+
+    /* mktime() works out whether DST is in effect, and like it, we fill
+     * in the rest of the broken-down time for strftime() */
+
+    let t = calendar::mktime(&tm);
+    if let Some(loc) = calendar::localtime(t) {
+        tm = loc;
+    }
+
+    let mut out = io::stdout();
+    writeln!(out, "calendar time (seconds since Epoch): {}", t)?;
+
+    let ofmt = argv
+        .get(3)
+        .map_or("%H:%M:%S %A, %d %B %Y %Z", |s| s.as_str());
+    writeln!(out, "strftime() yields: {}", tm.format(ofmt))?;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use alt::io;
+use alt::time::{Clock, TimerFd, TimerFlags};
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use tlpi::{get_int, itimerspec_from_str, usage_err, GN_GT_0};
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+
+    if argv.len() < 2 || argv[1] == "--help" {
+        usage_err!(
+            "{} secs[/nsecs][:int-secs[/int-nsecs]] [max-exp]\n",
+            argv[0]
+        );
+    }
+
+    let ts = match itimerspec_from_str(&argv[1]) {
+        Some(ts) => ts,
+        None => {
+            usage_err!(
+                "{} secs[/nsecs][:int-secs[/int-nsecs]] [max-exp]\n",
+                argv[0]
+            );
+            return Ok(());
+        }
+    };
+    let max_exp = match argv.get(2) {
+        Some(arg) => get_int(arg, GN_GT_0, "max-exp")? as u64,
+        None => 1,
+    };
+
+    let fd = TimerFd::new(Clock::REALTIME).context("timerfd_create")?;
+    fd.set(&ts, TimerFlags::empty())
+        .context("timerfd_settime")?;
+
+    let start = Clock::MONOTONIC.now().context("clock_gettime")?;
+
+    let mut out = io::stdout();
+    let mut total_exp = 0;
+    while total_exp < max_exp {
+        /* Read number of expirations on the timer, and then display
+         * time elapsed since timer was started, followed by number
+         * of expirations read and total expirations so far. */
+
+        let num_exp = fd.read().context("read")?;
+        total_exp += num_exp;
+
+        let now = Clock::MONOTONIC.now().context("clock_gettime")?;
+        let elapsed = now - start;
+        writeln!(
+            out,
+            "{}.{:03}: expirations read: {}; total={}",
+            elapsed.sec(),
+            (elapsed.subsec_nanos() + 500_000) / 1_000_000,
+            num_exp,
+            total_exp
+        )?;
+    }
+
+    Ok(())
+}
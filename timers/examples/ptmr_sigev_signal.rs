@@ -0,0 +1,85 @@
+use alt::io;
+use alt::time::{Clock, Notify, Timer, TimerFlags};
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use std::mem::MaybeUninit;
+use tlpi::{curr_time, itimerspec_from_str, usage_err};
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+
+    if argv.len() < 2 {
+        usage_err!("{} secs[/nsecs][:int-secs[/int-nsecs]]...\n", argv[0]);
+    }
+
+    /* Notification signal for the timers. Rather than catching it with
+     * a handler, keep it blocked and accept it with sigwaitinfo(), so
+     * that it is safe to do I/O when it arrives */
+
+    let timer_sig = unsafe { libc::__libc_current_sigrtmax() };
+    let mut mask = MaybeUninit::uninit();
+    unsafe {
+        libc::sigemptyset(mask.as_mut_ptr());
+        libc::sigaddset(mask.as_mut_ptr(), timer_sig);
+    }
+    let mask = unsafe { mask.assume_init() };
+    if unsafe { libc::sigprocmask(libc::SIG_BLOCK, &mask, std::ptr::null_mut()) } == -1 {
+        return Err(std::io::Error::last_os_error()).context("sigprocmask");
+    }
+
+    /* Create and start one timer for each command-line argument, with
+     * its index in the list as the notification value */
+
+    let mut out = io::stdout();
+    let mut tidlist = Vec::new();
+    for (j, arg) in argv[1..].iter().enumerate() {
+        let ts = match itimerspec_from_str(arg) {
+            Some(ts) => ts,
+            None => {
+                usage_err!("{} secs[/nsecs][:int-secs[/int-nsecs]]...\n", argv[0]);
+                return Ok(());
+            }
+        };
+
+        let notify = Notify::Signal {
+            signo: timer_sig,
+            value: j,
+        };
+        let timer = Timer::new(Clock::REALTIME, notify).context("timer_create")?;
+        writeln!(out, "Timer ID: {} ({})", timer.as_raw() as usize, arg)?;
+
+        timer
+            .set(&ts, TimerFlags::empty())
+            .context("timer_settime")?;
+        tidlist.push(timer);
+    }
+
+    loop {
+        let mut si = MaybeUninit::uninit();
+        let sig = unsafe { libc::sigwaitinfo(&mask, si.as_mut_ptr()) };
+        if sig == -1 {
+            let err = std::io::Error::last_os_error();
+            if err.raw_os_error() == Some(libc::EINTR) {
+                continue;
+            }
+            return Err(err).context("sigwaitinfo");
+        }
+        let si = unsafe { si.assume_init() };
+        let j = unsafe { si.si_value().sival_ptr } as usize;
+        let timer = &tidlist[j];
+
+        writeln!(
+            out,
+            "[{}] Got signal {}",
+            curr_time(Some("%T")).unwrap_or_default(),
+            sig
+        )?;
+        writeln!(out, "    *sival_ptr         = {}", timer.as_raw() as usize)?;
+        writeln!(
+            out,
+            "    timer_getoverrun() = {}",
+            timer.overrun().context("timer_getoverrun")?
+        )?;
+    }
+}
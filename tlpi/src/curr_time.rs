@@ -0,0 +1,14 @@
+/* Return a string containing the current local time formatted according
+ * to the specification in 'format' (see strftime(3) for specifiers). If
+ * 'format' is None, we use "%c" as a specifier (which gives the date and
+ * time as for ctime(3), but without the trailing newline). Returns None
+ * on error. */
+
+use alt::time::calendar;
+use alt::time::Clock;
+
+pub fn curr_time(format: Option<&str>) -> Option<String> {
+    let t = Clock::REALTIME.now().ok()?;
+    let tm = calendar::localtime(t.sec())?;
+    Some(tm.format(format.unwrap_or("%c")))
+}
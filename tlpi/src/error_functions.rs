@@ -3,7 +3,7 @@ use libc::EXIT_FAILURE;
 use std::env;
 use std::ffi::CStr;
 use std::fmt::Arguments;
-use std::io::{self, Write};
+use std::io::Write;
 use std::process;
 use super::errors::{ERROR_NAMES, MAX_ERROR_INDEX};
 
@@ -17,12 +17,9 @@ fn terminate(use_exit3: bool) -> ! {
     if use_exit3 {
         process::exit(libc::EXIT_FAILURE);
     } else {
-        unimplemented!("_exit(2)");
-        /*
         unsafe {
             libc::_exit(libc::EXIT_FAILURE);
         }
-        */
     }
 }
 
@@ -31,38 +28,44 @@ fn output_error(
     flush_stdout: bool,
     args: Arguments,
 ) -> Result<()> {
-    if flush_stdout {
-        io::stdout().flush()?;
-    }
-
-    let mut stderr = io::stderr().lock();
-    stderr.write_all(b"ERROR")?;
+    let mut buf = Vec::new();
+    buf.write_all(b"ERROR")?;
 
     if let Some(err) = err {
-        stderr.write_all(b" [")?;
+        buf.write_all(b" [")?;
         {
             let err: usize = err.try_into()?;
             if 0 < err && err <= MAX_ERROR_INDEX {
-                stderr.write_all(ERROR_NAMES[err].as_bytes())?;
+                buf.write_all(ERROR_NAMES[err].as_bytes())?;
             } else {
-                stderr.write_all(b"?UNKNOWN?")?;
+                buf.write_all(b"?UNKNOWN?")?;
             }
         }
-        stderr.write_all(b" ")?;
+        buf.write_all(b" ")?;
         unsafe {
             let err_msg = libc::strerror(err);
             let err_msg = CStr::from_ptr(err_msg);
-            stderr.write_all(err_msg.to_str()?.as_bytes())?;
+            buf.write_all(err_msg.to_str()?.as_bytes())?;
         }
-        stderr.write_all(b"]")?;
+        buf.write_all(b"]")?;
     } else {
-        stderr.write_all(b":")?;
+        buf.write_all(b":")?;
     }
 
-    stderr.write_all(b" ")?;
-    stderr.write_fmt(args)?;
-    stderr.write_all(b"\n")?;
-    stderr.flush()?;
+    buf.write_all(b" ")?;
+    buf.write_fmt(args)?;
+    buf.write_all(b"\n")?;
+
+    if flush_stdout {
+        alt::io::stdout().flush()?; /* Flush any pending stdout */
+    }
+
+    /* Emit the whole message with a single write to fd 2, so that it is
+     * ordered correctly relative to other writes to the same descriptor */
+
+    let mut stderr = alt::io::stderr().lock();
+    stderr.write_all(&buf)?;
+    stderr.flush()?; /* In case stderr is buffered */
 
     Ok(())
 }
@@ -114,9 +117,9 @@ pub fn fatal(args: Arguments) -> Result<()> {
 }
 
 pub fn usage_err(args: Arguments) -> Result<()> {
-    io::stdout().flush()?;
+    alt::io::stdout().flush()?;
 
-    let mut stderr = io::stderr().lock();
+    let mut stderr = alt::io::stderr().lock();
     stderr.write_all(b"Usage: ")?;
     stderr.write_fmt(args)?;
     stderr.flush()?;
@@ -125,9 +128,9 @@ pub fn usage_err(args: Arguments) -> Result<()> {
 }
 
 pub fn cmd_line_err(args: Arguments) -> Result<()> {
-    io::stdout().flush()?;
+    alt::io::stdout().flush()?;
 
-    let mut stderr = io::stderr().lock();
+    let mut stderr = alt::io::stderr().lock();
     stderr.write_all(b"Command-line usage error: ")?;
     stderr.write_fmt(args)?;
     stderr.flush()?;
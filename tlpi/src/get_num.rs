@@ -1,7 +1,7 @@
 use anyhow::Result;
 use libc::c_char;
 use std::ffi::{CStr, CString};
-use std::io::{self, Write};
+use std::io::Write;
 use std::process;
 use std::ptr;
 
@@ -13,7 +13,7 @@ pub const GN_BASE_8: u32 = 0o200;
 pub const GN_BASE_16: u32 = 0o400;
 
 fn gn_fail(fname: &str, msg: &str, arg: &str, name: &str) -> Result<()> {
-    let mut stderr = io::stderr().lock();
+    let mut stderr = alt::io::stderr().lock();
     stderr.write_fmt(format_args!("{} error", fname))?;
     if !name.is_empty() {
         stderr.write_fmt(format_args!(" (in {})", name))?;
@@ -0,0 +1,31 @@
+/* Convert a string of the following form to a TimerSpec:
+ *
+ *     value.sec[/value.nanosec][:interval.sec[/interval.nanosec]]
+ *
+ * Optional components that are omitted are set to zero. Returns None if
+ * any component is not a number, or a nanosecond count is 1000000000 or
+ * more. */
+
+use alt::time::{TimerSpec, Timespec};
+
+fn timespec_from_str(s: &str) -> Option<Timespec> {
+    let (sec, nsec) = match s.split_once('/') {
+        Some((sec, nsec)) => (sec, nsec.parse().ok()?),
+        None => (s, 0),
+    };
+    if nsec >= 1_000_000_000 {
+        return None;
+    }
+    Some(Timespec::new(sec.parse().ok()?, nsec))
+}
+
+pub fn itimerspec_from_str(s: &str) -> Option<TimerSpec> {
+    let (value, interval) = match s.split_once(':') {
+        Some((value, interval)) => (value, timespec_from_str(interval)?),
+        None => (s, Timespec::ZERO),
+    };
+    Some(TimerSpec {
+        value: timespec_from_str(value)?,
+        interval,
+    })
+}
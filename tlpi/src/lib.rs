@@ -1,9 +1,17 @@
+mod curr_time;
 mod errors;
 mod error_functions;
 mod get_num;
+mod itimerspec_from_str;
+mod print_rusage;
+mod ugid_functions;
 
+pub use curr_time::*;
 pub use error_functions::*;
 pub use get_num::*;
+pub use itimerspec_from_str::*;
+pub use print_rusage::*;
+pub use ugid_functions::*;
 
 #[macro_export]
 macro_rules! va_fn {
@@ -0,0 +1,39 @@
+/* Print the contents of the Rusage 'ru', prefixing each line with
+ * 'leader'. Only the fields that Linux maintains are shown. */
+
+use alt::process::Rusage;
+use anyhow::Result;
+use std::io::Write;
+
+pub fn print_rusage(leader: &str, ru: &Rusage) -> Result<()> {
+    let mut out = alt::io::stdout().lock();
+    writeln!(
+        out,
+        "{}CPU time (secs):         user={:.3}; system={:.3}",
+        leader,
+        ru.user_time.as_secs_f64(),
+        ru.system_time.as_secs_f64()
+    )?;
+    writeln!(out, "{}Max resident set size:   {}", leader, ru.max_rss)?;
+    writeln!(
+        out,
+        "{}Page reclaims:           {}",
+        leader, ru.minor_faults
+    )?;
+    writeln!(
+        out,
+        "{}Page faults:             {}",
+        leader, ru.major_faults
+    )?;
+    writeln!(
+        out,
+        "{}Block I/Os:              input={}; output={}",
+        leader, ru.block_inputs, ru.block_outputs
+    )?;
+    writeln!(
+        out,
+        "{}Context switches:        voluntary={}; involuntary={}",
+        leader, ru.voluntary_switches, ru.involuntary_switches
+    )?;
+    Ok(())
+}
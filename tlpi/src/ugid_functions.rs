@@ -0,0 +1,55 @@
+/* Functions to convert user and group IDs to and from user and group
+ * names. Lookup failures are treated the same as missing entries. */
+
+use alt::users;
+
+/* Return name corresponding to 'uid', or None on error */
+
+pub fn user_name_from_id(uid: u32) -> Option<String> {
+    users::get_user_by_uid(uid).ok().flatten().map(|pw| pw.name)
+}
+
+/* Return UID corresponding to 'name', or None on error */
+
+pub fn user_id_from_name(name: &str) -> Option<u32> {
+    if name.is_empty() {
+        return None;
+    }
+
+    if let Ok(uid) = name.parse() {
+        /* As a convenience to caller */
+        return Some(uid); /* allow a numeric string */
+    }
+
+    users::get_user_by_name(name)
+        .ok()
+        .flatten()
+        .map(|pw| pw.uid)
+}
+
+/* Return name corresponding to 'gid', or None on error */
+
+pub fn group_name_from_id(gid: u32) -> Option<String> {
+    users::get_group_by_gid(gid)
+        .ok()
+        .flatten()
+        .map(|gr| gr.name)
+}
+
+/* Return GID corresponding to 'name', or None on error */
+
+pub fn group_id_from_name(name: &str) -> Option<u32> {
+    if name.is_empty() {
+        return None;
+    }
+
+    if let Ok(gid) = name.parse() {
+        /* As a convenience to caller */
+        return Some(gid); /* allow a numeric string */
+    }
+
+    users::get_group_by_name(name)
+        .ok()
+        .flatten()
+        .map(|gr| gr.gid)
+}
@@ -0,0 +1,19 @@
+use alt::fs::xattr::{self, SetFlags};
+use anyhow::{Context, Result};
+use std::env;
+use tlpi::usage_err;
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+    if argv.len() < 2 || argv[1] == "--help" {
+        usage_err!("{} file\n", argv[0]);
+    }
+
+    let value = "The past is not dead.";
+    xattr::set(&argv[1], "user.x", value.as_bytes(), SetFlags::empty()).context("setxattr")?;
+
+    let value = "In fact, it's not even past.";
+    xattr::set(&argv[1], "user.y", value.as_bytes(), SetFlags::empty()).context("setxattr")?;
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use alt::fs::xattr;
+use alt::io;
+use anyhow::{Context, Result};
+use std::env;
+use std::io::Write;
+use tlpi::usage_err;
+
+fn main() -> Result<()> {
+    let argv: Vec<String> = env::args().collect();
+
+    /* Crude option handling in place of getopt(): a leading "-x" selects
+     * hex display of attribute values */
+
+    let mut hex_display = false;
+    let mut optind = 1;
+    while let Some(opt) = argv.get(optind).filter(|arg| arg.starts_with('-')) {
+        match opt.as_str() {
+            "-x" => hex_display = true,
+            _ => usage_err!("{} [-x] file...\n", argv[0]),
+        }
+        optind += 1;
+    }
+
+    if optind >= argv.len() {
+        usage_err!("{} [-x] file...\n", argv[0]);
+    }
+
+    let mut out = io::stdout();
+    for file in &argv[optind..] {
+        let names = xattr::list(file).context("listxattr")?;
+
+        writeln!(out, "{}:", file)?;
+
+        /* Loop through all EA names, displaying name + value */
+
+        for name in names {
+            write!(out, "        name={}; ", name.to_string_lossy())?;
+
+            match xattr::get(file, &name) {
+                Ok(Some(value)) if !hex_display => {
+                    write!(out, "value={}", String::from_utf8_lossy(&value))?;
+                }
+                Ok(Some(value)) => {
+                    write!(out, "value=")?;
+                    for byte in value {
+                        write!(out, "{:02x} ", byte)?;
+                    }
+                }
+                _ => write!(out, "couldn't get value")?,
+            }
+
+            writeln!(out)?;
+        }
+
+        writeln!(out)?;
+    }
+
+    Ok(())
+}